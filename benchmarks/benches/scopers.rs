@@ -0,0 +1,141 @@
+//! Benchmarks processing a tree of files, building scopers anew for each file.
+//!
+//! That's what library users of [`process_files`] do when pipelines differ per file.
+//! Creating a scoper compiles its query: prepared queries only once per process,
+//! custom ones every time. Each prepared query is compared to a custom one of
+//! identical source, and to a single scoper shared across all files as a baseline.
+//! Run using `cargo bench --package srgn-benchmarks`.
+
+#![allow(missing_docs)] // Items generated by `criterion_group!` lack docs
+
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use srgn::actions::Upper;
+use srgn::pipeline::{process_files, Pipeline};
+use srgn::scoping::langs::python::{CustomPythonQuery, PreparedPythonQuery, Python, PythonQuery};
+use srgn::scoping::langs::rust::{CustomRustQuery, PreparedRustQuery, Rust, RustQuery};
+use srgn::scoping::Scoper;
+use tempfile::TempDir;
+
+const N_FILES: usize = 100;
+
+const PYTHON: &str = r#"
+def function(a, b):
+    """Adds `a` and `b`, which is a docstring."""
+    # A comment.
+    return a + b
+
+
+class Class:
+    """A class, with a docstring."""
+
+    def method(self):
+        """A method, with a docstring."""
+        return "a string"
+"#;
+
+const RUST: &str = r#"
+/// Adds `a` and `b`, which is a doc comment.
+fn function(a: i32, b: i32) -> i32 {
+    // A comment.
+    a + b
+}
+
+/// A struct, with a doc comment.
+struct Struct;
+
+impl Struct {
+    /// A method, with a doc comment.
+    fn method(&self) -> &str {
+        "a string"
+    }
+}
+"#;
+
+/// Generates a tree of `N_FILES` files of `contents`, spread across a few
+/// directories.
+fn tree(contents: &str, extension: &str) -> (TempDir, Vec<PathBuf>) {
+    let root = tempfile::tempdir().unwrap();
+
+    let paths = (0..N_FILES)
+        .map(|i| {
+            let dir = root.path().join(format!("dir{}", i % 10));
+            fs::create_dir_all(&dir).unwrap();
+
+            let path = dir.join(format!("file{i}.{extension}"));
+            fs::write(&path, contents.repeat(10)).unwrap();
+            path
+        })
+        .collect();
+
+    (root, paths)
+}
+
+fn pipeline(scoper: impl Scoper + 'static) -> Pipeline {
+    let mut pipeline = Pipeline::new();
+    pipeline.scoper(scoper).action(Upper::default());
+    pipeline
+}
+
+fn bench_scopers_per_file(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scopers per file");
+    // Compiling custom queries for every file takes seconds per iteration.
+    group.sample_size(10);
+
+    let (_root, paths) = tree(RUST, "rs");
+    let source = PreparedRustQuery::Comments.as_ref().to_owned();
+    let rust = || Rust::new(RustQuery::Prepared(PreparedRustQuery::Comments));
+    group.bench_function("rust comments (shared)", |b| {
+        let pipelines = [pipeline(rust())];
+        b.iter(|| process_files(&pipelines, black_box(&paths)));
+    });
+    group.bench_function("rust comments (prepared)", |b| {
+        b.iter(|| {
+            for path in black_box(&paths) {
+                black_box(process_files(&[pipeline(rust())], &[path]));
+            }
+        });
+    });
+    group.bench_function("rust comments (custom)", |b| {
+        b.iter(|| {
+            for path in black_box(&paths) {
+                let query = CustomRustQuery::from_str(&source).unwrap();
+                let pipelines = [pipeline(Rust::new(RustQuery::Custom(query)))];
+                black_box(process_files(&pipelines, &[path]));
+            }
+        });
+    });
+
+    let (_root, paths) = tree(PYTHON, "py");
+    let source = PreparedPythonQuery::DocStrings.as_ref().to_owned();
+    let python = || Python::new(PythonQuery::Prepared(PreparedPythonQuery::DocStrings));
+    group.bench_function("python docstrings (shared)", |b| {
+        let pipelines = [pipeline(python())];
+        b.iter(|| process_files(&pipelines, black_box(&paths)));
+    });
+    group.bench_function("python docstrings (prepared)", |b| {
+        b.iter(|| {
+            for path in black_box(&paths) {
+                black_box(process_files(&[pipeline(python())], &[path]));
+            }
+        });
+    });
+    group.bench_function("python docstrings (custom)", |b| {
+        b.iter(|| {
+            for path in black_box(&paths) {
+                let query = CustomPythonQuery::from_str(&source).unwrap();
+                let pipelines = [pipeline(Python::new(PythonQuery::Custom(query)))];
+                black_box(process_files(&pipelines, &[path]));
+            }
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_scopers_per_file);
+criterion_main!(benches);
@@ -5,6 +5,18 @@ fn main() {
     natural_languages::generate_word_lists();
 
     hcl::build();
+    vendored::build("c", "0.23.4", false);
+    vendored::build("cpp", "0.23.4", true);
+    vendored::build("css", "0.23.2", true);
+    vendored::build("dockerfile", "0.2.0", true);
+    vendored::build("html", "0.23.2", true);
+    vendored::build("java", "0.23.5", false);
+    vendored::build("json", "0.24.8", false);
+    vendored::build_split("markdown", "0.3.2", &["markdown", "markdown-inline"]);
+    vendored::build("perl", "1.1.2", true);
+    vendored::build("ruby", "0.23.1", true);
+    vendored::build("sql", "0.3.8", true);
+    vendored::build("toml", "0.7.0", true);
 }
 
 mod hcl {
@@ -36,6 +48,51 @@ mod hcl {
     }
 }
 
+mod vendored {
+    /// Builds a grammar vendored from its published crate, as found in
+    /// `src/scoping/langs/tree_sitter_<name>/upstream-<version>`. This is a slimmed
+    /// down version of the crate's `bindings/rust/build.rs`.
+    ///
+    /// **Remove call sites once a release of `tree-sitter-<name>` compatible with our
+    /// `tree-sitter` version is available on <https://crates.io>**.
+    pub fn build(name: &str, version: &str, has_scanner: bool) {
+        let src_dir = format!("src/scoping/langs/tree_sitter_{name}/upstream-{version}/src");
+
+        compile(&format!("tree-sitter-{name}"), &src_dir, has_scanner);
+    }
+
+    /// Builds a crate vendoring *several* grammars, like `tree-sitter-md`, each found
+    /// in `upstream-<version>/tree-sitter-<grammar>` and coming with a scanner.
+    pub fn build_split(name: &str, version: &str, grammars: &[&str]) {
+        for grammar in grammars {
+            let src_dir = format!(
+                "src/scoping/langs/tree_sitter_{name}/upstream-{version}/tree-sitter-{grammar}/src"
+            );
+
+            compile(&format!("tree-sitter-{grammar}"), &src_dir, true);
+        }
+    }
+
+    fn compile(library: &str, src_dir: &str, has_scanner: bool) {
+        let src_dir = std::path::Path::new(src_dir);
+
+        let mut c_config = cc::Build::new();
+        c_config.std("c11").include(src_dir);
+        let parser_path = src_dir.join("parser.c");
+        c_config.file(&parser_path);
+        println!("cargo:rerun-if-changed={}", parser_path.to_str().unwrap());
+
+        if has_scanner {
+            let scanner_path = src_dir.join("scanner.c");
+            c_config.file(&scanner_path);
+            println!("cargo:rerun-if-changed={}", scanner_path.to_str().unwrap());
+        }
+
+        c_config.warnings(false);
+        c_config.compile(library);
+    }
+}
+
 #[cfg(feature = "german")]
 #[allow(unreachable_pub)] // Cannot get this to play nice with clippy
 mod natural_languages {
@@ -0,0 +1,146 @@
+use base64::alphabet::{self, Alphabet};
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use log::{info, warn};
+
+use super::Action;
+
+/// Encodes the bytes of input as [base64](https://en.wikipedia.org/wiki/Base64).
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, Base64Encode};
+///
+/// assert_eq!(Base64Encode::default().act("Hello?>"), "SGVsbG8/Pg==");
+/// assert_eq!(Base64Encode::new(true).act("Hello?>"), "SGVsbG8_Pg==");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Base64Encode {
+    url_safe: bool,
+}
+
+impl Base64Encode {
+    /// Creates a new encoder, using the URL-safe alphabet (`-` and `_` instead of `+`
+    /// and `/`) if `url_safe`.
+    #[must_use]
+    pub const fn new(url_safe: bool) -> Self {
+        Self { url_safe }
+    }
+}
+
+impl Action for Base64Encode {
+    fn act(&self, input: &str) -> String {
+        info!("Encoding as base64: '{}'", input);
+        engine(self.url_safe).encode(input)
+    }
+}
+
+/// Decodes [base64](https://en.wikipedia.org/wiki/Base64) input.
+///
+/// Padding (`=`) is optional. Input which is not valid base64, or does not decode to
+/// valid UTF-8, is returned unchanged.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, Base64Decode};
+///
+/// assert_eq!(Base64Decode::default().act("SGVsbG8/Pg=="), "Hello?>");
+/// assert_eq!(Base64Decode::new(true).act("SGVsbG8_Pg"), "Hello?>");
+///
+/// // Invalid input is left alone
+/// assert_eq!(Base64Decode::default().act("Hello!"), "Hello!");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Base64Decode {
+    url_safe: bool,
+}
+
+impl Base64Decode {
+    /// Creates a new decoder, using the URL-safe alphabet (`-` and `_` instead of `+`
+    /// and `/`) if `url_safe`.
+    #[must_use]
+    pub const fn new(url_safe: bool) -> Self {
+        Self { url_safe }
+    }
+}
+
+impl Action for Base64Decode {
+    fn act(&self, input: &str) -> String {
+        info!("Decoding from base64: '{}'", input);
+
+        let decoded = match engine(self.url_safe).decode(input) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                warn!("Not valid base64, leaving unchanged: '{input}' ({e})");
+                return input.to_owned();
+            }
+        };
+
+        String::from_utf8(decoded).unwrap_or_else(|e| {
+            warn!("Decoded base64 is not valid UTF-8, leaving unchanged: '{input}' ({e})");
+            input.to_owned()
+        })
+    }
+}
+
+const fn engine(url_safe: bool) -> GeneralPurpose {
+    let alphabet: &Alphabet = if url_safe {
+        &alphabet::URL_SAFE
+    } else {
+        &alphabet::STANDARD
+    };
+
+    GeneralPurpose::new(
+        alphabet,
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", false, "")]
+    #[case("a", false, "YQ==")]
+    #[case("ab", false, "YWI=")]
+    #[case("abc", false, "YWJj")]
+    #[case("äöü", false, "w6TDtsO8")]
+    #[case("???>>>", false, "Pz8/Pj4+")]
+    #[case("???>>>", true, "Pz8_Pj4-")]
+    fn test_base64_encode(#[case] input: &str, #[case] url_safe: bool, #[case] expected: &str) {
+        assert_eq!(Base64Encode::new(url_safe).act(input), expected);
+    }
+
+    #[rstest]
+    #[case("", false, "")]
+    #[case("YQ==", false, "a")]
+    #[case("YQ", false, "a")]
+    #[case("w6TDtsO8", false, "äöü")]
+    #[case("Pz8/Pj4+", false, "???>>>")]
+    #[case("Pz8_Pj4-", true, "???>>>")]
+    // Invalid base64 is left alone
+    #[case("Pz8_Pj4-", false, "Pz8_Pj4-")]
+    #[case("Pz8/Pj4+", true, "Pz8/Pj4+")]
+    #[case("not base64!", false, "not base64!")]
+    // Valid base64, but not UTF-8 (0xFF 0xFE)
+    #[case("//4=", false, "//4=")]
+    fn test_base64_decode(#[case] input: &str, #[case] url_safe: bool, #[case] expected: &str) {
+        assert_eq!(Base64Decode::new(url_safe).act(input), expected);
+    }
+
+    #[rstest]
+    #[case("Hello, World!", false)]
+    #[case("Grüße 你好 🎉", false)]
+    #[case("Grüße 你好 🎉", true)]
+    fn test_base64_roundtrip(#[case] input: &str, #[case] url_safe: bool) {
+        let encoded = Base64Encode::new(url_safe).act(input);
+
+        assert_eq!(Base64Decode::new(url_safe).act(&encoded), input);
+    }
+}
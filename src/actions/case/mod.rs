@@ -0,0 +1,204 @@
+use clap::ValueEnum;
+
+use super::Action;
+
+/// A naming convention for identifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CaseStyle {
+    /// `snake_case`
+    Snake,
+    /// `kebab-case`
+    Kebab,
+    /// `camelCase`
+    Camel,
+    /// `PascalCase`
+    Pascal,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnake,
+}
+
+/// Converts identifiers to the given [`CaseStyle`].
+///
+/// Every run of alphanumerics, underscores and hyphens in input is taken to be an
+/// identifier; anything else (like whitespace or `::`) is kept as-is. Leading and
+/// trailing underscores and hyphens of an identifier are kept as well, so `__init__`
+/// stays intact.
+///
+/// Identifiers are split into words at underscores, hyphens and case boundaries. A
+/// lowercase letter or digit followed by an uppercase letter starts a new word
+/// (`fooBar` is `foo` and `Bar`). A run of uppercase letters is an acronym and forms
+/// a single word, except for its last letter if that is followed by a lowercase
+/// letter, which starts the next word (`HTTPServer` is `HTTP` and `Server`). Digits
+/// stick to the preceding word (`utf8Decode` is `utf8` and `Decode`).
+///
+/// Words are then re-joined in the target style. This is lossy for acronyms:
+/// `HTTPServer` converts to `HttpServer` in [`CaseStyle::Pascal`].
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, CaseConvert, CaseStyle};
+///
+/// let action = CaseConvert::new(CaseStyle::Snake);
+/// assert_eq!(action.act("HTTPServer"), "http_server");
+/// assert_eq!(action.act("getUserID, parse-args"), "get_user_id, parse_args");
+///
+/// let action = CaseConvert::new(CaseStyle::Camel);
+/// assert_eq!(action.act("max_retry_count"), "maxRetryCount");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaseConvert {
+    style: CaseStyle,
+}
+
+impl CaseConvert {
+    /// Creates a new instance converting identifiers to `style`.
+    #[must_use]
+    pub const fn new(style: CaseStyle) -> Self {
+        Self { style }
+    }
+
+    fn convert(self, identifier: &str) -> String {
+        let core = identifier.trim_matches(is_separator);
+        let start = identifier.len() - identifier.trim_start_matches(is_separator).len();
+        let (prefix, suffix) = (&identifier[..start], &identifier[start + core.len()..]);
+
+        let words = split_words(core);
+        let delimiter = match self.style {
+            CaseStyle::Snake | CaseStyle::ScreamingSnake => "_",
+            CaseStyle::Kebab => "-",
+            CaseStyle::Camel | CaseStyle::Pascal => "",
+        };
+
+        let converted = words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| match self.style {
+                CaseStyle::Snake | CaseStyle::Kebab => word.to_lowercase(),
+                CaseStyle::ScreamingSnake => word.to_uppercase(),
+                CaseStyle::Camel if i == 0 => word.to_lowercase(),
+                CaseStyle::Camel | CaseStyle::Pascal => capitalize(word),
+            })
+            .collect::<Vec<_>>()
+            .join(delimiter);
+
+        format!("{prefix}{converted}{suffix}")
+    }
+}
+
+impl Action for CaseConvert {
+    fn act(&self, input: &str) -> String {
+        let mut out = String::with_capacity(input.len());
+        let mut identifier_start = None;
+
+        for (i, c) in input.char_indices() {
+            match (identifier_start, is_identifier(c)) {
+                (None, true) => identifier_start = Some(i),
+                (None, false) => out.push(c),
+                (Some(_), true) => {}
+                (Some(start), false) => {
+                    out.push_str(&self.convert(&input[start..i]));
+                    out.push(c);
+                    identifier_start = None;
+                }
+            }
+        }
+
+        if let Some(start) = identifier_start {
+            out.push_str(&self.convert(&input[start..]));
+        }
+
+        out
+    }
+}
+
+const fn is_separator(c: char) -> bool {
+    matches!(c, '_' | '-')
+}
+
+fn is_identifier(c: char) -> bool {
+    c.is_alphanumeric() || is_separator(c)
+}
+
+/// Splits an identifier into its words, see [`CaseConvert`] for the rules.
+fn split_words(identifier: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+
+    for part in identifier.split(is_separator).filter(|p| !p.is_empty()) {
+        let chars: Vec<(usize, char)> = part.char_indices().collect();
+        let mut start = 0;
+
+        for (j, &(i, c)) in chars.iter().enumerate().skip(1) {
+            let prev = chars[j - 1].1;
+            let next = chars.get(j + 1).map(|&(_, n)| n);
+
+            let boundary = c.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_numeric()
+                    || (prev.is_uppercase() && next.is_some_and(char::is_lowercase)));
+
+            if boundary {
+                words.push(&part[start..i]);
+                start = i;
+            }
+        }
+
+        words.push(&part[start..]);
+    }
+
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+
+    chars.next().map_or_else(String::new, |first| {
+        first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("", vec![])]
+    #[case::single("word", vec!["word"])]
+    #[case::snake("max_retry_count", vec!["max", "retry", "count"])]
+    #[case::kebab("parse-args", vec!["parse", "args"])]
+    #[case::camel("getUserName", vec!["get", "User", "Name"])]
+    #[case::pascal("UserName", vec!["User", "Name"])]
+    #[case::screaming("MAX_SIZE", vec!["MAX", "SIZE"])]
+    #[case::acronym_leading("HTTPServer", vec!["HTTP", "Server"])]
+    #[case::acronym_trailing("userID", vec!["user", "ID"])]
+    #[case::acronym_inner("parseHTTPRequest", vec!["parse", "HTTP", "Request"])]
+    #[case::digits("utf8Decode", vec!["utf8", "Decode"])]
+    #[case::digits_after_acronym("SHA256Hash", vec!["SHA256", "Hash"])]
+    #[case::repeated_separators("a__b--c", vec!["a", "b", "c"])]
+    #[case::unicode("straßeÜber", vec!["straße", "Über"])]
+    fn test_split_words(#[case] input: &str, #[case] expected: Vec<&str>) {
+        assert_eq!(split_words(input), expected);
+    }
+
+    #[rstest]
+    #[case::snake(CaseStyle::Snake, "getHTTPResponse", "get_http_response")]
+    #[case::kebab(CaseStyle::Kebab, "getHTTPResponse", "get-http-response")]
+    #[case::camel(CaseStyle::Camel, "getHTTPResponse", "getHttpResponse")]
+    #[case::pascal(CaseStyle::Pascal, "getHTTPResponse", "GetHttpResponse")]
+    #[case::screaming_snake(CaseStyle::ScreamingSnake, "getHTTPResponse", "GET_HTTP_RESPONSE")]
+    #[case::camel_from_screaming(CaseStyle::Camel, "MAX_SIZE", "maxSize")]
+    #[case::pascal_from_kebab(CaseStyle::Pascal, "parse-args", "ParseArgs")]
+    #[case::affixes_kept(CaseStyle::Pascal, "__init__", "__Init__")]
+    #[case::private_kept(CaseStyle::Snake, "_privateField", "_private_field")]
+    #[case::non_identifiers_kept(CaseStyle::Snake, "use fooBar::BazQux;", "use foo_bar::baz_qux;")]
+    #[case::only_separators(CaseStyle::Camel, "__", "__")]
+    #[case::idempotent(CaseStyle::Snake, "already_snake", "already_snake")]
+    fn test_case_convert(#[case] style: CaseStyle, #[case] input: &str, #[case] expected: &str) {
+        assert_eq!(CaseConvert::new(style).act(input), expected);
+    }
+}
@@ -0,0 +1,308 @@
+use clap::ValueEnum;
+
+use super::Action;
+
+/// A style of comments in C-family languages (C, C++, Rust, Java, Go, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CommentStyle {
+    /// Line comments: `// ...` (doc comments: `/// ...`, `//! ...`).
+    Line,
+    /// Block comments: `/* ... */` (doc comments: `/** ... */`, `/*! ... */`).
+    Block,
+}
+
+/// Converts comments in scope to the given [`CommentStyle`].
+///
+/// Scope should cover entire comments. A run of line comments on consecutive lines is
+/// coalesced into a single block comment, whose lines are prefixed with ` * `.
+/// Conversely, a block comment is split into one line comment per line. Doc comment
+/// flavors are kept (`///` ↔ `/**`, `//!` ↔ `/*!`).
+///
+/// Input which cannot be converted safely is left alone, for example a run of line
+/// comments of mixed flavors, line comments containing `*/`, or anything not looking
+/// like a comment at all.
+///
+/// As the column the comment starts at is unknown, continuation lines are indented
+/// like the continuation lines found in scope. Block comments followed by anything
+/// but whitespace on their line, like in `int x = /* note */ 5;`, are left alone (see
+/// [`Action::requires_line_end`]).
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, CommentStyle, ConvertComments};
+///
+/// let action = ConvertComments::new(CommentStyle::Block);
+/// assert_eq!(action.act("// Hello\n// World"), "/*\n * Hello\n * World\n */");
+///
+/// let action = ConvertComments::new(CommentStyle::Line);
+/// assert_eq!(action.act("/* Hello */"), "// Hello");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvertComments {
+    style: CommentStyle,
+}
+
+impl ConvertComments {
+    /// Creates a new instance converting comments to `style`.
+    #[must_use]
+    pub const fn new(style: CommentStyle) -> Self {
+        Self { style }
+    }
+}
+
+/// Pairs of line and block comment markers, longest first.
+const FLAVORS: [(&str, &str); 3] = [("///", "/**"), ("//!", "/*!"), ("//", "/*")];
+
+impl Action for ConvertComments {
+    fn act(&self, input: &str) -> String {
+        // Keep surrounding whitespace, like a trailing newline, as is.
+        let trimmed = input.trim();
+        let Some(start) = input.find(trimmed) else {
+            return input.to_owned();
+        };
+        let (leading, rest) = input.split_at(start);
+        let trailing = &rest[trimmed.len()..];
+
+        let converted = match self.style {
+            CommentStyle::Block => to_block(trimmed),
+            CommentStyle::Line => to_line(trimmed),
+        };
+
+        converted.map_or_else(
+            || input.to_owned(),
+            |converted| format!("{leading}{converted}{trailing}"),
+        )
+    }
+
+    fn requires_line_end(&self) -> bool {
+        // A line comment would swallow anything following it on its line.
+        self.style == CommentStyle::Line
+    }
+}
+
+/// Converts a run of line comments into a single block comment.
+fn to_block(input: &str) -> Option<String> {
+    let lines: Vec<&str> = input.lines().collect();
+    let first = lines.first()?;
+
+    let flavor = line_flavor(first.trim_start())?;
+    let (line_marker, block_marker) = flavor;
+
+    let mut texts = Vec::with_capacity(lines.len());
+    for line in &lines {
+        let line = line.trim_start();
+        if line_flavor(line) != Some(flavor) || line.contains("*/") {
+            return None;
+        }
+
+        let text = &line[line_marker.len()..];
+        texts.push(text.strip_prefix(' ').unwrap_or(text).trim_end());
+    }
+
+    if let [text] = texts[..] {
+        return Some(if text.is_empty() {
+            format!("{block_marker} */")
+        } else {
+            format!("{block_marker} {text} */")
+        });
+    }
+
+    let indent = lines.get(1).map_or("", |line| leading_whitespace(line));
+
+    let mut out = String::from(block_marker);
+    for text in texts {
+        out.push('\n');
+        out.push_str(indent);
+        out.push_str(" *");
+        if !text.is_empty() {
+            out.push(' ');
+            out.push_str(text);
+        }
+    }
+    out.push('\n');
+    out.push_str(indent);
+    out.push_str(" */");
+
+    Some(out)
+}
+
+/// Converts a single block comment into a run of line comments.
+fn to_line(input: &str) -> Option<String> {
+    let (line_marker, block_marker) = FLAVORS.into_iter().find(|(_, block_marker)| {
+        // `/**/` is an empty, regular comment.
+        input.starts_with(block_marker) && !(*block_marker == "/**" && input.starts_with("/**/"))
+    })?;
+
+    let body = input.strip_prefix(block_marker)?.strip_suffix("*/")?;
+    if body.contains("*/") || body.contains("/*") {
+        // Not a single comment, or nested comments.
+        return None;
+    }
+
+    let lines: Vec<&str> = body.lines().collect();
+    let continuations = || lines.iter().skip(1).filter(|line| !line.trim().is_empty());
+    let is_starred = continuations().next().is_some()
+        && continuations().all(|line| line.trim_start().starts_with('*'));
+
+    let mut texts: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let line = if i == 0 { line } else { line.trim_start() };
+            let line = if i > 0 && is_starred {
+                line.strip_prefix('*').unwrap_or(line)
+            } else {
+                line
+            };
+
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .collect();
+
+    if texts.len() > 1 && texts.first().is_some_and(|t| t.is_empty()) {
+        texts.remove(0);
+    }
+    if texts.len() > 1 && texts.last().is_some_and(|t| t.is_empty()) {
+        texts.pop();
+    }
+    if texts.is_empty() {
+        texts.push("");
+    }
+
+    // Derive indentation from the last line. In starred comments, it is
+    // conventionally indented by one more space than the comment itself. Otherwise,
+    // it is either the closing one, indented like the comment, or carries content
+    // aligned with the text after the opening marker.
+    let indent = lines.last().filter(|_| lines.len() > 1).map_or("", |line| {
+        let indent = leading_whitespace(line);
+        let excess = match (is_starred, line.trim().is_empty()) {
+            (true, _) => 1,
+            (false, true) => 0,
+            (false, false) => block_marker.len() + 1,
+        };
+
+        &indent[..indent.len().saturating_sub(excess)]
+    });
+
+    Some(
+        texts
+            .iter()
+            .map(|text| {
+                if text.is_empty() {
+                    line_marker.to_owned()
+                } else {
+                    format!("{line_marker} {text}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(&format!("\n{indent}")),
+    )
+}
+
+/// The line and block markers of the flavor of the given line comment.
+fn line_flavor(line: &str) -> Option<(&'static str, &'static str)> {
+    if line.starts_with("////") {
+        // Rather a separator than a doc comment, so leave it alone.
+        return None;
+    }
+
+    FLAVORS
+        .into_iter()
+        .find(|(line_marker, _)| line.starts_with(line_marker))
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::scoping::regex::Regex;
+    use crate::scoping::view::ScopedViewBuilder;
+    use crate::RegexPattern;
+
+    #[rstest]
+    // Rust
+    #[case::rust_single("// Hello", "/* Hello */")]
+    #[case::rust_empty("//", "/* */")]
+    #[case::rust_run(
+        "// Hello\n    // World\n    //\n    // Bye",
+        "/*\n     * Hello\n     * World\n     *\n     * Bye\n     */"
+    )]
+    #[case::rust_outer_doc("/// Docs\n/// more", "/**\n * Docs\n * more\n */")]
+    #[case::rust_inner_doc("//! Crate docs", "/*! Crate docs */")]
+    #[case::rust_keeps_surrounding_whitespace("// Hello\n", "/* Hello */\n")]
+    #[case::rust_mixed_flavors_untouched("/// Docs\n// Regular", "/// Docs\n// Regular")]
+    #[case::rust_quad_slash_untouched("//// Separator", "//// Separator")]
+    #[case::rust_closing_marker_untouched("// a */ b", "// a */ b")]
+    #[case::rust_not_a_comment("let x = 1;", "let x = 1;")]
+    #[case::rust_code_in_between_untouched("// a\nlet x = 1;\n// b", "// a\nlet x = 1;\n// b")]
+    // C
+    #[case::c_single("// TODO: fix", "/* TODO: fix */")]
+    #[case::c_run("// line one\n// line two", "/*\n * line one\n * line two\n */")]
+    #[case::c_trailing_whitespace_dropped("// a   \n// b", "/*\n * a\n * b\n */")]
+    #[case::c_already_block("/* done */", "/* done */")]
+    fn test_to_block(#[case] input: &str, #[case] expected: &str) {
+        let action = ConvertComments::new(CommentStyle::Block);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    // Rust
+    #[case::rust_single("/* Hello */", "// Hello")]
+    #[case::rust_empty("/**/", "//")]
+    #[case::rust_starred(
+        "/*\n     * Hello\n     * World\n     *\n     * Bye\n     */",
+        "// Hello\n    // World\n    //\n    // Bye"
+    )]
+    #[case::rust_outer_doc("/**\n * Docs\n * more\n */", "/// Docs\n/// more")]
+    #[case::rust_inner_doc("/*! Crate docs */", "//! Crate docs")]
+    #[case::rust_nested_untouched("/* a /* b */ c */", "/* a /* b */ c */")]
+    // C
+    #[case::c_single("/* TODO: fix */", "// TODO: fix")]
+    #[case::c_unstarred("/* line one\n   line two */", "// line one\n// line two")]
+    #[case::c_unstarred_indented(
+        "/*\n        line one\n        line two\n    */",
+        "// line one\n    // line two"
+    )]
+    #[case::c_text_on_opening_line("/* one\n * two\n */", "// one\n// two")]
+    #[case::c_two_comments_untouched("/* a */ /* b */", "/* a */ /* b */")]
+    #[case::c_already_line("// done", "// done")]
+    #[case::c_keeps_surrounding_whitespace("  /* x */\n", "  // x\n")]
+    fn test_to_line(#[case] input: &str, #[case] expected: &str) {
+        let action = ConvertComments::new(CommentStyle::Line);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case::inline("int x = /* note */ 5;", "int x = /* note */ 5;")]
+    #[case::trailing("int x = 5; /* note */", "int x = 5; // note")]
+    #[case::trailing_whitespace("x; /* a */ \t\ny", "x; // a \t\ny")]
+    #[case::next_line_irrelevant("/* a */\nx; /* b */ y;", "// a\nx; /* b */ y;")]
+    fn test_to_line_in_view(#[case] input: &str, #[case] expected: &str) {
+        let mut builder = ScopedViewBuilder::new(input);
+        builder.explode(&Regex::new(RegexPattern::new(r"/\*.*?\*/").unwrap()));
+        let mut view = builder.build();
+        view.map_without_context(&ConvertComments::new(CommentStyle::Line));
+
+        assert_eq!(view.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("// Hello")]
+    #[case("// Hello\n    // World\n    //\n    // Bye")]
+    #[case("/// Docs\n/// more")]
+    #[case("//! Inner\n//! docs")]
+    fn test_roundtrip(#[case] input: &str) {
+        let block = ConvertComments::new(CommentStyle::Block).act(input);
+        let line = ConvertComments::new(CommentStyle::Line).act(&block);
+
+        assert_eq!(line, input);
+    }
+}
@@ -0,0 +1,245 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use log::{info, warn};
+
+use super::replace::variables::{scan_variables, try_render, VariableExpressionError};
+use super::{Action, ActionError};
+use crate::scoping::regex::CaptureGroup;
+use crate::scoping::scope::ScopeContext;
+
+/// Expands references to environment variables, like `$HOME` or `${HOME}`.
+///
+/// Variables are looked up in the process environment, or in a fixed set of
+/// [variables](ExpandEnv::with_variables). References follow the same syntax as
+/// variables of [`Replacement`](super::Replacement), so `$$` turns into a literal
+/// `$`. Numbered references like `$1` are not environment variables and are left
+/// as-is.
+///
+/// Undefined variables are left as-is, as is input with invalid expressions (like an
+/// unclosed `${`). In [strict](ExpandEnv::strict) mode, both are an error instead
+/// (only surfaced where the action is applied fallibly, see [`Action::try_act`]).
+/// Defaults, as in `${NAME:-default}`, apply to undefined and empty variables alike,
+/// also in strict mode. Modifiers, as in `${NAME:upper}`, transform values.
+///
+/// ## Examples
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use srgn::actions::{Action, ExpandEnv};
+///
+/// let action = ExpandEnv::with_variables(
+///     HashMap::from([("NAME".to_owned(), "World".to_owned())]),
+///     false,
+/// );
+///
+/// assert_eq!(action.act("Hello, ${NAME}! $UNKNOWN"), "Hello, World! $UNKNOWN");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExpandEnv {
+    variables: Option<HashMap<String, String>>,
+    strict: bool,
+}
+
+impl ExpandEnv {
+    /// Creates a new instance looking up variables in the process environment.
+    #[must_use]
+    pub const fn new(strict: bool) -> Self {
+        Self {
+            variables: None,
+            strict,
+        }
+    }
+
+    /// Creates a new instance looking up variables in `variables` only.
+    #[must_use]
+    pub const fn with_variables(variables: HashMap<String, String>, strict: bool) -> Self {
+        Self {
+            variables: Some(variables),
+            strict,
+        }
+    }
+
+    /// Whether undefined variables are an error.
+    #[must_use]
+    pub const fn strict(&self) -> bool {
+        self.strict
+    }
+
+    fn lookup(&self, name: &str) -> Option<Cow<'_, str>> {
+        self.variables.as_ref().map_or_else(
+            || std::env::var(name).ok().map(Cow::Owned),
+            |variables| variables.get(name).map(|v| Cow::Borrowed(v.as_str())),
+        )
+    }
+
+    /// Expands all variable references in `input`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `input` contains invalid variable expressions, or, in
+    /// [strict](Self::strict) mode, references to undefined variables.
+    pub fn expand(&self, input: &str) -> Result<String, ExpandEnvError> {
+        let positions = scan_variables(input).map_err(ExpandEnvError::InvalidExpression)?;
+
+        try_render(input, &positions, |cg, default| match cg {
+            CaptureGroup::Named(name) => match (self.lookup(name), default) {
+                (Some(value), Some(default)) if value.is_empty() => Ok(Some(default.into())),
+                (Some(value), _) => Ok(Some(value)),
+                (None, Some(default)) => Ok(Some(default.into())),
+                (None, None) if self.strict => Err(ExpandEnvError::UndefinedVariable(name.clone())),
+                (None, None) => Ok(None),
+            },
+            CaptureGroup::Numbered(_) => Ok(None),
+        })
+    }
+}
+
+impl Action for ExpandEnv {
+    fn act(&self, input: &str) -> String {
+        info!("Expanding environment variables in '{}'", input);
+
+        match self.expand(input) {
+            Ok(expanded) => expanded,
+            Err(ExpandEnvError::UndefinedVariable(_)) => {
+                // Infallible, so fall back to the lenient behavior.
+                Self {
+                    strict: false,
+                    ..self.clone()
+                }
+                .act(input)
+            }
+            Err(e) => {
+                warn!("Leaving input unchanged: {e}");
+                input.to_owned()
+            }
+        }
+    }
+
+    fn act_with_context(
+        &self,
+        input: &str,
+        context: &ScopeContext<'_>,
+    ) -> Result<String, ActionError> {
+        let _ = context; // Environment variables are independent of captures
+        self.try_act(input)
+    }
+
+    fn try_act(&self, input: &str) -> Result<String, ActionError> {
+        if self.strict {
+            Ok(self.expand(input)?)
+        } else {
+            Ok(self.act(input))
+        }
+    }
+}
+
+/// An error in expanding environment variables.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ExpandEnvError {
+    /// A variable is not defined, while in strict mode.
+    UndefinedVariable(String),
+    /// Input contains an invalid variable expression.
+    InvalidExpression(VariableExpressionError),
+}
+
+impl fmt::Display for ExpandEnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UndefinedVariable(name) => write!(f, "Undefined variable: '{name}'"),
+            Self::InvalidExpression(e) => write!(f, "Invalid variable expression: {e}"),
+        }
+    }
+}
+
+impl Error for ExpandEnvError {}
+
+impl From<ExpandEnvError> for ActionError {
+    fn from(value: ExpandEnvError) -> Self {
+        Self::ExpandEnvError(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn action(strict: bool) -> ExpandEnv {
+        ExpandEnv::with_variables(
+            HashMap::from([
+                ("HOME".to_owned(), "/home/me".to_owned()),
+                ("USER".to_owned(), "me".to_owned()),
+                ("EMPTY".to_owned(), String::new()),
+            ]),
+            strict,
+        )
+    }
+
+    #[rstest]
+    #[case::no_variables("plain text", Ok("plain text"))]
+    #[case::defined("$HOME/x", Ok("/home/me/x"))]
+    #[case::braced("${USER}name", Ok("mename"))]
+    #[case::adjacent("$USER$USER", Ok("meme"))]
+    #[case::empty_value("[$EMPTY]", Ok("[]"))]
+    #[case::escaped_dollar("$$HOME", Ok("$HOME"))]
+    #[case::numbered_kept("$1 ${2}", Ok("$1 ${2}"))]
+    #[case::undefined_kept("$NOPE and ${NOPE}", Ok("$NOPE and ${NOPE}"))]
+    #[case::lone_dollar("costs 5$", Ok("costs 5$"))]
+    #[case::default_unused("${USER:-you}", Ok("me"))]
+    #[case::default_undefined("${NOPE:-/tmp}/x", Ok("/tmp/x"))]
+    #[case::default_empty("${EMPTY:-x}", Ok("x"))]
+    #[case::default_numbered_kept("${1:-x}", Ok("${1:-x}"))]
+    #[case::modifier("${USER:upper}", Ok("ME"))]
+    #[case::modifier_undefined_kept("${NOPE:upper}", Ok("${NOPE:upper}"))]
+    #[case::unknown_modifier(
+        "${USER:nope}",
+        Err(ExpandEnvError::InvalidExpression(
+            VariableExpressionError::UnknownModifier("nope".to_owned())
+        ))
+    )]
+    #[case::mismatched_braces(
+        "${HOME",
+        Err(ExpandEnvError::InvalidExpression(
+            VariableExpressionError::MismatchedBraces("HOME".to_owned())
+        ))
+    )]
+    fn test_expand_lenient(#[case] input: &str, #[case] expected: Result<&str, ExpandEnvError>) {
+        assert_eq!(action(false).expand(input), expected.map(str::to_owned));
+    }
+
+    #[rstest]
+    #[case::defined("${HOME}", Ok("/home/me"))]
+    #[case::numbered_kept("$1", Ok("$1"))]
+    #[case::undefined("$USER is $NOPE", Err(ExpandEnvError::UndefinedVariable("NOPE".to_owned())))]
+    #[case::undefined_braced("${NOPE}", Err(ExpandEnvError::UndefinedVariable("NOPE".to_owned())))]
+    #[case::undefined_with_default("${NOPE:-x}", Ok("x"))]
+    fn test_expand_strict(#[case] input: &str, #[case] expected: Result<&str, ExpandEnvError>) {
+        assert_eq!(action(true).expand(input), expected.map(str::to_owned));
+    }
+
+    #[rstest]
+    #[case::lenient(false)]
+    #[case::strict(true)]
+    fn test_act_is_lenient(#[case] strict: bool) {
+        assert_eq!(
+            action(strict).act("$USER $NOPE ${HOME"),
+            "$USER $NOPE ${HOME"
+        );
+        assert_eq!(action(strict).act("$USER $NOPE"), "me $NOPE");
+    }
+
+    #[test]
+    fn test_process_environment() {
+        let action = ExpandEnv::new(true);
+
+        // Set for every `cargo` invocation.
+        assert_eq!(
+            action.expand("${CARGO_PKG_NAME}"),
+            Ok(env!("CARGO_PKG_NAME").to_owned())
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use super::German;
+use crate::actions::{Action, Lower, Upper};
+
+/// Casing round-trips.
+///
+/// [`Upper`] maps `ß` to the capital `ẞ`, not `SS` as [`str::to_uppercase`] does, and
+/// [`Lower`] maps `ẞ` back to `ß`. So for German text, lowercasing uppercased text
+/// loses nothing, see [`German::casing_roundtrips`].
+///
+/// Text uppercased elsewhere, using `SS`, is a different story: `SS` might have been
+/// `ss` or `ß`, which only a word list can tell apart, see
+/// [`German::restore_lowercase`]. Some words are valid either way (`MASSE` from `Masse`
+/// or `Maße`), where the decision is [configurable](German::new).
+impl German {
+    /// Whether lowercasing `input` after uppercasing it, using [`Upper`] and
+    /// [`Lower`], yields the same as lowercasing it directly.
+    ///
+    /// This holds for any German text, including `ß` and `ẞ`. It does not hold for
+    /// some characters of other languages, whose uppercase form is ambiguous, like
+    /// `ŉ` (uppercase `ʼN`).
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use srgn::actions::German;
+    ///
+    /// assert!(German::casing_roundtrips("Die Straße ist süß"));
+    /// assert!(German::casing_roundtrips("GROẞ"));
+    /// assert!(!German::casing_roundtrips("ŉ"));
+    /// ```
+    #[must_use]
+    pub fn casing_roundtrips(input: &str) -> bool {
+        let lower = Lower::default();
+
+        lower.act(&Upper::default().act(input)) == lower.act(input)
+    }
+
+    /// Lowercases `input`, restoring `ß` from `SS` where German spelling calls for
+    /// it.
+    ///
+    /// Use this for uppercase text which wasn't uppercased by [`Upper`], but for
+    /// example by [`str::to_uppercase`], which turns `ß` into `SS`. As this builds on
+    /// the German action itself, alternative Umlaut spellings (`UE`) are restored as
+    /// well. Where both spellings are valid, the [configured preference](German::new)
+    /// decides.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use srgn::actions::German;
+    ///
+    /// let action = German::default();
+    /// assert_eq!(action.restore_lowercase("GRUESSE AUS DER STRASSE"), "grüße aus der straße");
+    ///
+    /// // Ambiguous: `Masse` (mass) and `Maße` (measurements) are both words.
+    /// assert_eq!(action.restore_lowercase("MASSE"), "maße");
+    /// assert_eq!(German::new(true, false).restore_lowercase("MASSE"), "masse");
+    /// ```
+    #[must_use]
+    pub fn restore_lowercase(&self, input: &str) -> String {
+        Lower::default().act(&self.act(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::ascii("Hallo Welt!")]
+    #[case::umlauts("Äpfel, Öfen und Übel")]
+    #[case::eszett("Straße")]
+    #[case::capital_eszett("STRAẞE")]
+    #[case::mixed_eszett("ßẞ")]
+    #[case::alternative_spelling("Strasse")]
+    fn test_casing_roundtrips(#[case] input: &str) {
+        assert!(German::casing_roundtrips(input));
+    }
+
+    #[rstest]
+    // Uppercase forms of these are ambiguous, see `SpecialCasing.txt`.
+    #[case::apostrophe_n("ŉ")]
+    #[case::j_with_caron("ǰ")]
+    fn test_casing_does_not_roundtrip(#[case] input: &str) {
+        assert!(!German::casing_roundtrips(input));
+    }
+
+    #[rstest]
+    #[case::std_uppercased("Straße")]
+    #[case::verb("schließen")]
+    #[case::umlaut_and_eszett("Grüße")]
+    #[case::plain_ss("Wasser")]
+    fn test_restore_lowercase_after_std_uppercase(#[case] word: &str) {
+        let action = German::default();
+
+        assert_eq!(
+            action.restore_lowercase(&word.to_uppercase()),
+            word.to_lowercase()
+        );
+    }
+
+    #[test]
+    fn test_restore_lowercase_after_upper_is_lowercase() {
+        let action = German::default();
+        let input = "Die Straße ist süß, das Wasser nass";
+
+        assert_eq!(
+            action.restore_lowercase(&Upper::default().act(input)),
+            Lower::default().act(input)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+mod casing;
 mod driver;
 mod machine;
 mod words;
@@ -0,0 +1,156 @@
+use std::fmt;
+
+use log::trace;
+
+use super::{Action, ActionError};
+use crate::scoping::scope::ScopeContext;
+use crate::RegexPattern;
+
+/// Wraps an action, [recognizing](Action::is_applied) inputs matching a pattern as
+/// already transformed.
+///
+/// Such inputs are skipped, which makes repeated runs of actions which aren't
+/// idempotent no-ops. For example, a replacement prepending a license header can be
+/// guarded by a pattern matching that header: on a second run, nothing changes.
+///
+/// To guard several actions, wrap them as a chain (`Vec<Box<dyn Action>>`): input is
+/// then checked once, before any of them ran, and skipped by all of them.
+///
+/// ## Example
+///
+/// ```rust
+/// use srgn::actions::{Replacement, SkipIfMatches};
+/// use srgn::scoping::regex::Regex;
+/// use srgn::scoping::view::ScopedViewBuilder;
+/// use srgn::RegexPattern;
+///
+/// let everything = Regex::new(RegexPattern::new(r"(?s)\A.*").unwrap());
+/// let action = SkipIfMatches::new(
+///     Replacement::try_from("// License\n$0".to_owned()).unwrap(),
+///     RegexPattern::new(r"\A// License").unwrap(),
+/// );
+///
+/// let mut output = "fn main() {}\n".to_owned();
+/// for _ in 0..2 {
+///     let mut builder = ScopedViewBuilder::new(&output);
+///     builder.explode(&everything);
+///     let mut view = builder.build();
+///     view.map_with_context(&action).unwrap();
+///     output = view.to_string();
+/// }
+///
+/// assert_eq!(output, "// License\nfn main() {}\n");
+/// ```
+pub struct SkipIfMatches<A> {
+    action: A,
+    recognizer: RegexPattern,
+}
+
+impl<A: Action> SkipIfMatches<A> {
+    /// Creates a new instance, skipping inputs matching `recognizer` for `action`.
+    #[must_use]
+    pub const fn new(action: A, recognizer: RegexPattern) -> Self {
+        Self { action, recognizer }
+    }
+}
+
+impl<A> fmt::Debug for SkipIfMatches<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Actions might be opaque trait objects.
+        f.debug_struct("SkipIfMatches")
+            .field("recognizer", &self.recognizer)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: Action> Action for SkipIfMatches<A> {
+    fn act(&self, input: &str) -> String {
+        self.action.act(input)
+    }
+
+    fn act_with_context(
+        &self,
+        input: &str,
+        context: &ScopeContext<'_>,
+    ) -> Result<String, ActionError> {
+        self.action.act_with_context(input, context)
+    }
+
+    fn try_act(&self, input: &str) -> Result<String, ActionError> {
+        self.action.try_act(input)
+    }
+
+    fn is_applied(&self, input: &str) -> bool {
+        // Runtime errors (backtracking limits) mean we cannot tell; play it safe.
+        let is_match = self.recognizer.is_match(input).unwrap_or(false);
+        trace!("Input recognized as already transformed: {is_match}");
+
+        is_match || self.action.is_applied(input)
+    }
+
+    fn requires_line_end(&self) -> bool {
+        self.action.requires_line_end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::actions::{Replacement, Upper};
+    use crate::scoping::regex::Regex;
+    use crate::scoping::view::ScopedViewBuilder;
+
+    /// Runs `action` over all `pattern` matches in `input`.
+    fn run(action: &impl Action, pattern: &str, input: &str) -> String {
+        let mut builder = ScopedViewBuilder::new(input);
+        builder.explode(&Regex::new(RegexPattern::new(pattern).unwrap()));
+        let mut view = builder.build();
+        view.map_with_context(action).unwrap();
+
+        view.to_string()
+    }
+
+    #[rstest]
+    #[case::header(r"(?s)\A.*", "// Header\n$0", r"\A// Header", "x\n", "// Header\nx\n")]
+    #[case::per_scope(r"\w+", "${0}_v2", r"_v2$", "a b_v2", "a_v2 b_v2")]
+    fn test_second_run_changes_nothing(
+        #[case] pattern: &str,
+        #[case] replacement: &str,
+        #[case] recognizer: &str,
+        #[case] input: &str,
+        #[case] expected: &str,
+    ) {
+        let action = SkipIfMatches::new(
+            Replacement::try_from(replacement.to_owned()).unwrap(),
+            RegexPattern::new(recognizer).unwrap(),
+        );
+
+        let first = run(&action, pattern, input);
+        assert_eq!(first, expected);
+
+        let second = run(&action, pattern, &first);
+        assert_eq!(second, first);
+    }
+
+    #[rstest]
+    #[case::unrecognized("ab", "XAB")]
+    #[case::recognized("Xb", "Xb")]
+    fn test_chain_guarded_as_whole(#[case] input: &str, #[case] expected: &str) {
+        let chain: Vec<Box<dyn Action>> = vec![
+            Box::new(Replacement::try_from("X$0".to_owned()).unwrap()),
+            Box::new(Upper::default()),
+        ];
+        let action = SkipIfMatches::new(chain, RegexPattern::new(r"^X").unwrap());
+
+        assert_eq!(run(&action, r"\w+", input), expected);
+    }
+
+    #[test]
+    fn test_unrecognized_is_acted_on() {
+        let action = SkipIfMatches::new(Upper::default(), RegexPattern::new(r"^x").unwrap());
+
+        assert_eq!(run(&action, r"\w+", "xa ab"), "xa AB");
+    }
+}
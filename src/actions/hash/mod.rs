@@ -0,0 +1,162 @@
+use std::fmt::Write;
+
+use clap::ValueEnum;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use super::Action;
+
+/// An algorithm to hash with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum HashAlgorithm {
+    /// SHA-256, 64 hex characters.
+    #[default]
+    Sha256,
+    /// SHA-1, 40 hex characters. Not cryptographically secure anymore, but common.
+    Sha1,
+    /// BLAKE3, 64 hex characters.
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// The digest of `input`.
+    fn digest(self, input: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha256 => Sha256::digest(input).to_vec(),
+            Self::Sha1 => Sha1::digest(input).to_vec(),
+            Self::Blake3 => blake3::hash(input).as_bytes().to_vec(),
+        }
+    }
+}
+
+/// Replaces input with the lowercase hex digest of its (UTF-8) bytes.
+///
+/// Hashing is deterministic, so the same input always yields the same output. Combined
+/// with a scope targeting emails, tokens or similar, this pseudonymizes data while
+/// keeping equal values recognizable as such. Note that low-entropy inputs (like
+/// short numbers) are easily recovered by brute force.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, Hash, HashAlgorithm};
+///
+/// let action = Hash::new(HashAlgorithm::Sha256);
+/// assert_eq!(
+///     action.act("hello"),
+///     "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+/// );
+///
+/// let action = Hash::truncated(HashAlgorithm::Sha256, 8);
+/// assert_eq!(action.act("hello"), "2cf24dba");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Hash {
+    algorithm: HashAlgorithm,
+    length: Option<usize>,
+}
+
+impl Hash {
+    /// Creates a new instance hashing using `algorithm`, yielding the full digest.
+    #[must_use]
+    pub const fn new(algorithm: HashAlgorithm) -> Self {
+        Self {
+            algorithm,
+            length: None,
+        }
+    }
+
+    /// Creates a new instance hashing using `algorithm`, yielding only the first
+    /// `length` hex characters of the digest.
+    ///
+    /// A `length` longer than the digest yields the full digest.
+    #[must_use]
+    pub const fn truncated(algorithm: HashAlgorithm, length: usize) -> Self {
+        Self {
+            algorithm,
+            length: Some(length),
+        }
+    }
+}
+
+impl Action for Hash {
+    fn act(&self, input: &str) -> String {
+        let digest = self.algorithm.digest(input.as_bytes());
+
+        let mut hex = String::with_capacity(digest.len() * 2);
+        for byte in digest {
+            write!(hex, "{byte:02x}").expect("writing to string is infallible");
+        }
+
+        if let Some(length) = self.length {
+            hex.truncate(length);
+        }
+
+        hex
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    // Known answers, see for example `printf '' | sha256sum`.
+    #[case::sha256_empty(
+        HashAlgorithm::Sha256,
+        "",
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    )]
+    #[case::sha256(
+        HashAlgorithm::Sha256,
+        "abc",
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    )]
+    #[case::sha1_empty(HashAlgorithm::Sha1, "", "da39a3ee5e6b4b0d3255bfef95601890afd80709")]
+    #[case::sha1(HashAlgorithm::Sha1, "abc", "a9993e364706816aba3e25717850c26c9cd0d89d")]
+    #[case::blake3_empty(
+        HashAlgorithm::Blake3,
+        "",
+        "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+    )]
+    #[case::blake3(
+        HashAlgorithm::Blake3,
+        "abc",
+        "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+    )]
+    #[case::unicode(
+        HashAlgorithm::Sha1,
+        "größe",
+        "93c6a3acf1b4a1beba25d8a45a3ccd719721538b"
+    )]
+    fn test_hash(#[case] algorithm: HashAlgorithm, #[case] input: &str, #[case] expected: &str) {
+        let action = Hash::new(algorithm);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case::zero(0, "")]
+    #[case::short(8, "ba7816bf")]
+    #[case::odd(3, "ba7")]
+    #[case::full(64, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")]
+    #[case::longer_than_digest(
+        100,
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    )]
+    fn test_hash_truncated(#[case] length: usize, #[case] expected: &str) {
+        let action = Hash::truncated(HashAlgorithm::Sha256, length);
+
+        assert_eq!(action.act("abc"), expected);
+    }
+
+    #[test]
+    fn test_hash_is_deterministic() {
+        let action = Hash::new(HashAlgorithm::Blake3);
+
+        assert_eq!(action.act("a@example.com"), action.act("a@example.com"));
+        assert_ne!(action.act("a@example.com"), action.act("b@example.com"));
+    }
+}
@@ -0,0 +1,185 @@
+use itertools::Itertools;
+
+use super::Action;
+
+/// Sorts import statements, removing exact duplicates.
+///
+/// Works line-wise and language-agnostic, making it suitable for Rust `use`, Python
+/// `import`, and similar statements alike. Statements spanning multiple lines are kept
+/// together, as long as they wrap using brackets (`use a::{\n b,\n c\n};`). Blank lines
+/// separate groups of imports, which are sorted individually and kept in place.
+///
+/// Best applied to a scope containing nothing but import statements, such as a
+/// language's prepared query for imports.
+///
+/// Statements are separated by newlines by default. Any other separator, such as NUL,
+/// can be set using [`SortImports::with_separator`], which makes this a general tool
+/// for de-duplicating (and sorting) records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortImports {
+    separator: char,
+}
+
+impl Default for SortImports {
+    fn default() -> Self {
+        Self { separator: '\n' }
+    }
+}
+
+impl SortImports {
+    /// Returns this instance, but sorting records separated by `separator` instead of
+    /// lines.
+    #[must_use]
+    pub const fn with_separator(self, separator: char) -> Self {
+        Self { separator }
+    }
+
+    /// Splits a group of lines into statements, keeping bracketed continuation lines
+    /// with the line starting the statement.
+    fn statements<'a>(lines: &[&'a str]) -> Vec<Vec<&'a str>> {
+        let mut statements = Vec::new();
+        let mut current = Vec::new();
+        let mut depth: isize = 0;
+
+        for line in lines {
+            current.push(*line);
+
+            for c in line.chars() {
+                match c {
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' => depth -= 1,
+                    _ => {}
+                }
+            }
+
+            if depth <= 0 {
+                statements.push(std::mem::take(&mut current));
+                depth = 0;
+            }
+        }
+
+        if !current.is_empty() {
+            // Unbalanced tail, keep as-is.
+            statements.push(current);
+        }
+
+        statements
+    }
+}
+
+/// Splits `input` into records terminated by `separator`, dropping the terminators.
+///
+/// Like [`str::lines`] for a newline `separator`, including its handling of `\r\n`
+/// and of a trailing terminator.
+fn records(input: &str, separator: char) -> Vec<&str> {
+    if separator == '\n' {
+        return input.lines().collect();
+    }
+
+    input.split_terminator(separator).collect()
+}
+
+/// The terminator to join records separated by `separator` with: `\r\n` for newlines
+/// if present anywhere in `input`.
+fn terminator(input: &str, separator: char) -> String {
+    if separator == '\n' && input.contains("\r\n") {
+        "\r\n".to_owned()
+    } else {
+        separator.to_string()
+    }
+}
+
+impl Action for SortImports {
+    fn act(&self, input: &str) -> String {
+        let line_ending = terminator(input, self.separator);
+        let trailing_line_ending = input.ends_with(self.separator);
+
+        let mut out = Vec::new();
+
+        for (is_blank, group) in &records(input, self.separator)
+            .into_iter()
+            .chunk_by(|line| line.trim().is_empty())
+        {
+            let group = group.collect_vec();
+
+            if is_blank {
+                out.extend(group);
+                continue;
+            }
+
+            let statements = Self::statements(&group)
+                .into_iter()
+                .map(|statement| statement.into_iter().map(str::trim_end).collect_vec())
+                .sorted()
+                .dedup();
+
+            out.extend(statements.flatten());
+        }
+
+        let mut res = out.join(&line_ending);
+        if trailing_line_ending {
+            res.push_str(&line_ending);
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("\n", "\n")]
+    #[case("use a;", "use a;")]
+    #[case("use a;\n", "use a;\n")]
+    //
+    #[case(
+        "use std::io;\nuse std::fmt;\nuse std::io;\n",
+        "use std::fmt;\nuse std::io;\n"
+    )]
+    #[case(
+        // Duplicates left over from a merge conflict
+        "use crate::b;\nuse crate::a;\nuse crate::b;\nuse crate::a;\nuse crate::c;",
+        "use crate::a;\nuse crate::b;\nuse crate::c;"
+    )]
+    #[case(
+        // Trailing whitespace doesn't prevent de-duplication
+        "use a;  \nuse a;\n",
+        "use a;\n"
+    )]
+    #[case(
+        // Groups are sorted individually
+        "use std::io;\nuse std::fmt;\n\nuse itertools::Itertools;\nuse clap::Parser;\n\nuse crate::b;\nuse crate::b;\n",
+        "use std::fmt;\nuse std::io;\n\nuse clap::Parser;\nuse itertools::Itertools;\n\nuse crate::b;\n"
+    )]
+    #[case(
+        // Multi-line statements stick together
+        "use std::io;\nuse crate::{\n    a,\n    b,\n};\nuse std::io;\nuse anyhow::Result;\n",
+        "use anyhow::Result;\nuse crate::{\n    a,\n    b,\n};\nuse std::io;\n"
+    )]
+    #[case("    use b;\n    use a;\n    use b;\n", "    use a;\n    use b;\n")]
+    #[case("use b;\r\nuse a;\r\nuse b;\r\n", "use a;\r\nuse b;\r\n")]
+    #[case(
+        "import sys\nimport os\nfrom typing import (\n    Any,\n)\nimport os\n",
+        "from typing import (\n    Any,\n)\nimport os\nimport sys\n"
+    )]
+    fn test_sort_imports(#[case] input: &str, #[case] expected: &str) {
+        let result = SortImports::default().act(input);
+
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case("b\0a\0b\0a\0", "a\0b\0")]
+    #[case("b\0a\0b", "a\0b")]
+    #[case("x\ny\0x\ny\0a\0", "a\0x\ny\0")]
+    fn test_sort_imports_separator(#[case] input: &str, #[case] expected: &str) {
+        let result = SortImports::default().with_separator('\0').act(input);
+
+        assert_eq!(result, expected);
+    }
+}
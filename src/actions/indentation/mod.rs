@@ -0,0 +1,312 @@
+use clap::ValueEnum;
+
+use super::Action;
+
+/// A unit of indentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum IndentUnit {
+    /// Indent using spaces only.
+    #[default]
+    Spaces,
+    /// Indent using tabs, plus spaces for any remainder not filling a whole tab.
+    Tabs,
+}
+
+/// Fixes lines whose indentation mixes tabs and spaces, converting it to a single
+/// [`IndentUnit`].
+///
+/// The indentation's width is kept: tabs advance to the next tab stop, every `width`
+/// columns. Lines indented consistently, using only tabs or only spaces, are left
+/// alone, as is whitespace past the indentation. Scope should cover entire lines.
+///
+/// See [`MixedIndentationReport`](crate::report::MixedIndentationReport) for only
+/// reporting such lines.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, FixMixedIndentation, IndentUnit};
+///
+/// let action = FixMixedIndentation::new(IndentUnit::Spaces, 4);
+/// assert_eq!(action.act("\t  x\n\ty\n"), "      x\n\ty\n");
+///
+/// let action = FixMixedIndentation::new(IndentUnit::Tabs, 4);
+/// assert_eq!(action.act("  \t  x\n"), "\t  x\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixMixedIndentation {
+    unit: IndentUnit,
+    width: usize,
+}
+
+impl FixMixedIndentation {
+    /// Creates a new instance converting mixed indentation to `unit`, with tab stops
+    /// every `width` columns.
+    ///
+    /// A `width` of zero is treated as one.
+    #[must_use]
+    pub const fn new(unit: IndentUnit, width: usize) -> Self {
+        Self {
+            unit,
+            width: if width == 0 { 1 } else { width },
+        }
+    }
+
+    /// The indentation `indentation` converts to.
+    fn convert(self, indentation: &str) -> String {
+        let columns = indentation.chars().fold(0, |column, c| match c {
+            '\t' => column + self.width - column % self.width,
+            _ => column + 1,
+        });
+
+        match self.unit {
+            IndentUnit::Spaces => " ".repeat(columns),
+            IndentUnit::Tabs => {
+                "\t".repeat(columns / self.width) + &" ".repeat(columns % self.width)
+            }
+        }
+    }
+}
+
+impl Action for FixMixedIndentation {
+    fn act(&self, input: &str) -> String {
+        let mut out = String::with_capacity(input.len());
+
+        for line in input.split_inclusive('\n') {
+            let end = line.find(|c| c != ' ' && c != '\t').unwrap_or(line.len());
+            let (indentation, rest) = line.split_at(end);
+
+            if indentation.contains(' ') && indentation.contains('\t') {
+                out.push_str(&self.convert(indentation));
+            } else {
+                out.push_str(indentation);
+            }
+
+            out.push_str(rest);
+        }
+
+        out
+    }
+}
+
+/// Indents or dedents each line by a number of [`IndentUnit`]s.
+///
+/// A positive `amount` prepends that many units to each line, leaving lines
+/// consisting of only whitespace alone. A negative `amount` removes up to that many
+/// units of leading whitespace, never anything else: lines indented less are
+/// dedented as far as possible.
+///
+/// Indentation is measured in columns, with tabs advancing to the next tab stop every
+/// `width` columns, so mixed tab and space indentation dedents as expected. A tab
+/// only partially removed is replaced by spaces for its remaining width. Scope should
+/// cover entire lines.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, Indent, IndentUnit};
+///
+/// let action = Indent::new(4, IndentUnit::Spaces, 4);
+/// assert_eq!(action.act("x\n\ny\n"), "    x\n\n    y\n");
+///
+/// let action = Indent::new(-1, IndentUnit::Tabs, 4);
+/// assert_eq!(action.act("\t\tx\n  \ty\n"), "\tx\ny\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Indent {
+    amount: isize,
+    unit: IndentUnit,
+    width: usize,
+}
+
+impl Indent {
+    /// Creates a new instance indenting by `amount` of `unit`, dedenting if negative,
+    /// with tab stops every `width` columns.
+    ///
+    /// A `width` of zero is treated as one.
+    #[must_use]
+    pub const fn new(amount: isize, unit: IndentUnit, width: usize) -> Self {
+        Self {
+            amount,
+            unit,
+            width: if width == 0 { 1 } else { width },
+        }
+    }
+
+    /// Removes `columns` of indentation from `line`, as far as possible.
+    fn dedent(self, line: &str, columns: usize) -> String {
+        let mut removed = 0;
+
+        for (i, c) in line.char_indices() {
+            if removed >= columns {
+                return line[i..].to_owned();
+            }
+
+            removed += match c {
+                ' ' => 1,
+                '\t' => self.width - removed % self.width,
+                _ => return line[i..].to_owned(),
+            };
+
+            if removed > columns {
+                // Tab stop overshot; keep what's left of it as spaces.
+                return " ".repeat(removed - columns) + &line[i + c.len_utf8()..];
+            }
+        }
+
+        String::new()
+    }
+}
+
+impl Action for Indent {
+    fn act(&self, input: &str) -> String {
+        let mut out = String::with_capacity(input.len());
+
+        let units = self.amount.unsigned_abs();
+        let (indentation, columns) = match self.unit {
+            IndentUnit::Spaces => (" ".repeat(units), units),
+            IndentUnit::Tabs => ("\t".repeat(units), units * self.width),
+        };
+
+        for line in input.split_inclusive('\n') {
+            if self.amount < 0 {
+                let (content, line_ending) = split_line_ending(line);
+                out.push_str(&self.dedent(content, columns));
+                out.push_str(line_ending);
+            } else {
+                if !line.trim().is_empty() {
+                    out.push_str(&indentation);
+                }
+                out.push_str(line);
+            }
+        }
+
+        out
+    }
+}
+
+/// Splits `line` into its content and line ending, if any.
+fn split_line_ending(line: &str) -> (&str, &str) {
+    let content = line
+        .strip_suffix("\r\n")
+        .or_else(|| line.strip_suffix('\n'))
+        .unwrap_or(line);
+
+    line.split_at(content.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("", "")]
+    #[case::spaces_untouched("    x\n", "    x\n")]
+    #[case::tabs_untouched("\t\tx\n", "\t\tx\n")]
+    #[case::tab_then_spaces("\t  x\n", "      x\n")]
+    #[case::spaces_then_tab("  \tx\n", "    x\n")]
+    #[case::tab_stop_reached("    \tx", "        x")]
+    #[case::not_leading_untouched("x\t y\n", "x\t y\n")]
+    #[case::only_mixed_lines("\tx\n\t y\n    z\n", "\tx\n     y\n    z\n")]
+    #[case::crlf("\t x\r\n", "     x\r\n")]
+    #[case::whitespace_only_line(" \t\n", "    \n")]
+    fn test_to_spaces(#[case] input: &str, #[case] expected: &str) {
+        let action = FixMixedIndentation::new(IndentUnit::Spaces, 4);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case::spaces_untouched("    x\n", "    x\n")]
+    #[case::tabs_untouched("\t\tx\n", "\t\tx\n")]
+    #[case::tab_then_spaces("\t    x\n", "\t\tx\n")]
+    #[case::remainder_as_spaces("\t  x\n", "\t  x\n")]
+    #[case::spaces_then_tab("  \tx\n", "\tx\n")]
+    #[case::several("\t    a\n  \t  b\n", "\t\ta\n\t  b\n")]
+    fn test_to_tabs(#[case] input: &str, #[case] expected: &str) {
+        let action = FixMixedIndentation::new(IndentUnit::Tabs, 4);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case(2, "\t x", "   x")]
+    #[case(8, "\t x", "         x")]
+    #[case(0, "\t x", "  x")]
+    fn test_width(#[case] width: usize, #[case] input: &str, #[case] expected: &str) {
+        let action = FixMixedIndentation::new(IndentUnit::Spaces, width);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case::empty(2, IndentUnit::Spaces, "", "")]
+    #[case::zero(0, IndentUnit::Spaces, "x\n", "x\n")]
+    #[case::spaces(2, IndentUnit::Spaces, "x\n  y\n", "  x\n    y\n")]
+    #[case::tabs(1, IndentUnit::Tabs, "x\n\ty", "\tx\n\t\ty")]
+    #[case::blank_lines_untouched(2, IndentUnit::Spaces, "x\n\n \ny\n", "  x\n\n \n  y\n")]
+    #[case::crlf(1, IndentUnit::Tabs, "x\r\ny\r\n", "\tx\r\n\ty\r\n")]
+    #[case::onto_mixed(1, IndentUnit::Spaces, "\t x\n", " \t x\n")]
+    fn test_indent(
+        #[case] amount: isize,
+        #[case] unit: IndentUnit,
+        #[case] input: &str,
+        #[case] expected: &str,
+    ) {
+        let action = Indent::new(amount, unit, 4);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case::spaces(-2, IndentUnit::Spaces, "    x\n  y\n", "  x\ny\n")]
+    #[case::tabs(-1, IndentUnit::Tabs, "\t\tx\n\ty\n", "\tx\ny\n")]
+    #[case::not_enough_indentation(-4, IndentUnit::Spaces, "  x\n      y\n", "x\n  y\n")]
+    #[case::non_whitespace_kept(-8, IndentUnit::Spaces, "x  y\n", "x  y\n")]
+    #[case::whitespace_only_line(-2, IndentUnit::Spaces, "    \n", "  \n")]
+    #[case::crlf(-1, IndentUnit::Tabs, "\tx\r\n\ty\r\n", "x\r\ny\r\n")]
+    // Mixed tab/space indentation
+    #[case::mixed_tab_by_spaces(-4, IndentUnit::Spaces, "\t  x\n", "  x\n")]
+    #[case::mixed_spaces_by_tab(-1, IndentUnit::Tabs, "  \t x\n", " x\n")]
+    #[case::mixed_partial_tab(-2, IndentUnit::Spaces, "\t x\n", "   x\n")]
+    #[case::mixed_tab_after_spaces(-4, IndentUnit::Spaces, "  \t\tx\n", "\tx\n")]
+    fn test_dedent(
+        #[case] amount: isize,
+        #[case] unit: IndentUnit,
+        #[case] input: &str,
+        #[case] expected: &str,
+    ) {
+        let action = Indent::new(amount, unit, 4);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case(IndentUnit::Spaces, "fn f() {\n    x\n}\n")]
+    #[case(IndentUnit::Tabs, "fn f() {\n\tx\n}\n")]
+    fn test_indent_dedent_roundtrip(#[case] unit: IndentUnit, #[case] input: &str) {
+        let indented = Indent::new(3, unit, 4).act(input);
+        let dedented = Indent::new(-3, unit, 4).act(&indented);
+
+        assert_eq!(dedented, input);
+    }
+
+    #[test]
+    fn test_fixed_is_consistent() {
+        let input = "\t  x\n  \ty\n \t \tz\n";
+
+        for unit in [IndentUnit::Spaces, IndentUnit::Tabs] {
+            let fixed = FixMixedIndentation::new(unit, 4).act(input);
+
+            for line in fixed.lines() {
+                let indentation = &line[..line.find(|c| c != ' ' && c != '\t').unwrap()];
+                match unit {
+                    IndentUnit::Spaces => assert!(!indentation.contains('\t')),
+                    IndentUnit::Tabs => assert!(!indentation.contains(" \t")),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use clap::ValueEnum;
+
+use super::Action;
+
+/// SQL keywords, in their canonical (uppercase) spelling.
+const SQL_KEYWORDS: &[&str] = &[
+    "ADD",
+    "ALL",
+    "ALTER",
+    "AND",
+    "AS",
+    "ASC",
+    "BETWEEN",
+    "BY",
+    "CASE",
+    "CREATE",
+    "CROSS",
+    "DEFAULT",
+    "DELETE",
+    "DESC",
+    "DISTINCT",
+    "DROP",
+    "ELSE",
+    "END",
+    "EXISTS",
+    "FALSE",
+    "FOREIGN",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "HAVING",
+    "IN",
+    "INDEX",
+    "INNER",
+    "INSERT",
+    "INTO",
+    "IS",
+    "JOIN",
+    "KEY",
+    "LEFT",
+    "LIKE",
+    "LIMIT",
+    "NOT",
+    "NULL",
+    "OFFSET",
+    "ON",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "PRIMARY",
+    "REFERENCES",
+    "RETURNING",
+    "RIGHT",
+    "SELECT",
+    "SET",
+    "TABLE",
+    "THEN",
+    "TRUE",
+    "UNION",
+    "UNIQUE",
+    "UPDATE",
+    "VALUES",
+    "VIEW",
+    "WHEN",
+    "WHERE",
+    "WITH",
+];
+
+/// Python keyword constants, in their canonical spelling.
+const PYTHON_KEYWORDS: &[&str] = &["False", "None", "True"];
+
+/// A language whose keywords to normalize the casing of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum KeywordLanguage {
+    /// Uppercase SQL keywords, like `SELECT` and `NULL`.
+    Sql,
+    /// Proper-case the Python constants `True`, `False` and `None`.
+    Python,
+    /// Lowercase HTML tag names, like `<div>` and `</p>`.
+    Html,
+}
+
+impl KeywordLanguage {
+    /// Whether `c` can be part of a token which might be a keyword.
+    fn is_token_char(self, c: char) -> bool {
+        match self {
+            Self::Sql | Self::Python => c.is_alphanumeric() || c == '_',
+            Self::Html => c.is_alphanumeric() || c == '-',
+        }
+    }
+
+    /// The canonical spelling of `token`, if it's a keyword. `preceding` is all input
+    /// before it.
+    fn canonical(self, token: &str, preceding: &str) -> Option<String> {
+        let lookup = |keywords: &[&str]| {
+            keywords
+                .iter()
+                .find(|keyword| keyword.eq_ignore_ascii_case(token))
+                .map(|&keyword| keyword.to_owned())
+        };
+
+        match self {
+            Self::Sql => lookup(SQL_KEYWORDS),
+            Self::Python => lookup(PYTHON_KEYWORDS),
+            Self::Html => (preceding.ends_with('<') || preceding.ends_with("</"))
+                .then(|| token.to_ascii_lowercase()),
+        }
+    }
+}
+
+/// Normalizes the casing of keywords according to the conventions of a
+/// [`KeywordLanguage`].
+///
+/// Works on identifier-like tokens, looking them up in a small per-language map of
+/// keywords: other tokens, and tokens only partially matching a keyword (`selected`),
+/// are left alone. Nothing is known about the language's syntax otherwise, so
+/// keywords inside string literals or comments are converted as well. Scope
+/// accordingly to avoid that, for example using a language's prepared queries.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, KeywordLanguage, NormalizeKeywords};
+///
+/// let action = NormalizeKeywords::new(KeywordLanguage::Sql);
+/// assert_eq!(action.act("select id from users"), "SELECT id FROM users");
+///
+/// let action = NormalizeKeywords::new(KeywordLanguage::Python);
+/// assert_eq!(action.act("x = true or none"), "x = True or None");
+///
+/// let action = NormalizeKeywords::new(KeywordLanguage::Html);
+/// assert_eq!(action.act("<DIV>Hello</DIV>"), "<div>Hello</div>");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NormalizeKeywords {
+    language: KeywordLanguage,
+}
+
+impl NormalizeKeywords {
+    /// Creates a new instance normalizing keywords of the given `language`.
+    #[must_use]
+    pub const fn new(language: KeywordLanguage) -> Self {
+        Self { language }
+    }
+}
+
+impl Action for NormalizeKeywords {
+    fn act(&self, input: &str) -> String {
+        let mut out = String::with_capacity(input.len());
+        let mut rest = input;
+
+        while let Some(start) = rest.find(|c| self.language.is_token_char(c)) {
+            let end = rest[start..]
+                .find(|c| !self.language.is_token_char(c))
+                .map_or(rest.len(), |end| start + end);
+
+            let token = &rest[start..end];
+            out.push_str(&rest[..start]);
+
+            let preceding = &input[..input.len() - rest.len() + start];
+            match self.language.canonical(token, preceding) {
+                Some(keyword) => out.push_str(&keyword),
+                None => out.push_str(token),
+            }
+
+            rest = &rest[end..];
+        }
+
+        out.push_str(rest);
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("", "")]
+    #[case::simple("select * from t", "SELECT * FROM t")]
+    #[case::mixed_case(
+        "Select a From t wHeRe a Is Not Null",
+        "SELECT a FROM t WHERE a IS NOT NULL"
+    )]
+    #[case::already_canonical("SELECT 1", "SELECT 1")]
+    #[case::identifiers_untouched(
+        "select selected, from_date from t",
+        "SELECT selected, from_date FROM t"
+    )]
+    #[case::multiline(
+        "insert into users (id, name)\nvalues (1, 'x')\nreturning id;\n",
+        "INSERT INTO users (id, name)\nVALUES (1, 'x')\nRETURNING id;\n"
+    )]
+    #[case::joins("a left join b on a.id = b.id", "a LEFT JOIN b ON a.id = b.id")]
+    #[case::punctuation("count(distinct(x))", "count(DISTINCT(x))")]
+    #[case::unicode_untouched("select größe, äin from maße", "SELECT größe, äin FROM maße")]
+    fn test_sql(#[case] input: &str, #[case] expected: &str) {
+        let action = NormalizeKeywords::new(KeywordLanguage::Sql);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case::lowercase("x = true", "x = True")]
+    #[case::uppercase("return FALSE", "return False")]
+    #[case::none("if x is none:", "if x is None:")]
+    #[case::already_canonical("(True, False, None)", "(True, False, None)")]
+    #[case::identifiers_untouched("is_true = nonempty", "is_true = nonempty")]
+    #[case::other_keywords_untouched("IF x AND y", "IF x AND y")]
+    fn test_python(#[case] input: &str, #[case] expected: &str) {
+        let action = NormalizeKeywords::new(KeywordLanguage::Python);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case::simple("<P>Text</P>", "<p>Text</p>")]
+    #[case::attributes_untouched("<A HREF=\"X\">", "<a HREF=\"X\">")]
+    #[case::custom_element("<My-Element/>", "<my-element/>")]
+    #[case::text_untouched("Some <B>Bold</B> Text", "Some <b>Bold</b> Text")]
+    #[case::digits("<H1>", "<h1>")]
+    fn test_html(#[case] input: &str, #[case] expected: &str) {
+        let action = NormalizeKeywords::new(KeywordLanguage::Html);
+
+        assert_eq!(action.act(input), expected);
+    }
+}
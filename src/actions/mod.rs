@@ -1,30 +1,83 @@
+mod base64;
+mod case;
+mod comments;
 mod deletion;
+mod env;
 #[cfg(feature = "german")]
 mod german;
+mod guard;
+#[cfg(feature = "hash")]
+mod hash;
+mod imports;
+mod indentation;
+mod keywords;
 mod lower;
 mod normalization;
+mod numbers;
+mod properties;
+mod redact;
+mod regex_escape;
+mod rename;
 /// Replacing inputs.
 pub mod replace;
+mod reverse;
+mod separators;
+mod sort;
+mod squeeze;
+mod strings;
 mod style;
+mod substitution;
 #[cfg(feature = "symbols")]
 mod symbols;
+mod table;
+mod tabs;
 mod titlecase;
+mod toml_keys;
 mod upper;
+mod url_encoding;
+mod urls;
 
 use std::error::Error;
 use std::fmt;
 
+pub use base64::{Base64Decode, Base64Encode};
+pub use case::{CaseConvert, CaseStyle};
+pub use comments::{CommentStyle, ConvertComments};
 pub use deletion::Deletion;
+pub use env::{ExpandEnv, ExpandEnvError};
 #[cfg(feature = "german")]
 pub use german::German;
+pub use guard::SkipIfMatches;
+#[cfg(feature = "hash")]
+pub use hash::{Hash, HashAlgorithm};
+pub use imports::SortImports;
+pub use indentation::{FixMixedIndentation, Indent, IndentUnit};
+pub use keywords::{KeywordLanguage, NormalizeKeywords};
 pub use lower::Lower;
 pub use normalization::Normalization;
+pub use numbers::{Alignment, NumberOp, NumberOpError, NumberOperation, PadNumbers};
+pub use properties::{ConvertProperties, PropertyStyle};
+pub use redact::Redact;
+pub use regex_escape::RegexEscape;
+pub(crate) use rename::find_collision;
+pub use rename::{Rename, RenameCollision};
 pub use replace::{Replacement, ReplacementError};
+pub use reverse::Reverse;
+pub use separators::ConvertSeparators;
+pub use sort::{SortLines, SortOrder};
+pub use squeeze::{Squeeze, SqueezeMode};
+pub use strings::{CollapseStringWhitespace, Concatenation, RewrapStrings};
 pub use style::Style;
+pub use substitution::Substitution;
 #[cfg(feature = "symbols")]
 pub use symbols::{inversion::Symbols as SymbolsInversion, Symbols};
+pub use table::MarkdownTable;
+pub use tabs::ExpandTabs;
 pub use titlecase::Titlecase;
+pub use toml_keys::SortTomlKeys;
 pub use upper::Upper;
+pub use url_encoding::{UrlDecode, UrlEncode};
+pub use urls::{UrlWrapping, WrapUrls};
 
 use crate::scoping::scope::ScopeContext;
 
@@ -57,6 +110,38 @@ pub trait Action: Send + Sync {
         let _ = context; // Mark variable as used
         Ok(self.act(input))
     }
+
+    /// Acts, but fallibly, for inputs without any context.
+    ///
+    /// By default, this is just [`Action::act`]. Implementors which can fail on bad
+    /// input (as opposed to bad context) can overwrite this method.
+    ///
+    /// # Errors
+    ///
+    /// See docs of the [`Err`] variant type.
+    fn try_act(&self, input: &str) -> Result<String, ActionError> {
+        Ok(self.act(input))
+    }
+
+    /// Whether `input` is recognized as already transformed by this action.
+    ///
+    /// Recognized inputs are left alone entirely, so repeated runs of actions which
+    /// aren't idempotent (like prepending a header) change nothing. By default, nothing
+    /// is recognized. See [`SkipIfMatches`] for a recognizer based on a pattern.
+    fn is_applied(&self, input: &str) -> bool {
+        let _ = input; // Mark variable as used
+        false
+    }
+
+    /// Whether this action only acts on inputs ending their line.
+    ///
+    /// If so, inputs followed by anything but whitespace on their line are left
+    /// alone. This is for transformations only valid at the end of a line, like
+    /// turning a block comment into a line comment, which would otherwise swallow
+    /// any code following it. By default, inputs are acted on regardless.
+    fn requires_line_end(&self) -> bool {
+        false
+    }
 }
 
 /// An error during application of an action.
@@ -64,6 +149,10 @@ pub trait Action: Send + Sync {
 pub enum ActionError {
     /// Produced if [`Replacement`] fails.
     ReplacementError(ReplacementError),
+    /// Produced if [`Rename`] would merge distinct names.
+    RenameCollision(RenameCollision),
+    /// Produced if [`ExpandEnv`] fails.
+    ExpandEnvError(ExpandEnvError),
 }
 
 impl fmt::Display for ActionError {
@@ -72,6 +161,12 @@ impl fmt::Display for ActionError {
             Self::ReplacementError(re) => {
                 write!(f, "Action failed in replacement: {re}")
             }
+            Self::RenameCollision(rc) => {
+                write!(f, "Action failed in rename: {rc}")
+            }
+            Self::ExpandEnvError(ee) => {
+                write!(f, "Action failed in expanding environment variables: {ee}")
+            }
         }
     }
 }
@@ -102,4 +197,50 @@ impl Action for Box<dyn Action> {
     ) -> Result<String, ActionError> {
         self.as_ref().act_with_context(input, context)
     }
+
+    fn try_act(&self, input: &str) -> Result<String, ActionError> {
+        self.as_ref().try_act(input)
+    }
+
+    fn is_applied(&self, input: &str) -> bool {
+        self.as_ref().is_applied(input)
+    }
+
+    fn requires_line_end(&self) -> bool {
+        self.as_ref().requires_line_end()
+    }
+}
+
+/// A chain of [`Action`]s, applied one after another to the same input.
+///
+/// Input is recognized as [applied](Action::is_applied) if any action of the chain
+/// recognizes it, in which case the entire chain is skipped.
+impl Action for Vec<Box<dyn Action>> {
+    fn act(&self, input: &str) -> String {
+        self.iter()
+            .fold(input.to_owned(), |input, action| action.act(&input))
+    }
+
+    fn act_with_context(
+        &self,
+        input: &str,
+        context: &ScopeContext<'_>,
+    ) -> Result<String, ActionError> {
+        self.iter().try_fold(input.to_owned(), |input, action| {
+            action.act_with_context(&input, context)
+        })
+    }
+
+    fn try_act(&self, input: &str) -> Result<String, ActionError> {
+        self.iter()
+            .try_fold(input.to_owned(), |input, action| action.try_act(&input))
+    }
+
+    fn is_applied(&self, input: &str) -> bool {
+        self.iter().any(|action| action.is_applied(input))
+    }
+
+    fn requires_line_end(&self) -> bool {
+        self.iter().any(Action::requires_line_end)
+    }
 }
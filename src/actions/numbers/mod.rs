@@ -0,0 +1,456 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::LazyLock;
+
+use clap::ValueEnum;
+use fancy_regex::Regex;
+
+use super::Action;
+
+/// Matches numbers: runs of digits, optionally with a fractional part, which are not
+/// part of a word (like `v2` or `0x1f`) or of a longer dotted number (like `1.2.3`). A
+/// leading sign is included unless it follows a word (like the `-` in `10-5`).
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?<![\w.])(?<sign>[-+])?(?<digits>[0-9]+(?:\.[0-9]+)?)(?!\w|\.[0-9])")
+        .expect("number regex to be valid")
+});
+
+/// Where numbers end up inside their padded width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Alignment {
+    /// Pad on the left, like `00042`.
+    #[default]
+    Right,
+    /// Pad on the right, like `42   `.
+    Left,
+}
+
+/// Pads numbers to a fixed width, for example to make IDs sort correctly or to align
+/// columns.
+///
+/// In scope, numbers are runs of ASCII digits, optionally with a fractional part
+/// (`3.14`). Digits which are part of a word (`v2`, `0x1f`) or of a version-like
+/// dotted sequence (`1.2.3`) are left alone, as are numbers already at least as wide as
+/// the requested width. Scope to number literals of a language for precision.
+///
+/// A leading sign counts towards the width. When padding with zeros on the left, the
+/// zeros go after the sign (`-0042`); other fills go before it (`  -42`).
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, Alignment, PadNumbers};
+///
+/// let action = PadNumbers::new(5, '0', Alignment::Right);
+/// assert_eq!(action.act("id 42, id 123456"), "id 00042, id 123456");
+/// assert_eq!(action.act("x = -7"), "x = -0007");
+///
+/// let action = PadNumbers::new(4, ' ', Alignment::Left);
+/// assert_eq!(action.act("7|12|"), "7   |12  |");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PadNumbers {
+    width: usize,
+    fill: char,
+    alignment: Alignment,
+}
+
+impl PadNumbers {
+    /// Creates a new instance padding numbers to `width` characters using `fill`,
+    /// aligned as indicated.
+    #[must_use]
+    pub const fn new(width: usize, fill: char, alignment: Alignment) -> Self {
+        Self {
+            width,
+            fill,
+            alignment,
+        }
+    }
+}
+
+impl Default for PadNumbers {
+    fn default() -> Self {
+        Self::new(0, '0', Alignment::default())
+    }
+}
+
+impl Action for PadNumbers {
+    fn act(&self, input: &str) -> String {
+        let mut out = String::with_capacity(input.len());
+        let mut last = 0;
+
+        for caps in NUMBER.captures_iter(input) {
+            let Ok(caps) = caps else {
+                // Backtracking limit exceeded; leave the rest untouched.
+                break;
+            };
+            let m = caps.get(0).expect("group 0 always participates");
+
+            let number = m.as_str();
+            let padding = std::iter::repeat(self.fill)
+                .take(self.width.saturating_sub(number.len()))
+                .collect::<String>();
+
+            out.push_str(&input[last..m.start()]);
+            match (self.alignment, caps.name("sign")) {
+                (Alignment::Right, Some(sign)) if self.fill == '0' => {
+                    out.push_str(sign.as_str());
+                    out.push_str(&padding);
+                    out.push_str(&caps["digits"]);
+                }
+                (Alignment::Right, _) => {
+                    out.push_str(&padding);
+                    out.push_str(number);
+                }
+                (Alignment::Left, _) => {
+                    out.push_str(number);
+                    out.push_str(&padding);
+                }
+            }
+            last = m.end();
+        }
+
+        out.push_str(&input[last..]);
+        out
+    }
+}
+
+/// An arithmetic operation for [`NumberOp`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum NumberOperation {
+    /// Add the operand, like `8080` to `8081` for an operand of `1`.
+    #[default]
+    Add,
+    /// Subtract the operand.
+    Subtract,
+    /// Multiply by the operand.
+    Multiply,
+    /// Replace by the operand.
+    Set,
+}
+
+/// A decimal number, exactly represented as `mantissa * 10^-scale`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    /// Parses an optionally signed run of ASCII digits, with an optional fractional
+    /// part, like `-12.50`.
+    fn parse(s: &str) -> Option<Self> {
+        let unsigned = s.strip_prefix(['-', '+']).unwrap_or(s);
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+        let is_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        if !is_digits(integer) || (unsigned.contains('.') && !is_digits(fraction)) {
+            return None;
+        }
+
+        let mantissa = format!("{integer}{fraction}").parse::<i128>().ok()?;
+
+        Some(Self {
+            mantissa: if s.starts_with('-') {
+                -mantissa
+            } else {
+                mantissa
+            },
+            scale: u32::try_from(fraction.len()).ok()?,
+        })
+    }
+
+    /// The mantissa at the (larger or equal) `scale`, if representable.
+    fn mantissa_at(self, scale: u32) -> Option<i128> {
+        10_i128
+            .checked_pow(scale - self.scale)?
+            .checked_mul(self.mantissa)
+    }
+
+    /// Applies `operation` with `operand` to this number, if the result is
+    /// representable.
+    fn apply(self, operation: NumberOperation, operand: Self) -> Option<Self> {
+        let scale = self.scale.max(operand.scale);
+        let (a, b) = (self.mantissa_at(scale)?, operand.mantissa_at(scale)?);
+
+        let (mantissa, scale) = match operation {
+            NumberOperation::Add => (a.checked_add(b)?, scale),
+            NumberOperation::Subtract => (a.checked_sub(b)?, scale),
+            NumberOperation::Multiply => (
+                self.mantissa.checked_mul(operand.mantissa)?,
+                self.scale.checked_add(operand.scale)?,
+            ),
+            NumberOperation::Set => (operand.mantissa, operand.scale),
+        };
+
+        Some(Self { mantissa, scale })
+    }
+
+    /// Formats canonically: no leading zeros beyond padding the integer part to
+    /// `width` digits, no trailing fractional zeros, and at least one fractional
+    /// digit only if `fractional`.
+    fn format(self, width: usize, fractional: bool) -> String {
+        let Self {
+            mut mantissa,
+            mut scale,
+        } = self;
+        while scale > 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            scale -= 1;
+        }
+
+        let scale = scale as usize;
+        let digits = format!("{:0>1$}", mantissa.unsigned_abs(), scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+
+        let sign = if mantissa < 0 { "-" } else { "" };
+        match (fraction, fractional) {
+            ("", false) => format!("{sign}{integer:0>width$}"),
+            ("", true) => format!("{sign}{integer:0>width$}.0"),
+            _ => format!("{sign}{integer:0>width$}.{fraction}"),
+        }
+    }
+}
+
+/// Performs arithmetic on numbers, like bumping ports or version components.
+///
+/// The entire input (ignoring surrounding whitespace) must be a number: an optionally
+/// signed run of ASCII digits, optionally with a fractional part. Anything else is left
+/// unchanged, as are results which aren't representable. Scope to numbers only, for
+/// example using the JSON or YAML prepared queries for values.
+///
+/// Arithmetic is exact, so `0.1 + 0.2` is `0.3`. Results are emitted in canonical
+/// form, without a `+` sign, leading zeros or trailing fractional zeros. If the input
+/// or operand has a fractional part, so does the result (`1.5 + 1.5` is `3.0`).
+/// Optionally, the width of integer parts with leading zeros is preserved instead.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, NumberOp, NumberOperation};
+///
+/// let action = NumberOp::new(NumberOperation::Add, "1", false).unwrap();
+/// assert_eq!(action.act("8080"), "8081");
+/// assert_eq!(action.act("-0.5"), "0.5");
+/// assert_eq!(action.act("007"), "8");
+/// assert_eq!(action.act("latest"), "latest");
+///
+/// let action = NumberOp::new(NumberOperation::Add, "1", true).unwrap();
+/// assert_eq!(action.act("007"), "008");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberOp {
+    operation: NumberOperation,
+    operand: Decimal,
+    preserve_width: bool,
+}
+
+impl NumberOp {
+    /// Creates a new instance applying `operation` with `operand`, optionally
+    /// preserving the width of integer parts with leading zeros.
+    ///
+    /// # Errors
+    ///
+    /// If `operand` isn't a number as understood by this action.
+    pub fn new(
+        operation: NumberOperation,
+        operand: &str,
+        preserve_width: bool,
+    ) -> Result<Self, NumberOpError> {
+        let operand =
+            Decimal::parse(operand).ok_or_else(|| NumberOpError::InvalidOperand(operand.into()))?;
+
+        Ok(Self {
+            operation,
+            operand,
+            preserve_width,
+        })
+    }
+}
+
+impl Action for NumberOp {
+    fn act(&self, input: &str) -> String {
+        let number = input.trim();
+        let Some(result) =
+            Decimal::parse(number).and_then(|n| n.apply(self.operation, self.operand))
+        else {
+            return input.to_owned();
+        };
+
+        let integer = number
+            .trim_start_matches(['-', '+'])
+            .split('.')
+            .next()
+            .unwrap_or_default();
+        let width = if self.preserve_width && integer.len() > 1 && integer.starts_with('0') {
+            integer.len()
+        } else {
+            0
+        };
+        let fractional = number.contains('.') || self.operand.scale > 0;
+
+        let start = input.len() - input.trim_start().len();
+        let end = start + number.len();
+
+        format!(
+            "{}{}{}",
+            &input[..start],
+            result.format(width, fractional),
+            &input[end..]
+        )
+    }
+}
+
+/// An error in creating a [`NumberOp`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NumberOpError {
+    /// The operand is not a number.
+    InvalidOperand(String),
+}
+
+impl fmt::Display for NumberOpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidOperand(operand) => write!(f, "Not a number: '{operand}'"),
+        }
+    }
+}
+
+impl Error for NumberOpError {}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("no numbers", "no numbers")]
+    #[case("7", "00007")]
+    #[case("42", "00042")]
+    #[case("12345", "12345")]
+    #[case("1234567", "1234567")] // Exceeding width, unchanged
+    #[case("a 1, b 22, c 333", "a 00001, b 00022, c 00333")]
+    #[case("x = 3.14;", "x = 03.14;")]
+    #[case("-5", "-0005")]
+    #[case("+5", "+0005")]
+    #[case("x = -3.5", "x = -03.5")]
+    #[case("-12345", "-12345")] // Sign counts towards width
+    #[case("10-5", "00010-00005")] // Not a sign after a word
+    #[case("item 5.", "item 00005.")]
+    #[case("Größe 5", "Größe 00005")]
+    // Not numbers on their own
+    #[case("v2 x86_64 0x1f", "v2 x86_64 0x1f")]
+    #[case("1.2.3", "1.2.3")]
+    #[case("٣", "٣")] // Non-ASCII digits
+    fn test_pad_numbers_zero(#[case] input: &str, #[case] expected: &str) {
+        let action = PadNumbers::new(5, '0', Alignment::Right);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case(' ', Alignment::Right, "1\n22\n", "   1\n  22\n")]
+    #[case(' ', Alignment::Left, "1|22|", "1   |22  |")]
+    #[case('0', Alignment::Left, "1", "1000")]
+    #[case('·', Alignment::Right, "1", "···1")]
+    #[case(' ', Alignment::Right, "-1", "  -1")]
+    #[case('0', Alignment::Left, "-1", "-100")]
+    fn test_pad_numbers_options(
+        #[case] fill: char,
+        #[case] alignment: Alignment,
+        #[case] input: &str,
+        #[case] expected: &str,
+    ) {
+        let action = PadNumbers::new(4, fill, alignment);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[test]
+    fn test_pad_numbers_default_is_noop() {
+        assert_eq!(PadNumbers::default().act("1 22"), "1 22");
+    }
+
+    #[rstest]
+    #[case::add(NumberOperation::Add, "1", "8080", "8081")]
+    #[case::subtract(NumberOperation::Subtract, "1", "10", "9")]
+    #[case::multiply(NumberOperation::Multiply, "3", "14", "42")]
+    #[case::set(NumberOperation::Set, "443", "80", "443")]
+    #[case::plus_sign(NumberOperation::Add, "+1", "+1", "2")]
+    #[case::whitespace_kept(NumberOperation::Add, "1", " 1\n", " 2\n")]
+    // Negative numbers
+    #[case::negative_input(NumberOperation::Add, "1", "-5", "-4")]
+    #[case::negative_operand(NumberOperation::Add, "-10", "3", "-7")]
+    #[case::negative_result(NumberOperation::Subtract, "5", "3", "-2")]
+    #[case::negative_multiply(NumberOperation::Multiply, "-2", "-21", "42")]
+    #[case::negative_zero(NumberOperation::Multiply, "-1", "0", "0")]
+    // Floats
+    #[case::float_exact(NumberOperation::Add, "0.2", "0.1", "0.3")]
+    #[case::float_input(NumberOperation::Add, "1", "2.5", "3.5")]
+    #[case::float_operand(NumberOperation::Multiply, "0.5", "3", "1.5")]
+    #[case::float_trailing_zeros(NumberOperation::Add, "1", "1.50", "2.5")]
+    #[case::float_stays_float(NumberOperation::Add, "1.5", "1.5", "3.0")]
+    #[case::float_negative(NumberOperation::Subtract, "0.75", "0.5", "-0.25")]
+    #[case::float_small(NumberOperation::Multiply, "0.01", "0.5", "0.005")]
+    // Canonical form
+    #[case::leading_zeros_dropped(NumberOperation::Add, "1", "007", "8")]
+    #[case::set_canonical(NumberOperation::Set, "007", "1", "7")]
+    // Not numbers
+    #[case::empty(NumberOperation::Add, "1", "", "")]
+    #[case::word(NumberOperation::Add, "1", "latest", "latest")]
+    #[case::embedded(NumberOperation::Add, "1", "v2", "v2")]
+    #[case::version(NumberOperation::Add, "1", "1.2.3", "1.2.3")]
+    #[case::trailing_dot(NumberOperation::Add, "1", "1.", "1.")]
+    #[case::leading_dot(NumberOperation::Add, "1", ".5", ".5")]
+    #[case::sign_only(NumberOperation::Add, "1", "-", "-")]
+    #[case::exponent(NumberOperation::Add, "1", "1e3", "1e3")]
+    #[case::non_ascii_digits(NumberOperation::Add, "1", "٣", "٣")]
+    #[case::overflow(
+        NumberOperation::Multiply,
+        "10",
+        "99999999999999999999999999999999999999",
+        "99999999999999999999999999999999999999"
+    )]
+    fn test_number_op(
+        #[case] operation: NumberOperation,
+        #[case] operand: &str,
+        #[case] input: &str,
+        #[case] expected: &str,
+    ) {
+        let action = NumberOp::new(operation, operand, false).unwrap();
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case::leading_zeros(NumberOperation::Add, "1", "007", "008")]
+    #[case::overflowing_width(NumberOperation::Add, "1", "099", "100")]
+    #[case::negative(NumberOperation::Add, "1", "-07", "-06")]
+    #[case::float(NumberOperation::Add, "0.5", "01.5", "02.0")]
+    #[case::no_leading_zeros(NumberOperation::Subtract, "1", "10", "9")]
+    #[case::single_zero(NumberOperation::Add, "10", "0", "10")]
+    fn test_number_op_preserve_width(
+        #[case] operation: NumberOperation,
+        #[case] operand: &str,
+        #[case] input: &str,
+        #[case] expected: &str,
+    ) {
+        let action = NumberOp::new(operation, operand, true).unwrap();
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("one")]
+    #[case("1.2.3")]
+    #[case("0x10")]
+    fn test_number_op_invalid_operand(#[case] operand: &str) {
+        assert_eq!(
+            NumberOp::new(NumberOperation::Add, operand, false),
+            Err(NumberOpError::InvalidOperand(operand.to_owned()))
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use clap::ValueEnum;
+
+use super::Action;
+
+/// A style of properties in JavaScript/TypeScript object literals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PropertyStyle {
+    /// Shorthand properties: `{ x }`.
+    Shorthand,
+    /// Longhand properties: `{ x: x }`.
+    Longhand,
+}
+
+/// Converts properties of JavaScript/TypeScript object literals in scope to the given
+/// [`PropertyStyle`].
+///
+/// Scope should cover entire object literals, braces included. Shorthand properties
+/// are expanded (`{ x }` → `{ x: x }`), or longhand properties whose value is the
+/// very identifier used as key collapsed (`{ x: x }` → `{ x }`). Nested object
+/// literals are converted as well. Other properties, such as computed (`[k]: v`),
+/// spread (`...rest`) or method properties, are left alone.
+///
+/// Input which cannot be converted safely is left alone, for example objects
+/// containing comments, or anything not looking like an object literal at all.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, ConvertProperties, PropertyStyle};
+///
+/// let action = ConvertProperties::new(PropertyStyle::Longhand);
+/// assert_eq!(action.act("{ x, y: 1, ...z }"), "{ x: x, y: 1, ...z }");
+///
+/// let action = ConvertProperties::new(PropertyStyle::Shorthand);
+/// assert_eq!(action.act("{ x: x, y: 1, ...z }"), "{ x, y: 1, ...z }");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvertProperties {
+    style: PropertyStyle,
+}
+
+impl ConvertProperties {
+    /// Creates a new instance converting properties to `style`.
+    #[must_use]
+    pub const fn new(style: PropertyStyle) -> Self {
+        Self { style }
+    }
+
+    /// Converts the object literal `input`, if it looks like one.
+    fn convert_object(self, input: &str) -> Option<String> {
+        let start = input.find('{')?;
+        let end = input.rfind('}')?;
+
+        if end < start || !input[..start].trim().is_empty() || !input[end + 1..].trim().is_empty() {
+            return None;
+        }
+
+        let properties = split_properties(&input[start + 1..end])?
+            .into_iter()
+            .map(|property| self.convert_property(property))
+            .collect::<Vec<_>>();
+
+        Some(format!(
+            "{}{}{}",
+            &input[..=start],
+            properties.join(","),
+            &input[end..]
+        ))
+    }
+
+    /// Converts a single property, keeping surrounding whitespace.
+    fn convert_property(self, property: &str) -> String {
+        let trimmed = property.trim();
+        let Some(start) = property.find(trimmed) else {
+            return property.to_owned();
+        };
+        let (leading, rest) = property.split_at(start);
+        let trailing = &rest[trimmed.len()..];
+
+        let converted = match (self.style, trimmed.split_once(':')) {
+            (PropertyStyle::Longhand, None) if is_identifier(trimmed) => {
+                format!("{trimmed}: {trimmed}")
+            }
+            (PropertyStyle::Shorthand, Some((key, value)))
+                if is_identifier(key.trim()) && key.trim() == value.trim() =>
+            {
+                key.trim().to_owned()
+            }
+            (_, Some((key, value))) if is_identifier(key.trim()) => {
+                // Perhaps a nested object literal.
+                self.convert_object(value)
+                    .map_or_else(|| trimmed.to_owned(), |value| format!("{key}:{value}"))
+            }
+            _ => trimmed.to_owned(),
+        };
+
+        format!("{leading}{converted}{trailing}")
+    }
+}
+
+impl Action for ConvertProperties {
+    fn act(&self, input: &str) -> String {
+        self.convert_object(input)
+            .unwrap_or_else(|| input.to_owned())
+    }
+}
+
+/// Whether `s` is a plain identifier, usable as a shorthand property.
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Splits the inside of an object literal into its properties, at commas not nested
+/// inside brackets or strings.
+///
+/// Returns [`None`] if brackets or strings are unbalanced, or comments are found
+/// (which might contain anything).
+fn split_properties(input: &str) -> Option<Vec<&str>> {
+    let mut properties = Vec::new();
+    let mut depth: usize = 0;
+    let mut quote = None;
+    let mut last = 0;
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                chars.next(); // Escaped, whatever it is
+            }
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'' | '`') => quote = Some(c),
+            (None, '/') if matches!(chars.peek(), Some((_, '/' | '*'))) => return None,
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth = depth.checked_sub(1)?,
+            (None, ',') if depth == 0 => {
+                properties.push(&input[last..i]);
+                last = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    if depth != 0 || quote.is_some() {
+        return None;
+    }
+
+    properties.push(&input[last..]);
+
+    Some(properties)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("{}", "{}")]
+    #[case::single("{ x }", "{ x: x }")]
+    #[case::mixed("{ x, y: 1, z }", "{ x: x, y: 1, z: z }")]
+    #[case::already_longhand("{ x: x }", "{ x: x }")]
+    #[case::trailing_comma("{\n  a,\n  b,\n}", "{\n  a: a,\n  b: b,\n}")]
+    #[case::spread_untouched("{ ...rest, x }", "{ ...rest, x: x }")]
+    #[case::computed_untouched("{ [k]: v, x }", "{ [k]: v, x: x }")]
+    #[case::method_untouched("{ f() { return x }, x }", "{ f() { return x }, x: x }")]
+    #[case::nested("{ a: { b, c: [d, e] }, f }", "{ a: { b: b, c: [d, e] }, f: f }")]
+    #[case::strings_untouched("{ a: 'x, y', b }", "{ a: 'x, y', b: b }")]
+    #[case::template_untouched("{ a: `${x}, ${y}`, b }", "{ a: `${x}, ${y}`, b: b }")]
+    #[case::unicode("{ größe }", "{ größe: größe }")]
+    #[case::surrounding_whitespace(" { x }\n", " { x: x }\n")]
+    #[case::comment_untouched("{ x, // y\n}", "{ x, // y\n}")]
+    #[case::not_an_object("x, y", "x, y")]
+    #[case::unbalanced("{ x, [y }", "{ x, [y }")]
+    fn test_to_longhand(#[case] input: &str, #[case] expected: &str) {
+        let action = ConvertProperties::new(PropertyStyle::Longhand);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case::empty("{}", "{}")]
+    #[case::single("{ x: x }", "{ x }")]
+    #[case::mixed("{ x: x, y: 1, z:z }", "{ x, y: 1, z }")]
+    #[case::different_value("{ x: y }", "{ x: y }")]
+    #[case::already_shorthand("{ x }", "{ x }")]
+    #[case::quoted_key_untouched("{ 'x': x }", "{ 'x': x }")]
+    #[case::spread_untouched("{ ...rest, x: x }", "{ ...rest, x }")]
+    #[case::computed_untouched("{ [x]: x }", "{ [x]: x }")]
+    #[case::nested("{ a: { b: b }, c: c }", "{ a: { b }, c }")]
+    #[case::multiline("{\n  a: a,\n  b: 2,\n}", "{\n  a,\n  b: 2,\n}")]
+    fn test_to_shorthand(#[case] input: &str, #[case] expected: &str) {
+        let action = ConvertProperties::new(PropertyStyle::Shorthand);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case("{ a, b: 1, ...c, [d]: d, e() {} }")]
+    #[case("{ a: { b, c: c }, d }")]
+    fn test_roundtrip(#[case] input: &str) {
+        let longhand = ConvertProperties::new(PropertyStyle::Longhand).act(input);
+        let shorthand = ConvertProperties::new(PropertyStyle::Shorthand).act(&longhand);
+
+        assert_eq!(
+            shorthand,
+            ConvertProperties::new(PropertyStyle::Shorthand).act(input)
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use super::Action;
+
+/// Masks input, replacing every character with an asterisk (`*`).
+///
+/// Line breaks are kept, so masked input retains its line structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Redact {}
+
+impl Action for Redact {
+    fn act(&self, input: &str) -> String {
+        input
+            .chars()
+            .map(|c| if matches!(c, '\n' | '\r') { c } else { '*' })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("hunter2", "*******")]
+    #[case("two words", "*********")]
+    #[case("äö你", "***")]
+    #[case("a\nb\r\nc", "*\n*\r\n*")]
+    fn test_redact(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(Redact::default().act(input), expected);
+    }
+}
@@ -0,0 +1,59 @@
+use log::info;
+
+use super::Action;
+
+/// Escapes regex metacharacters, such that the result matches the input literally
+/// when used as a regular expression pattern.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, RegexEscape};
+///
+/// let action = RegexEscape::default();
+/// assert_eq!(action.act("main.rs"), r"main\.rs");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RegexEscape {}
+
+impl Action for RegexEscape {
+    fn act(&self, input: &str) -> String {
+        info!("Escaping for regex: '{}'", input);
+        regex::escape(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("abc_123", "abc_123")]
+    #[case("a.b", r"a\.b")]
+    #[case("a*", r"a\*")]
+    #[case("a+?", r"a\+\?")]
+    #[case("(group)", r"\(group\)")]
+    #[case("[class]", r"\[class\]")]
+    #[case("{1,2}", r"\{1,2\}")]
+    #[case("^start$", r"\^start\$")]
+    #[case("a|b", r"a\|b")]
+    #[case(r"back\slash", r"back\\slash")]
+    #[case("x-y#z&w~", r"x\-y\#z\&w\~")]
+    #[case("äöü 你好", "äöü 你好")]
+    fn test_regex_escape(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(RegexEscape::default().act(input), expected);
+    }
+
+    #[rstest]
+    #[case("foo.bar(baz)*")]
+    #[case("[^a-z]+ | {x} \\d")]
+    fn test_regex_escape_matches_input_literally(#[case] input: &str) {
+        let pattern = RegexEscape::default().act(input);
+        let re = regex::Regex::new(&format!("^{pattern}$")).unwrap();
+
+        assert!(re.is_match(input));
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use super::{Action, ActionError, Replacement, ReplacementError};
+use crate::scoping::scope::ScopeContext;
+#[cfg(doc)]
+use crate::scoping::view::ScopedView;
+
+/// Renames following a scheme derived from the original, such as `new_$1`.
+///
+/// On its own, this acts just like [`Replacement`]. Its purpose is use via
+/// [`ScopedView::rename`], which first computes *all* renames of a view, and refuses
+/// to apply any of them if two distinct sources would end up with the same name.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::RegexPattern;
+/// use srgn::actions::Rename;
+/// use srgn::scoping::{view::ScopedViewBuilder, regex::Regex};
+///
+/// let scoper = Regex::new(RegexPattern::new(r"get_(\w+)").unwrap());
+/// let rename = Rename::try_from("fetch_$1".to_owned()).unwrap();
+///
+/// let mut builder = ScopedViewBuilder::new("get_a(); get_b(); get_a();");
+/// builder.explode(&scoper);
+/// let mut view = builder.build();
+/// view.rename(&rename).unwrap();
+/// assert_eq!(view.to_string(), "fetch_a(); fetch_b(); fetch_a();");
+///
+/// // Both `get_a` and `get_b` would turn into `fetch`, which is an error.
+/// let rename = Rename::try_from("fetch".to_owned()).unwrap();
+///
+/// let mut builder = ScopedViewBuilder::new("get_a(); get_b();");
+/// builder.explode(&scoper);
+/// let mut view = builder.build();
+/// assert!(view.rename(&rename).is_err());
+/// assert_eq!(view.to_string(), "get_a(); get_b();"); // Untouched
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Rename(Replacement);
+
+impl TryFrom<String> for Rename {
+    type Error = ReplacementError;
+
+    /// Creates a new renaming scheme. See [`Replacement`] for details, which this
+    /// delegates to.
+    fn try_from(scheme: String) -> Result<Self, Self::Error> {
+        Ok(Self(Replacement::try_from(scheme)?))
+    }
+}
+
+impl Action for Rename {
+    fn act(&self, input: &str) -> String {
+        self.0.act(input)
+    }
+
+    fn act_with_context(
+        &self,
+        input: &str,
+        context: &ScopeContext<'_>,
+    ) -> Result<String, ActionError> {
+        self.0.act_with_context(input, context)
+    }
+}
+
+/// Two or more distinct sources would be renamed to the same target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameCollision {
+    /// The name all sources would be renamed to.
+    pub target: String,
+    /// The distinct, colliding sources, in order of appearance.
+    pub sources: Vec<String>,
+}
+
+impl fmt::Display for RenameCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Renaming would merge distinct names {} into '{}'",
+            self.sources
+                .iter()
+                .map(|s| format!("'{s}'"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.target
+        )
+    }
+}
+
+impl Error for RenameCollision {}
+
+impl From<RenameCollision> for ActionError {
+    fn from(value: RenameCollision) -> Self {
+        Self::RenameCollision(value)
+    }
+}
+
+/// Checks the given `(source, target)` pairs for collisions, returning the first one
+/// found (in order of appearance of the target).
+pub fn find_collision<'a>(
+    renames: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Option<RenameCollision> {
+    let mut sources_by_target: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut targets = Vec::new();
+
+    for (source, target) in renames {
+        let sources = sources_by_target.entry(target).or_insert_with(|| {
+            targets.push(target);
+            Vec::new()
+        });
+
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+
+    targets.into_iter().find_map(|target| {
+        let sources = &sources_by_target[target];
+
+        (sources.len() > 1).then(|| RenameCollision {
+            target: target.to_owned(),
+            sources: sources.iter().map(|&s| s.to_owned()).collect(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::scoping::regex::Regex;
+    use crate::scoping::view::ScopedViewBuilder;
+    use crate::RegexPattern;
+
+    #[rstest]
+    #[case(&[], None)]
+    #[case(&[("a", "x")], None)]
+    #[case(&[("a", "x"), ("a", "x")], None)]
+    #[case(&[("a", "x"), ("b", "y")], None)]
+    #[case(
+        &[("a", "x"), ("b", "x")],
+        Some(RenameCollision { target: "x".into(), sources: vec!["a".into(), "b".into()] })
+    )]
+    #[case(
+        &[("a", "y"), ("b", "x"), ("c", "x"), ("a", "x")],
+        Some(RenameCollision {
+            target: "x".into(),
+            sources: vec!["b".into(), "c".into(), "a".into()],
+        })
+    )]
+    fn test_find_collision(
+        #[case] renames: &[(&str, &str)],
+        #[case] expected: Option<RenameCollision>,
+    ) {
+        assert_eq!(find_collision(renames.iter().copied()), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "let old_a = old_b + old_a;",
+        r"old_(\w+)",
+        "new_$1",
+        Ok("let new_a = new_b + new_a;")
+    )]
+    #[case(
+        "let fooBar = foo_bar;",
+        r"foo_?[bB]ar",
+        "baz",
+        Err(RenameCollision {
+            target: "baz".into(),
+            sources: vec!["fooBar".into(), "foo_bar".into()],
+        })
+    )]
+    #[case(
+        "x_1 x_2 y_1",
+        r"(?<prefix>[a-z])_(?<n>\d)",
+        "${prefix}${prefix}",
+        Err(RenameCollision {
+            target: "xx".into(),
+            sources: vec!["x_1".into(), "x_2".into()],
+        })
+    )]
+    fn test_rename(
+        #[case] input: &str,
+        #[case] pattern: &str,
+        #[case] scheme: &str,
+        #[case] expected: Result<&str, RenameCollision>,
+    ) {
+        let scoper = Regex::new(RegexPattern::new(pattern).unwrap());
+        let rename = Rename::try_from(scheme.to_owned()).unwrap();
+
+        let mut builder = ScopedViewBuilder::new(input);
+        builder.explode(&scoper);
+        let mut view = builder.build();
+
+        let res = view.rename(&rename).map(|v| v.to_string());
+
+        match expected {
+            Ok(expected) => assert_eq!(res.unwrap(), expected),
+            Err(collision) => {
+                assert_eq!(res.unwrap_err(), ActionError::RenameCollision(collision));
+                assert_eq!(view.to_string(), input, "view must be left untouched");
+            }
+        }
+    }
+}
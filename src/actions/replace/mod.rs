@@ -1,11 +1,19 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::string::FromUtf8Error;
 
 use log::{debug, info};
 use unescape::unescape;
-use variables::{inject_variables, VariableExpressionError};
+use variables::{
+    render, scan_variables, scan_variables_lenient, VariableExpressionError, VariablePositions,
+};
 
 use super::{Action, ActionError};
+use crate::scoping::regex::CaptureGroup;
 use crate::scoping::scope::ScopeContext;
 
 /// Items for dealing with variables in replacement values.
@@ -48,7 +56,12 @@ pub mod variables;
 /// );
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct Replacement(String);
+pub struct Replacement {
+    /// The replacement value, possibly containing variables.
+    value: String,
+    /// Where in [`Replacement::value`] variables are found, scanned once upfront.
+    variables: VariablePositions,
+}
 
 impl TryFrom<String> for Replacement {
     type Error = ReplacementError;
@@ -56,7 +69,7 @@ impl TryFrom<String> for Replacement {
     /// Creates a new replacement from an owned string.
     ///
     /// Escape sequences are accepted and processed, with invalid escape sequences
-    /// returning an [`Err`].
+    /// returning an [`Err`]. Same for invalid variable expressions.
     ///
     /// ## Examples
     ///
@@ -87,8 +100,100 @@ impl TryFrom<String> for Replacement {
     fn try_from(replacement: String) -> Result<Self, Self::Error> {
         let unescaped =
             unescape(&replacement).ok_or(ReplacementError::InvalidEscapeSequences(replacement))?;
+        let variables = scan_variables(&unescaped)?;
+
+        Ok(Self {
+            value: unescaped,
+            variables,
+        })
+    }
+}
+
+impl Replacement {
+    /// Creates a new replacement from an owned string, taking it **verbatim**.
+    ///
+    /// As opposed to [`Replacement::try_from`], no escape sequences are processed:
+    /// backslashes are kept literally, and construction cannot fail. Variables are
+    /// still supported when acting with context. Invalid variable expressions (like
+    /// mismatched braces) are kept literally as well, without affecting valid ones.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use srgn::actions::{Action, Replacement};
+    ///
+    /// // Would be an invalid escape sequence for `Replacement::try_from`
+    /// let replacement = Replacement::raw(r"C:\zig".to_owned());
+    /// assert_eq!(replacement.act("anything"), r"C:\zig");
+    /// ```
+    #[must_use]
+    pub fn raw(replacement: String) -> Self {
+        let variables = scan_variables_lenient(&replacement);
+
+        Self {
+            value: replacement,
+            variables,
+        }
+    }
+
+    /// Creates a new replacement from everything `reader` yields, for example for
+    /// large or multi-line replacements.
+    ///
+    /// The content is taken as is, including any trailing newline, then processed
+    /// like for [`Replacement::try_from`]: escape sequences and variables work the
+    /// same.
+    ///
+    /// # Errors
+    ///
+    /// Errors like [`Replacement::try_from`], if reading fails, or if the content is
+    /// not valid UTF-8, for example of binary files.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use srgn::actions::{Replacement, ReplacementError};
+    ///
+    /// let replacement = Replacement::from_reader("Hello,\n$1!\n".as_bytes());
+    /// assert!(replacement.is_ok());
+    ///
+    /// let replacement = Replacement::from_reader(&[0xff, 0xfe][..]);
+    /// assert!(matches!(replacement, Err(ReplacementError::InvalidUtf8(_))));
+    /// ```
+    pub fn from_reader(mut reader: impl Read) -> Result<Self, ReplacementError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| ReplacementError::Io {
+                path: None,
+                message: e.to_string(),
+            })?;
+
+        Self::try_from(String::from_utf8(bytes)?)
+    }
+
+    /// Creates a new replacement from the contents of the file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Errors like [`Replacement::from_reader`], or if the file cannot be opened.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ReplacementError> {
+        let path = path.as_ref();
+        let with_path = |message: String| ReplacementError::Io {
+            path: Some(path.to_owned()),
+            message,
+        };
+
+        let file = File::open(path).map_err(|e| with_path(e.to_string()))?;
+
+        Self::from_reader(file).map_err(|e| match e {
+            ReplacementError::Io { message, .. } => with_path(message),
+            e => e,
+        })
+    }
 
-        Ok(Self(unescaped))
+    /// Renders the replacement, substituting its variables with the given values.
+    pub(crate) fn render(&self, variables: &HashMap<CaptureGroup, &str>) -> String {
+        render(&self.value, &self.variables, variables)
     }
 }
 
@@ -99,6 +204,15 @@ pub enum ReplacementError {
     InvalidEscapeSequences(String),
     /// The replacement contains an error in its variable expressions.
     VariableError(VariableExpressionError),
+    /// The replacement could not be read, see [`Replacement::from_reader`].
+    Io {
+        /// The file in question, if read via [`Replacement::from_path`].
+        path: Option<PathBuf>,
+        /// The underlying error's message.
+        message: String,
+    },
+    /// The replacement read is not valid UTF-8, for example because it is binary.
+    InvalidUtf8(FromUtf8Error),
 }
 
 impl fmt::Display for ReplacementError {
@@ -110,6 +224,21 @@ impl fmt::Display for ReplacementError {
             Self::VariableError(err) => {
                 write!(f, "Error in variable expressions: {err}")
             }
+            Self::Io {
+                path: Some(path),
+                message,
+            } => write!(
+                f,
+                "Cannot read replacement from '{}': {message}",
+                path.display()
+            ),
+            Self::Io {
+                path: None,
+                message,
+            } => write!(f, "Cannot read replacement: {message}"),
+            Self::InvalidUtf8(err) => {
+                write!(f, "Replacement is not valid UTF-8 (binary?): {err}")
+            }
         }
     }
 }
@@ -122,11 +251,17 @@ impl From<VariableExpressionError> for ReplacementError {
     }
 }
 
+impl From<FromUtf8Error> for ReplacementError {
+    fn from(value: FromUtf8Error) -> Self {
+        Self::InvalidUtf8(value)
+    }
+}
+
 impl Action for Replacement {
     fn act(&self, input: &str) -> String {
-        info!("Substituting '{}' with '{}'", input, self.0);
+        info!("Substituting '{}' with '{}'", input, self.value);
         info!("This substitution is verbatim and does not take into account variables");
-        self.0.clone()
+        self.value.clone()
     }
 
     fn act_with_context(
@@ -138,7 +273,7 @@ impl Action for Replacement {
             ScopeContext::CaptureGroups(cgs) => {
                 debug!("Available capture group variables: {cgs:?}");
 
-                Ok(inject_variables(self.0.as_str(), cgs)?)
+                Ok(self.render(cgs))
             }
         }
     }
@@ -155,3 +290,127 @@ impl From<ReplacementError> for ActionError {
         Self::ReplacementError(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(r"\z", r"\z")]
+    #[case(r"\t", r"\t")]
+    #[case(r"\\", r"\\")]
+    #[case("a\tb", "a\tb")]
+    fn test_raw_replacement_is_verbatim(#[case] replacement: &str, #[case] expected: &str) {
+        let replacement = Replacement::raw(replacement.to_owned());
+
+        assert_eq!(replacement.act("input"), expected);
+    }
+
+    #[rstest]
+    #[case(r"\z", Err(ReplacementError::InvalidEscapeSequences(r"\z".to_owned())))]
+    #[case(r"\t", Ok("\t"))]
+    #[case(r"\\", Ok(r"\"))]
+    fn test_replacement_unescapes(
+        #[case] replacement: &str,
+        #[case] expected: Result<&str, ReplacementError>,
+    ) {
+        let replacement = Replacement::try_from(replacement.to_owned());
+
+        assert_eq!(
+            replacement.map(|r| r.act("input")),
+            expected.map(ToOwned::to_owned)
+        );
+    }
+
+    #[rstest]
+    #[case::plain("Hello\nWorld\n", Ok("Hello\nWorld\n"))]
+    #[case::escapes(r"a\tb\n", Ok("a\tb\n"))]
+    #[case::variables("<$1>", Ok("<x>"))]
+    #[case::invalid_escape(r"\z", Err(ReplacementError::InvalidEscapeSequences(r"\z".to_owned())))]
+    fn test_replacement_from_reader(
+        #[case] content: &str,
+        #[case] expected: Result<&str, ReplacementError>,
+    ) {
+        let replacement = Replacement::from_reader(content.as_bytes());
+        let context =
+            ScopeContext::CaptureGroups(HashMap::from([(CaptureGroup::Numbered(1), "x")]));
+
+        assert_eq!(
+            replacement.map(|r| r.act_with_context("input", &context).unwrap()),
+            expected.map(ToOwned::to_owned)
+        );
+    }
+
+    #[test]
+    fn test_replacement_from_reader_rejects_binary() {
+        let replacement = Replacement::from_reader(&b"PNG\x89\x00\xff"[..]);
+
+        assert!(matches!(
+            replacement,
+            Err(ReplacementError::InvalidUtf8(e)) if e.utf8_error().valid_up_to() == 3
+        ));
+    }
+
+    #[test]
+    fn test_replacement_from_path() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        io::Write::write_all(&mut file, b"multi\nline $1\n").unwrap();
+        let context =
+            ScopeContext::CaptureGroups(HashMap::from([(CaptureGroup::Numbered(1), "x")]));
+
+        let replacement = Replacement::from_path(file.path()).unwrap();
+
+        assert_eq!(
+            replacement.act_with_context("input", &context),
+            Ok("multi\nline x\n".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_replacement_from_missing_path() {
+        let error = Replacement::from_path("does/not/exist").unwrap_err();
+
+        let ReplacementError::Io { path, message } = &error else {
+            panic!("expected an I/O error, got {error:?}");
+        };
+        assert_eq!(path.as_deref(), Some(Path::new("does/not/exist")));
+        assert!(!message.is_empty());
+        assert!(error
+            .to_string()
+            .starts_with("Cannot read replacement from 'does/not/exist': "));
+    }
+
+    #[test]
+    fn test_raw_replacement_supports_variables() {
+        let replacement = Replacement::raw(r"\$1\".to_owned());
+        let context =
+            ScopeContext::CaptureGroups(HashMap::from([(CaptureGroup::Numbered(1), "x")]));
+
+        assert_eq!(
+            replacement.act_with_context("input", &context),
+            Ok(r"\x\".to_owned())
+        );
+    }
+
+    #[rstest]
+    #[case::mismatched_braces("$1 ${", "x ${")]
+    #[case::unknown_modifier("${1:bogus} $1", "${1:bogus} x")]
+    #[case::several_invalid("${ $1 ${1:bogus}$1 ${", "${ x ${1:bogus}x ${")]
+    fn test_raw_replacement_keeps_valid_variables_next_to_invalid_ones(
+        #[case] replacement: &str,
+        #[case] expected: &str,
+    ) {
+        let replacement = Replacement::raw(replacement.to_owned());
+        let context =
+            ScopeContext::CaptureGroups(HashMap::from([(CaptureGroup::Numbered(1), "x")]));
+
+        assert_eq!(
+            replacement.act_with_context("input", &context),
+            Ok(expected.to_owned())
+        );
+    }
+}
@@ -1,52 +1,146 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::error::Error;
 use std::fmt;
+use std::ops::Range;
 
 use log::trace;
 
+use crate::actions::{Action, Lower, Titlecase, Upper};
 use crate::scoping::regex::CaptureGroup;
 
 type Variables<'a> = HashMap<CaptureGroup, &'a str>;
 
-/// In an input like `Hello $var World`, inject all variables.
+/// Positions of all variable expressions found in an input like `Hello $var World`,
+/// in order of appearance.
+///
+/// Obtained once via [`scan_variables`], then applied any number of times via
+/// [`render`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct VariablePositions(Vec<(Range<usize>, Substitution)>);
+
+/// What to substitute a variable expression with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Substitution {
+    /// The value of the capture group of this variable, falling back to its default
+    /// value (as in `${var:-default}`), if any. The value is then transformed by its
+    /// modifier (as in `${var:upper}`), if any.
+    Variable {
+        group: CaptureGroup,
+        default: Option<String>,
+        modifier: Option<Modifier>,
+    },
+    /// A literal `$`, from an escaped `$$`.
+    Dollar,
+}
+
+/// A transformation of the value of a variable, as in `${var:upper}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Modifier {
+    /// See [`Upper`].
+    Upper,
+    /// See [`Lower`].
+    Lower,
+    /// See [`Titlecase`].
+    Title,
+}
+
+impl Modifier {
+    /// All modifiers, by name.
+    const ALL: [(&'static str, Self); 3] = [
+        ("upper", Self::Upper),
+        ("lower", Self::Lower),
+        ("title", Self::Title),
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find_map(|&(n, modifier)| (n == name).then_some(modifier))
+    }
+
+    fn apply(self, value: &str) -> String {
+        match self {
+            Self::Upper => Upper::default().act(value),
+            Self::Lower => Lower::default().act(value),
+            Self::Title => Titlecase::default().act(value),
+        }
+    }
+}
+
+/// In an input like `Hello $var World`, find the positions of all variables.
 ///
 /// Variables are treated as they occur in regular expressions: they can be [named or
-/// numbered](https://docs.rs/regex/latest/regex/struct.Captures.html).
+/// numbered](https://docs.rs/regex/latest/regex/struct.Captures.html). Braced ones
+/// can carry either a default value, like `${var:-default}`, taken literally up to the
+/// closing brace, or a modifier transforming their value, like `${var:upper}`.
+pub(crate) fn scan_variables(input: &str) -> Result<VariablePositions, VariableExpressionError> {
+    let mut positions = Vec::new();
+    scan(input, &mut positions).map_err(|(_, e)| e)?;
+
+    Ok(VariablePositions(positions))
+}
+
+/// Like [`scan_variables`], but skips invalid variable expressions instead of failing.
+///
+/// Invalid expressions are kept literally, while valid ones around them are still
+/// found.
+pub(crate) fn scan_variables_lenient(input: &str) -> VariablePositions {
+    let mut positions = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let mut found = Vec::new();
+        let res = scan(&input[offset..], &mut found);
+        positions.extend(
+            found.into_iter().map(|(range, substitution)| {
+                (range.start + offset..range.end + offset, substitution)
+            }),
+        );
+
+        match res {
+            Ok(()) => break,
+            Err((start, e)) => {
+                trace!("Keeping invalid variable expression at {start} literally: {e}");
+                // Resume right after its `$`, so it's kept literally.
+                offset += start + '$'.len_utf8();
+            }
+        }
+    }
+
+    VariablePositions(positions)
+}
+
+/// Scans `input` for variables, appending their positions to `positions`.
+///
+/// Stops at the first invalid variable expression, returning its starting position
+/// along with the error.
 #[allow(clippy::too_many_lines)] // :(
-pub(super) fn inject_variables(
+fn scan(
     input: &str,
-    variables: &Variables<'_>,
-) -> Result<String, VariableExpressionError> {
+    positions: &mut Vec<(Range<usize>, Substitution)>,
+) -> Result<(), (usize, VariableExpressionError)> {
     let mut state = State::default();
-    let mut out = String::with_capacity(input.len());
-    let mut to_remove = 0; // Remove this many pushed chars once a var is detected
-
-    for c in input.chars() {
-        trace!(
-            "Injecting variables. Current output is: '{}', current state is {:?}",
-            out.escape_debug(),
-            state
-        );
-        out.push(c);
+    let mut start = 0; // Byte position of the `$` of the current variable
+
+    for (i, c) in input.char_indices() {
+        trace!("Scanning for variables. At {i} ('{c}'), current state is {state:?}");
 
         state = match (state, c) {
             // Initial state
             (State::Noop, '$') => {
-                to_remove = 1;
+                start = i;
                 State::Start
             }
             (State::Start, '$') => {
-                // Ignore previous `$`, and only push one.
-                assert_eq!(out.pop().expect("was pushed in earlier loop"), '$',);
+                positions.push((start..i + c.len_utf8(), Substitution::Dollar));
                 State::default()
             }
             (State::Noop, _) => State::default(),
 
             // Init
-            (State::Start, '{') => {
-                to_remove += 1;
-                State::BracedStart
-            }
+            (State::Start, '{') => State::BracedStart,
             (State::Start, 'a'..='z' | 'A'..='Z' | '_') => State::BuildingNamedVar {
                 name: String::from(c),
                 braced: false,
@@ -94,153 +188,234 @@ pub(super) fn inject_variables(
                 braced,
             },
 
+            // Default values and modifiers
+            (State::BuildingNamedVar { name, braced: true }, ':') => State::StartingSuffix {
+                group: CaptureGroup::Named(name),
+            },
+            (State::BuildingNumberedVar { num, braced: true }, ':') => State::StartingSuffix {
+                group: CaptureGroup::Numbered(num),
+            },
+            (State::StartingSuffix { group }, '-') => State::BuildingDefault {
+                group,
+                default: String::new(),
+            },
+            (State::StartingSuffix { .. }, '}') => {
+                return Err((
+                    start,
+                    VariableExpressionError::UnknownModifier(String::new()),
+                ))
+            }
+            (State::StartingSuffix { group }, _) => State::BuildingModifier {
+                group,
+                modifier: String::from(c),
+            },
+            (State::BuildingDefault { group, default }, '}') => {
+                trace!("Finishing up variable '{group:?}' with default '{default}'");
+                positions.push((
+                    start..i + c.len_utf8(),
+                    Substitution::Variable {
+                        group,
+                        default: Some(default),
+                        modifier: None,
+                    },
+                ));
+                State::Noop
+            }
+            (State::BuildingModifier { group, modifier }, '}') => {
+                trace!("Finishing up variable '{group:?}' with modifier '{modifier}'");
+                let Some(modifier) = Modifier::from_name(&modifier) else {
+                    return Err((start, VariableExpressionError::UnknownModifier(modifier)));
+                };
+                positions.push((
+                    start..i + c.len_utf8(),
+                    Substitution::Variable {
+                        group,
+                        default: None,
+                        modifier: Some(modifier),
+                    },
+                ));
+                State::Noop
+            }
+            (
+                State::BuildingModifier {
+                    group,
+                    mut modifier,
+                },
+                _,
+            ) => State::BuildingModifier {
+                group,
+                modifier: {
+                    modifier.push(c);
+                    modifier
+                },
+            },
+            (State::BuildingDefault { group, mut default }, _) => State::BuildingDefault {
+                group,
+                default: {
+                    default.push(c);
+                    default
+                },
+            },
+
             // Building stops
             (State::BuildingNamedVar { name, braced: true }, '}') => {
-                to_remove += 1;
-
-                State::FinishNamedVar(name)
+                trace!("Finishing up named variable '{name}'");
+                positions.push((start..i + c.len_utf8(), Substitution::named(name)));
+                State::Noop
             }
             (State::BuildingNumberedVar { num, braced: true }, '}') => {
-                to_remove += 1;
-
-                State::FinishNumberedVar(num)
+                trace!("Finishing up numbered variable '{num}'");
+                positions.push((start..i + c.len_utf8(), Substitution::numbered(num)));
+                State::Noop
+            }
+            (State::BuildingNamedVar { name, braced: true }, _) => {
+                return Err((start, VariableExpressionError::MismatchedBraces(name)))
+            }
+            (State::BuildingNumberedVar { num, braced: true }, _) => {
+                return Err((
+                    start,
+                    VariableExpressionError::MismatchedBraces(num.to_string()),
+                ))
             }
             (
                 State::BuildingNamedVar {
-                    name, braced: true, ..
-                },
-                _,
-            ) => return Err(VariableExpressionError::MismatchedBraces(name)),
-            (
-                State::BuildingNumberedVar {
-                    num, braced: true, ..
+                    name,
+                    braced: false,
                 },
                 _,
-            ) => return Err(VariableExpressionError::MismatchedBraces(num.to_string())),
-
-            (State::FinishNamedVar(name) | State::BuildingNamedVar { name, .. }, _) => {
+            ) => {
                 trace!("Finishing up named variable '{name}'");
-                match variables.get(&CaptureGroup::Named(name.clone())) {
-                    Some(repl) => {
-                        let tail = out
-                            .pop()
-                            .expect("chars are pushed unconditionally, one is present");
-                        out.truncate(out.len() - (to_remove + name.len()));
-                        out.push_str(repl);
-                        out.push(tail);
-                    }
-                    None => return Err(VariableExpressionError::UndefinedVariable(name)),
-                };
-
-                match c {
-                    '$' => {
-                        to_remove = 1;
-                        State::Start
-                    }
-                    _ => State::Noop,
-                }
+                positions.push((start..i, Substitution::named(name)));
+                State::after_variable(c, &mut start, i)
             }
-            (State::FinishNumberedVar(num) | State::BuildingNumberedVar { num, .. }, _) => {
+            (State::BuildingNumberedVar { num, braced: false }, _) => {
                 trace!("Finishing up numbered variable '{num}'");
-                match variables.get(&CaptureGroup::Numbered(num)) {
-                    Some(repl) => {
-                        let tail = out
-                            .pop()
-                            .expect("chars are pushed unconditionally, one is present");
-                        out.truncate(out.len() - (to_remove + width(num)));
-                        out.push_str(repl);
-                        out.push(tail);
-                    }
-                    None => {
-                        return Err(VariableExpressionError::UndefinedVariable(num.to_string()))
-                    }
-                };
-
-                match c {
-                    '$' => {
-                        to_remove = 1;
-                        State::Start
-                    }
-                    _ => State::Noop,
-                }
+                positions.push((start..i, Substitution::numbered(num)));
+                State::after_variable(c, &mut start, i)
             }
         }
     }
 
-    trace!(
-        "Finished character iteration, output is '{}', state is {:?}",
-        out.escape_debug(),
-        state
-    );
-
     // Flush out any pending state
-    let last = out.chars().last();
-    state = match (&state, last) {
-        (
-            State::FinishNamedVar(name)
-            | State::BuildingNamedVar {
-                name,
-                braced: false,
-            },
-            _,
-        ) => {
-            trace!("Finishing up named variable '{name}'");
-            match variables.get(&CaptureGroup::Named(name.clone())) {
-                Some(repl) => {
-                    out.truncate(out.len() - (to_remove + name.len()));
-                    out.push_str(repl);
-
-                    state
-                }
-                None => return Err(VariableExpressionError::UndefinedVariable(name.clone())),
-            }
+    match state {
+        State::BuildingNamedVar {
+            name,
+            braced: false,
+        } => {
+            positions.push((start..input.len(), Substitution::named(name)));
         }
-        (State::FinishNumberedVar(num) | State::BuildingNumberedVar { num, braced: false }, _) => {
-            trace!("Finishing up numbered variable '{num}'");
-            match variables.get(&CaptureGroup::Numbered(*num)) {
-                Some(repl) => {
-                    out.truncate(out.len() - (to_remove + width(*num)));
-                    out.push_str(repl);
-
-                    state
-                }
-                None => return Err(VariableExpressionError::UndefinedVariable(num.to_string())),
-            }
+        State::BuildingNumberedVar { num, braced: false } => {
+            positions.push((start..input.len(), Substitution::numbered(num)));
+        }
+        State::BuildingNamedVar { name, braced: true } => {
+            return Err((start, VariableExpressionError::MismatchedBraces(name)))
+        }
+        State::BuildingNumberedVar { num, braced: true } => {
+            return Err((
+                start,
+                VariableExpressionError::MismatchedBraces(num.to_string()),
+            ))
         }
-        (
-            State::BuildingNamedVar {
-                name, braced: true, ..
+        State::StartingSuffix { group }
+        | State::BuildingDefault { group, .. }
+        | State::BuildingModifier { group, .. } => {
+            return Err((start, VariableExpressionError::mismatched_braces(&group)))
+        }
+        State::Noop | State::Start | State::BracedStart => {}
+    };
+
+    trace!("Done scanning for variables, found: {positions:?}");
+
+    Ok(())
+}
+
+/// Renders `input` by substituting all variables at `positions` (as previously
+/// obtained from [`scan_variables`] *for the same input*) with their values.
+///
+/// Variables without a value, say for capture groups not present in the pattern,
+/// are substituted with the empty string, as is the case for regex replacements.
+/// Variables with a default value are substituted with that instead, also if their
+/// value is empty. Variables with a modifier are substituted with their transformed
+/// value.
+pub(super) fn render(
+    input: &str,
+    positions: &VariablePositions,
+    variables: &Variables<'_>,
+) -> String {
+    let res: Result<_, Infallible> = try_render(input, positions, |cg, default| {
+        let value = variables.get(cg).copied().filter(|value| !value.is_empty());
+
+        Ok(Some(Cow::Borrowed(value.or(default).unwrap_or_else(
+            || {
+                trace!("No value for variable {cg:?}, substituting empty string");
+                ""
             },
-            _,
-        ) => return Err(VariableExpressionError::MismatchedBraces(name.clone())),
-        (
-            State::BuildingNumberedVar {
-                num, braced: true, ..
+        ))))
+    });
+
+    match res {
+        Ok(out) => out,
+        Err(never) => match never {},
+    }
+}
+
+/// Like [`render`], but looks up values via `value`, which can fail. It is passed
+/// each variable and its default value, if any, and is responsible for falling back
+/// to the latter. Modifiers are applied to whatever it returns.
+///
+/// If `value` returns [`None`], the variable expression is left as-is.
+pub(crate) fn try_render<'a, E>(
+    input: &str,
+    positions: &'a VariablePositions,
+    mut value: impl FnMut(&CaptureGroup, Option<&'a str>) -> Result<Option<Cow<'a, str>>, E>,
+) -> Result<String, E> {
+    let mut out = input.to_owned();
+
+    // Back to front, so that earlier positions remain valid.
+    for (range, substitution) in positions.0.iter().rev() {
+        let value = match substitution {
+            Substitution::Variable {
+                group,
+                default,
+                modifier,
+            } => match (value(group, default.as_deref())?, modifier) {
+                (Some(value), Some(modifier)) => Cow::Owned(modifier.apply(&value)),
+                (Some(value), None) => value,
+                (None, _) => continue,
             },
-            _,
-        ) => return Err(VariableExpressionError::MismatchedBraces(num.to_string())),
-        (State::Noop | State::Start | State::BracedStart, _) => state,
-    };
+            Substitution::Dollar => Cow::Borrowed("$"),
+        };
+
+        out.replace_range(range.clone(), &value);
+    }
 
     trace!(
-        "Done injecting variables, final output is '{}', final state is {:?}",
-        out.escape_debug(),
-        state
+        "Done rendering variables, output is '{}'",
+        out.escape_debug()
     );
 
     Ok(out)
 }
 
-/// Gets the width in characters of a number.
-const fn width(num: usize) -> usize {
-    if num == 0 {
-        1
-    } else {
-        (num.ilog10() + 1) as usize
+impl Substitution {
+    const fn named(name: String) -> Self {
+        Self::Variable {
+            group: CaptureGroup::Named(name),
+            default: None,
+            modifier: None,
+        }
+    }
+
+    const fn numbered(num: usize) -> Self {
+        Self::Variable {
+            group: CaptureGroup::Numbered(num),
+            default: None,
+            modifier: None,
+        }
     }
 }
 
-/// State during injection of variables in an expression like `Hello $var World`.
+/// State during scanning for variables in an expression like `Hello $var World`.
 #[derive(Debug, PartialEq, Eq, Default)]
 enum State {
     #[default]
@@ -254,10 +429,31 @@ enum State {
     BuildingNamedVar { name: String, braced: bool },
     /// A numbered variable is detected and is being built up.
     BuildingNumberedVar { num: usize, braced: bool },
-    /// Processing of a named variable is done, finish it up.
-    FinishNamedVar(String),
-    /// Processing of a numbered variable is done, finish it up.
-    FinishNumberedVar(usize),
+    /// A braced variable is followed by `:`, starting a default value or modifier.
+    StartingSuffix { group: CaptureGroup },
+    /// The default value of a braced variable is being built up.
+    BuildingDefault {
+        group: CaptureGroup,
+        default: String,
+    },
+    /// The modifier of a braced variable is being built up.
+    BuildingModifier {
+        group: CaptureGroup,
+        modifier: String,
+    },
+}
+
+impl State {
+    /// The state after an unbraced variable was terminated by `c` at position `i`,
+    /// which might directly start the next variable.
+    fn after_variable(c: char, start: &mut usize, i: usize) -> Self {
+        if c == '$' {
+            *start = i;
+            Self::Start
+        } else {
+            Self::Noop
+        }
+    }
 }
 
 /// An error in variable expressions.
@@ -265,8 +461,17 @@ enum State {
 pub enum VariableExpressionError {
     /// A variable expression with mismatched number of braces.
     MismatchedBraces(String),
-    /// A requested variable was not passed.
-    UndefinedVariable(String),
+    /// A variable expression with an unknown modifier, like `${var:nope}`.
+    UnknownModifier(String),
+}
+
+impl VariableExpressionError {
+    fn mismatched_braces(group: &CaptureGroup) -> Self {
+        Self::MismatchedBraces(match group {
+            CaptureGroup::Named(name) => name.clone(),
+            CaptureGroup::Numbered(num) => num.to_string(),
+        })
+    }
 }
 
 impl fmt::Display for VariableExpressionError {
@@ -275,8 +480,12 @@ impl fmt::Display for VariableExpressionError {
             Self::MismatchedBraces(var) => {
                 write!(f, "Mismatched braces for variable: '{var}'")
             }
-            Self::UndefinedVariable(var) => {
-                write!(f, "Undefined variable, unable to substitute: '{var}'")
+            Self::UnknownModifier(modifier) => {
+                write!(
+                    f,
+                    "Unknown modifier '{modifier}', expected one of: {}",
+                    Modifier::ALL.map(|(name, _)| name).join(", ")
+                )
             }
         }
     }
@@ -296,6 +505,7 @@ mod test {
             (CaptureGroup::Named("var1".to_owned()), "val1"),
             (CaptureGroup::Named("VAR_2".to_owned()), "val2"),
             (CaptureGroup::Numbered(2), "nval"),
+            (CaptureGroup::Named("empty".to_owned()), ""),
         ])
     }
 
@@ -342,14 +552,13 @@ mod test {
     #[case("${2}$2", Ok("nvalnval"))]
     #[case("${var1}$var1 ${2}$2", Ok("val1val1 nvalnval"))]
     //
-    // Undefined variables
-    #[case("$NO", Err(VariableExpressionError::UndefinedVariable("NO".to_owned())))]
-    #[case("$NO such thing", Err(VariableExpressionError::UndefinedVariable("NO".to_owned())))]
-    #[case("$NO$ON", Err(VariableExpressionError::UndefinedVariable("NO".to_owned())))]
-    // Numbers will be stringified
-    #[case("$1337", Err(VariableExpressionError::UndefinedVariable("1337".to_owned())))]
-    #[case("$1337 is missing", Err(VariableExpressionError::UndefinedVariable("1337".to_owned())))]
-    #[case("$1337$7331", Err(VariableExpressionError::UndefinedVariable("1337".to_owned())))]
+    // Undefined variables are empty
+    #[case("$NO", Ok(""))]
+    #[case("$NO such thing", Ok(" such thing"))]
+    #[case("$NO$ON", Ok(""))]
+    #[case("$1337", Ok(""))]
+    #[case("$1337 is missing", Ok(" is missing"))]
+    #[case("$1337$7331$2", Ok("nval"))]
     //
     // Improperly closed braces
     #[case("${var1", Err(VariableExpressionError::MismatchedBraces("var1".to_owned())))]
@@ -357,29 +566,152 @@ mod test {
     // Excess trailing ones are fine tho
     #[case("${var1}}", Ok("val1}"))]
     //
+    // Default values
+    #[case("${var1:-x}", Ok("val1"))]
+    #[case("${2:-x}", Ok("nval"))]
+    #[case("${NO:-x}", Ok("x"))]
+    #[case("${1337:-x}", Ok("x"))]
+    #[case("${empty:-x}", Ok("x"))]
+    #[case("${NO:-}", Ok(""))]
+    #[case("${NO:-a b}c", Ok("a bc"))]
+    #[case("${NO:-$var1 $$}", Ok("$var1 $$"))]
+    #[case("${NO:-{x}}", Ok("{x}"))]
+    #[case("${NO:-:-}", Ok(":-"))]
+    #[case("${NO:-ü}${var1:-ü}", Ok("üval1"))]
+    // Only for braced variables
+    #[case("$NO:-x", Ok(":-x"))]
+    #[case("$var1:-x", Ok("val1:-x"))]
+    // Improperly closed or malformed
+    #[case("${NO:-x", Err(VariableExpressionError::MismatchedBraces("NO".to_owned())))]
+    #[case("${2:-x", Err(VariableExpressionError::MismatchedBraces("2".to_owned())))]
+    #[case("${NO:", Err(VariableExpressionError::MismatchedBraces("NO".to_owned())))]
+    #[case("${NO-x}", Err(VariableExpressionError::MismatchedBraces("NO".to_owned())))]
+    //
+    // Modifiers
+    #[case("${var1:upper}", Ok("VAL1"))]
+    #[case("${VAR_2:lower}", Ok("val2"))]
+    #[case("${var1:title}", Ok("Val1"))]
+    #[case("${2:upper}", Ok("NVAL"))]
+    #[case("${var1:upper}_${var1}", Ok("VAL1_val1"))]
+    #[case("${NO:upper}", Ok(""))]
+    #[case("$var1:upper", Ok("val1:upper"))]
+    #[case("${var1:UPPER}", Err(VariableExpressionError::UnknownModifier("UPPER".to_owned())))]
+    #[case("${var1:nope}", Err(VariableExpressionError::UnknownModifier("nope".to_owned())))]
+    #[case(
+        "${var1:}",
+        Err(VariableExpressionError::UnknownModifier(String::new()))
+    )]
+    #[case("${var1:upper:-x}", Err(VariableExpressionError::UnknownModifier("upper:-x".to_owned())))]
+    #[case("${var1:upper", Err(VariableExpressionError::MismatchedBraces("var1".to_owned())))]
+    //
     // Remaining edge cases
     // Aborting a (brace) start
     #[case("$?", Ok("$?"))]
     #[case("${?", Ok("${?"))]
-    fn test_inject_variables(
+    fn test_scan_and_render_variables(
         #[case] expression: &str,
         #[case] expected: Result<&str, VariableExpressionError>,
         variables: Variables<'_>,
     ) {
-        let result = inject_variables(expression, &variables);
+        let result =
+            scan_variables(expression).map(|positions| render(expression, &positions, &variables));
         let expected = expected.map(str::to_owned);
 
         assert_eq!(result, expected);
     }
 
     #[rstest]
-    #[case(0, 1)]
-    #[case(1, 1)]
-    #[case(9, 1)]
-    #[case(10, 2)]
-    #[case(99, 2)]
-    #[case(100, 3)]
-    fn test_width(#[case] num: usize, #[case] expected: usize) {
-        assert_eq!(width(num), expected);
+    #[case("", vec![])]
+    #[case("no variables", vec![])]
+    #[case("a$", vec![])]
+    #[case("$?", vec![])]
+    #[case("${?", vec![])]
+    #[case("$$", vec![(0..2, Substitution::Dollar)])]
+    #[case("$$$", vec![(0..2, Substitution::Dollar)])]
+    #[case("$a", vec![(0..2, Substitution::named("a".to_owned()))])]
+    #[case("$a b", vec![(0..2, Substitution::named("a".to_owned()))])]
+    #[case("x$ab_1-", vec![(1..6, Substitution::named("ab_1".to_owned()))])]
+    #[case("$12x", vec![(0..3, Substitution::numbered(12))])]
+    #[case("${a}b", vec![(0..4, Substitution::named("a".to_owned()))])]
+    #[case("${a}}", vec![(0..4, Substitution::named("a".to_owned()))])]
+    #[case("${12}3", vec![(0..5, Substitution::numbered(12))])]
+    #[case(
+        "$a$1",
+        vec![
+            (0..2, Substitution::named("a".to_owned())),
+            (2..4, Substitution::numbered(1)),
+        ]
+    )]
+    #[case(
+        "$a$$",
+        vec![
+            (0..2, Substitution::named("a".to_owned())),
+            (2..4, Substitution::Dollar),
+        ]
+    )]
+    #[case(
+        "${NO:-x}",
+        vec![(
+            0..8,
+            Substitution::Variable {
+                group: CaptureGroup::Named("NO".to_owned()),
+                default: Some("x".to_owned()),
+                modifier: None,
+            },
+        )]
+    )]
+    #[case(
+        "${1:title}",
+        vec![(
+            0..10,
+            Substitution::Variable {
+                group: CaptureGroup::Numbered(1),
+                default: None,
+                modifier: Some(Modifier::Title),
+            },
+        )]
+    )]
+    #[case(
+        "a $1 ${b}$$ü$c",
+        vec![
+            (2..4, Substitution::numbered(1)),
+            (5..9, Substitution::named("b".to_owned())),
+            (9..11, Substitution::Dollar),
+            (13..15, Substitution::named("c".to_owned())),
+        ]
+    )]
+    #[case(
+        "ü${a:-ö}$1",
+        vec![
+            (
+                2..10,
+                Substitution::Variable {
+                    group: CaptureGroup::Named("a".to_owned()),
+                    default: Some("ö".to_owned()),
+                    modifier: None,
+                },
+            ),
+            (10..12, Substitution::numbered(1)),
+        ]
+    )]
+    #[case(
+        "${a:lower}$1",
+        vec![
+            (
+                0..10,
+                Substitution::Variable {
+                    group: CaptureGroup::Named("a".to_owned()),
+                    default: None,
+                    modifier: Some(Modifier::Lower),
+                },
+            ),
+            (10..12, Substitution::numbered(1)),
+        ]
+    )]
+    fn test_scan_variables(
+        #[case] expression: &str,
+        #[case] expected: Vec<(Range<usize>, Substitution)>,
+    ) {
+        assert_eq!(scan_variables(expression), Ok(VariablePositions(expected)));
     }
 }
@@ -0,0 +1,69 @@
+use log::info;
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::Action;
+
+/// Reverses anything in scope, by user-perceived character.
+///
+/// Reversal works on extended grapheme clusters, not on bytes or [`char`]s, so
+/// characters made up of several code points, like letters with combining accents or
+/// emoji sequences, stay intact.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, Reverse};
+///
+/// let action = Reverse::default();
+/// assert_eq!(action.act("Hello, World!"), "!dlroW ,olleH");
+/// assert_eq!(action.act("🇩🇪 🇫🇷"), "🇫🇷 🇩🇪");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Reverse {}
+
+impl Action for Reverse {
+    fn act(&self, input: &str) -> String {
+        info!("Reversing: '{}'", input);
+        input.graphemes(true).rev().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("a", "a")]
+    #[case("abc", "cba")]
+    #[case("a b\nc", "c\nb a")]
+    #[case("Größe", "eßörG")]
+    #[case("\r\n", "\r\n")] // Single cluster
+    //
+    // Regional indicator pairs (flags)
+    #[case("🇩🇪", "🇩🇪")]
+    #[case("🇩🇪🇫🇷", "🇫🇷🇩🇪")]
+    //
+    // Combining characters: `e` followed by U+0301 COMBINING ACUTE ACCENT
+    #[case("cafe\u{301}", "e\u{301}fac")]
+    #[case("e\u{301}a", "ae\u{301}")]
+    //
+    // Zero-width joiner sequences
+    #[case("👩‍👩‍👧!", "!👩‍👩‍👧")]
+    fn test_reverse(#[case] input: &str, #[case] expected: &str) {
+        let result = Reverse::default().act(input);
+
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case("abc")]
+    #[case("🇩🇪 cafe\u{301} 👩‍👩‍👧")]
+    fn test_reverse_is_involution(#[case] input: &str) {
+        let action = Reverse::default();
+
+        assert_eq!(action.act(&action.act(input)), input);
+    }
+}
@@ -0,0 +1,117 @@
+use super::Action;
+
+/// Converts separators between segments of paths, such as file paths (`a/b/c`) and
+/// module paths (`a::b::c` in Rust, `a.b.c` in Python or Java).
+///
+/// Only separators actually *separating* two segments are converted, that is those
+/// with a letter, digit or underscore immediately on both sides. Other occurrences,
+/// like the ones in `a / b`, `//` or `./a`, are left alone.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, ConvertSeparators};
+///
+/// let action = ConvertSeparators::new("/", "::");
+/// assert_eq!(action.act("crate/io/error"), "crate::io::error");
+///
+/// let action = ConvertSeparators::new(".", "/");
+/// assert_eq!(action.act("os.path.join"), "os/path/join");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertSeparators {
+    from: String,
+    to: String,
+}
+
+impl ConvertSeparators {
+    /// Creates a new instance converting `from` separators into `to` separators.
+    #[must_use]
+    pub fn new(from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+}
+
+/// Whether `c` can border a separator, as part of a segment.
+fn is_segment_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl Action for ConvertSeparators {
+    fn act(&self, input: &str) -> String {
+        if self.from.is_empty() {
+            return input.to_owned();
+        }
+
+        let mut out = String::with_capacity(input.len());
+        let mut pieces = input.split(self.from.as_str());
+        let mut previous = pieces.next().unwrap_or_default();
+        out.push_str(previous);
+
+        for piece in pieces {
+            let separates = previous.chars().next_back().is_some_and(is_segment_char)
+                && piece.chars().next().is_some_and(is_segment_char);
+
+            out.push_str(if separates { &self.to } else { &self.from });
+            out.push_str(piece);
+            previous = piece;
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    // Rust
+    #[case::path_to_rust("/", "::", "a/b/c", "a::b::c")]
+    #[case::rust_to_path("::", "/", "a::b::c", "a/b/c")]
+    #[case::rust_in_sentence("/", "::", "see std/io/Error.", "see std::io::Error.")]
+    #[case::rust_hyphenated("/", "::", "my-crate/src", "my-crate::src")]
+    // Python, Java
+    #[case::path_to_python("/", ".", "os/path/join", "os.path.join")]
+    #[case::python_to_path(".", "/", "org.example.App", "org/example/App")]
+    #[case::unicode(".", "/", "größe.maß", "größe/maß")]
+    // Only separators between segments converted
+    #[case::leading("/", "::", "/a/b", "/a::b")]
+    #[case::trailing("/", "::", "a/b/", "a::b/")]
+    #[case::relative("/", "::", "./a/b", "./a::b")]
+    #[case::url("/", "::", "https://x.org/a", "https://x.org::a")]
+    // Untouched
+    #[case::spaced("/", "::", "a / b", "a / b")]
+    #[case::doubled("/", "::", "a//b", "a//b")]
+    #[case::empty_input("/", "::", "", "")]
+    #[case::empty_separator("", "::", "a/b", "a/b")]
+    fn test_convert_separators(
+        #[case] from: &str,
+        #[case] to: &str,
+        #[case] input: &str,
+        #[case] expected: &str,
+    ) {
+        let action = ConvertSeparators::new(from, to);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case("/", "::", "a/b/c")]
+    #[case(".", "/", "a.b.c")]
+    fn test_convert_separators_roundtrip(
+        #[case] from: &str,
+        #[case] to: &str,
+        #[case] input: &str,
+    ) {
+        let there = ConvertSeparators::new(from, to).act(input);
+        let back = ConvertSeparators::new(to, from).act(&there);
+
+        assert_eq!(back, input);
+    }
+}
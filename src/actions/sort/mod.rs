@@ -0,0 +1,274 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use clap::ValueEnum;
+
+use super::Action;
+
+/// The order to sort in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum SortOrder {
+    /// Smallest first, like `a, b, c`.
+    #[default]
+    Ascending,
+    /// Largest first, like `c, b, a`.
+    Descending,
+}
+
+/// Sorts lines.
+///
+/// Lines comparing equal, such as exact duplicates or lines differing in case only
+/// when sorting case-insensitively, keep their original relative order (the sort is
+/// stable). Nothing is removed. Lines keep their line ending (`\n` or `\r\n`) as
+/// they move. A trailing line ending is kept if present, and not added otherwise.
+///
+/// Lines are separated by newlines by default. Any other separator, such as NUL for
+/// the output of `find -print0`, can be set using [`SortLines::with_separator`].
+///
+/// Numeric-aware sorting compares runs of ASCII digits by their value, so that
+/// `item2` sorts before `item10`.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, SortLines, SortOrder};
+///
+/// let action = SortLines::new(SortOrder::Ascending, false, false);
+/// assert_eq!(action.act("b\nc\na\n"), "a\nb\nc\n");
+///
+/// let action = SortLines::new(SortOrder::Descending, false, true);
+/// assert_eq!(action.act("v2\nv10\nv1"), "v10\nv2\nv1");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortLines {
+    order: SortOrder,
+    case_insensitive: bool,
+    numeric: bool,
+    separator: char,
+}
+
+impl Default for SortLines {
+    fn default() -> Self {
+        Self::new(SortOrder::default(), false, false)
+    }
+}
+
+impl SortLines {
+    /// Creates a new instance sorting in the given `order`, optionally ignoring case
+    /// and comparing numbers by value.
+    #[must_use]
+    pub const fn new(order: SortOrder, case_insensitive: bool, numeric: bool) -> Self {
+        Self {
+            order,
+            case_insensitive,
+            numeric,
+            separator: '\n',
+        }
+    }
+
+    /// Returns this instance, but sorting records separated by `separator` instead of
+    /// lines.
+    #[must_use]
+    pub const fn with_separator(self, separator: char) -> Self {
+        Self { separator, ..self }
+    }
+
+    /// Compares two lines according to the configured options, in ascending order.
+    fn compare(self, a: &str, b: &str) -> Ordering {
+        let (a, b): (Cow<'_, str>, Cow<'_, str>) = if self.case_insensitive {
+            (a.to_lowercase().into(), b.to_lowercase().into())
+        } else {
+            (a.into(), b.into())
+        };
+
+        if self.numeric {
+            compare_numeric(&a, &b)
+        } else {
+            a.cmp(&b)
+        }
+    }
+}
+
+/// Compares `a` and `b` chunk-wise, where runs of ASCII digits compare by value and
+/// anything else as usual.
+fn compare_numeric(a: &str, b: &str) -> Ordering {
+    let mut a = chunks(a);
+    let mut b = chunks(b);
+
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ordering = match (is_number(x), is_number(y)) {
+                    (true, true) => {
+                        let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                        x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+                    }
+                    _ => x.cmp(y),
+                };
+
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        }
+    }
+}
+
+/// Whether `chunk` (as produced by [`chunks`]) is a number.
+fn is_number(chunk: &str) -> bool {
+    chunk.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Splits `s` into alternating runs of ASCII digits and anything else.
+fn chunks(s: &str) -> impl Iterator<Item = &str> {
+    let mut rest = s;
+
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let digits = first.is_ascii_digit();
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() != digits)
+            .unwrap_or(rest.len());
+
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+
+        Some(chunk)
+    })
+}
+
+/// Splits `input` into records terminated by `separator`, as `(record, terminator)`.
+///
+/// For a newline `separator`, a preceding `\r` is part of the terminator. The
+/// terminator of the last record is empty if `input` doesn't end in one.
+fn terminated_records(input: &str, separator: char) -> Vec<(&str, &str)> {
+    input
+        .split_inclusive(separator)
+        .map(|record| {
+            let Some(content) = record.strip_suffix(separator) else {
+                return (record, "");
+            };
+            let content = match content.strip_suffix('\r') {
+                Some(content) if separator == '\n' => content,
+                _ => content,
+            };
+
+            (content, &record[content.len()..])
+        })
+        .collect()
+}
+
+impl Action for SortLines {
+    fn act(&self, input: &str) -> String {
+        let mut lines = terminated_records(input, self.separator);
+        lines.sort_by(|(a, _), (b, _)| match self.order {
+            SortOrder::Ascending => self.compare(a, b),
+            SortOrder::Descending => self.compare(b, a),
+        });
+
+        // A last line lacking a line ending has to stay last in that regard: swap
+        // endings with whichever line ended up last instead.
+        if let Some(i) = lines.iter().position(|(_, ending)| ending.is_empty()) {
+            let last = lines.len() - 1;
+            lines[i].1 = lines[last].1;
+            lines[last].1 = "";
+        }
+
+        lines.into_iter().flat_map(<[&str; 2]>::from).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("", "")]
+    #[case::newline_only("\n", "\n")]
+    #[case::single("a", "a")]
+    #[case::trailing_newline_kept("b\na\n", "a\nb\n")]
+    #[case::no_trailing_newline_added("b\na", "a\nb")]
+    #[case::crlf("b\r\na\r\n", "a\r\nb\r\n")]
+    #[case::mixed_endings_kept("c\nb\r\na\n", "a\nb\r\nc\n")]
+    #[case::mixed_endings_no_trailing("c\r\nb\na", "a\r\nb\nc")]
+    #[case::duplicates_kept("b\na\nb\na\n", "a\na\nb\nb\n")]
+    #[case::uppercase_first("b\nB\na\n", "B\na\nb\n")]
+    #[case::lexicographic_numbers("item10\nitem2\nitem1\n", "item1\nitem10\nitem2\n")]
+    #[case::imports(
+        "use std::io;\nuse anyhow::Result;\nuse clap::Parser;\n",
+        "use anyhow::Result;\nuse clap::Parser;\nuse std::io;\n"
+    )]
+    fn test_sort_lines_ascending(#[case] input: &str, #[case] expected: &str) {
+        let action = SortLines::new(SortOrder::Ascending, false, false);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case::plain(SortOrder::Descending, false, false, "a\nc\nb\n", "c\nb\na\n")]
+    #[case::case_insensitive(SortOrder::Ascending, true, false, "b\nA\nc\n", "A\nb\nc\n")]
+    #[case::numeric(
+        SortOrder::Ascending,
+        false,
+        true,
+        "item10\nitem2\nitem1\n",
+        "item1\nitem2\nitem10\n"
+    )]
+    #[case::numeric_leading_zeros(
+        SortOrder::Ascending,
+        false,
+        true,
+        "x010\nx9\nx0011\n",
+        "x9\nx010\nx0011\n"
+    )]
+    #[case::numeric_mixed(SortOrder::Ascending, false, true, "a\n10\n9\nb1\n", "9\n10\na\nb1\n")]
+    #[case::numeric_descending(SortOrder::Descending, false, true, "2\n10\n1\n", "10\n2\n1\n")]
+    #[case::all(SortOrder::Descending, true, true, "A2\na10\nB1\n", "B1\na10\nA2\n")]
+    fn test_sort_lines_options(
+        #[case] order: SortOrder,
+        #[case] case_insensitive: bool,
+        #[case] numeric: bool,
+        #[case] input: &str,
+        #[case] expected: &str,
+    ) {
+        let action = SortLines::new(order, case_insensitive, numeric);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    // Lines comparing equal keep their original order, in either direction.
+    #[case::ascending(SortOrder::Ascending, "b\nA\na\nB\nA\n", "A\na\nA\nb\nB\n")]
+    #[case::descending(SortOrder::Descending, "b\nA\na\nB\nA\n", "b\nB\nA\na\nA\n")]
+    fn test_sort_lines_stable_on_duplicates(
+        #[case] order: SortOrder,
+        #[case] input: &str,
+        #[case] expected: &str,
+    ) {
+        let action = SortLines::new(order, true, false);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case::nul("b\0c\0a\0", "a\0b\0c\0")]
+    #[case::nul_no_trailing("b\0a", "a\0b")]
+    #[case::newlines_within_records("b\nx\0a\ny\0", "a\ny\0b\nx\0")]
+    fn test_sort_lines_separator(#[case] input: &str, #[case] expected: &str) {
+        let action = SortLines::default().with_separator('\0');
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[test]
+    fn test_sort_lines_numeric_stable_on_equal_values() {
+        let action = SortLines::new(SortOrder::Ascending, false, true);
+
+        assert_eq!(action.act("x01\nx1\nx001\n"), "x01\nx1\nx001\n");
+    }
+}
@@ -0,0 +1,128 @@
+use super::strings::collapse_whitespace;
+use super::Action;
+
+/// What to squeeze.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SqueezeMode {
+    /// Collapse runs of whitespace into a single space, and trim leading and trailing
+    /// whitespace.
+    #[default]
+    Whitespace,
+    /// Collapse runs of three or more line breaks (ignoring whitespace in between)
+    /// into two, leaving at most a single blank line.
+    BlankLines,
+}
+
+/// Squeezes whitespace, according to a [`SqueezeMode`].
+///
+/// Whitespace is anything [`char::is_whitespace`] considers such, including Unicode
+/// whitespace like non-breaking spaces.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, Squeeze, SqueezeMode};
+///
+/// let action = Squeeze::new(SqueezeMode::Whitespace);
+/// assert_eq!(action.act("  Hello, \t\n World!  "), "Hello, World!");
+///
+/// let action = Squeeze::new(SqueezeMode::BlankLines);
+/// assert_eq!(action.act("a\n\n\n\nb\n"), "a\n\nb\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Squeeze {
+    mode: SqueezeMode,
+}
+
+impl Squeeze {
+    /// Creates a new instance squeezing according to `mode`.
+    #[must_use]
+    pub const fn new(mode: SqueezeMode) -> Self {
+        Self { mode }
+    }
+}
+
+/// Collapses runs of three or more line breaks into two, see
+/// [`SqueezeMode::BlankLines`].
+fn squeeze_blank_lines(input: &str) -> String {
+    let line_ending = if input.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(first) = rest.find('\n') {
+        // Extend across all whitespace, up to and including the last line break in
+        // it. Anything after (indentation) belongs to the next line.
+        let run = &rest[first..];
+        let whitespace = run.find(|c: char| !c.is_whitespace()).unwrap_or(run.len());
+        let end = first + run[..whitespace].rfind('\n').map_or(0, |last| last + 1);
+
+        let breaks = rest[first..end].matches('\n').count();
+        if breaks >= 3 {
+            // A `\r` right before the first break is part of the line ending.
+            out.push_str(rest[..first].strip_suffix('\r').unwrap_or(&rest[..first]));
+            out.push_str(&line_ending.repeat(2));
+        } else {
+            out.push_str(&rest[..end]);
+        }
+
+        rest = &rest[end..];
+    }
+
+    out.push_str(rest);
+
+    out
+}
+
+impl Action for Squeeze {
+    fn act(&self, input: &str) -> String {
+        match self.mode {
+            SqueezeMode::Whitespace => collapse_whitespace(input.split_inclusive(|_| true)),
+            SqueezeMode::BlankLines => squeeze_blank_lines(input),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("", "")]
+    #[case::whitespace_only(" \t\n ", "")]
+    #[case::untouched("a b", "a b")]
+    #[case::trimmed("  a b  ", "a b")]
+    #[case::collapsed("a \t  b\n\nc", "a b c")]
+    #[case::non_breaking_space("a\u{a0}\u{a0}b\u{a0}", "a b")]
+    #[case::ideographic_space("a\u{3000}b", "a b")]
+    #[case::comment(
+        "// This   comment was\n//   generated.  ",
+        "// This comment was // generated."
+    )]
+    fn test_squeeze_whitespace(#[case] input: &str, #[case] expected: &str) {
+        let action = Squeeze::new(SqueezeMode::Whitespace);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case::empty("", "")]
+    #[case::single_blank_line_untouched("a\n\nb\n", "a\n\nb\n")]
+    #[case::no_blank_line_untouched("a\nb", "a\nb")]
+    #[case::two_blank_lines("a\n\n\nb", "a\n\nb")]
+    #[case::many_blank_lines("a\n\n\n\n\n\nb\n", "a\n\nb\n")]
+    #[case::whitespace_only_lines("a\n  \n\t\n\nb", "a\n\nb")]
+    #[case::indentation_kept("    a\n\n\n\n    b", "    a\n\n    b")]
+    #[case::trailing_whitespace_kept("a  \n\n\nb", "a  \n\nb")]
+    #[case::leading("\n\n\na", "\n\na")]
+    #[case::trailing("a\n\n\n", "a\n\n")]
+    #[case::crlf("a\r\n\r\n\r\n\r\nb\r\n", "a\r\n\r\nb\r\n")]
+    #[case::spaces_untouched("a    b", "a    b")]
+    #[case::non_breaking_space_line("a\n\u{a0}\n\nb", "a\n\nb")]
+    fn test_squeeze_blank_lines(#[case] input: &str, #[case] expected: &str) {
+        let action = Squeeze::new(SqueezeMode::BlankLines);
+
+        assert_eq!(action.act(input), expected);
+    }
+}
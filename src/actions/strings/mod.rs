@@ -0,0 +1,360 @@
+use clap::ValueEnum;
+
+use super::Action;
+
+/// How consecutive pieces of a split string literal are joined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Concatenation {
+    /// Adjacent literals, which the compiler joins, as in C, C++ and Python:
+    /// `"abc" "def"`.
+    #[default]
+    Adjacent,
+    /// The `+` operator, as in Java, JavaScript, C# or Go: `"abc" + "def"`.
+    Plus,
+}
+
+/// Splits over-long string literals into multiple concatenated ones.
+///
+/// Operates on double-quoted string literals, *including* their quotes: anything in
+/// scope not looking like one is left alone. Literals fitting into the configured
+/// width are not touched either. Otherwise, the literal is broken into pieces, each
+/// on its own line and no wider than the width (including quotes and operators),
+/// preferring to break after whitespace. Escape sequences are never split.
+///
+/// Lines after the first are not indented, as the original column is unknown.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, Concatenation, RewrapStrings};
+///
+/// let action = RewrapStrings::new(12, Concatenation::Plus);
+/// assert_eq!(
+///     action.act(r#""Hello there, World""#),
+///     "\"Hello \" +\n\"there, \" +\n\"World\""
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RewrapStrings {
+    width: usize,
+    concatenation: Concatenation,
+}
+
+impl RewrapStrings {
+    /// Creates a new instance splitting literals wider than `width` characters,
+    /// joining the pieces as indicated.
+    #[must_use]
+    pub const fn new(width: usize, concatenation: Concatenation) -> Self {
+        Self {
+            width,
+            concatenation,
+        }
+    }
+}
+
+impl Default for RewrapStrings {
+    fn default() -> Self {
+        Self::new(80, Concatenation::default())
+    }
+}
+
+impl Action for RewrapStrings {
+    fn act(&self, input: &str) -> String {
+        let Some(content) = input
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .filter(|s| !s.is_empty())
+        else {
+            return input.to_owned();
+        };
+
+        if input.chars().count() <= self.width {
+            return input.to_owned();
+        }
+
+        let (separator, operator_width) = match self.concatenation {
+            Concatenation::Adjacent => ("\n", 0),
+            Concatenation::Plus => (" +\n", " +".len()),
+        };
+        // Two for the quotes; every piece but the last also carries the operator.
+        // Always allow at least a single unit per piece, to guarantee progress.
+        let budget = self.width.saturating_sub(2 + operator_width).max(1);
+
+        let pieces = split(content, budget);
+        if pieces.len() < 2 {
+            return input.to_owned();
+        }
+
+        pieces
+            .iter()
+            .map(|piece| format!("\"{piece}\""))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
+
+/// Collapses runs of whitespace inside string literals into a single space, and trims
+/// leading and trailing whitespace.
+///
+/// Operates on the *content* of string literals. If the input is an entire literal
+/// including its quotes, as scoped by the prepared `strings` queries of for example C,
+/// C#, Go, Java and Ruby, the quotes (and any prefix, like `L"` or `@"`) are kept and
+/// only the content between them is collapsed and trimmed. Escape sequences (like `\n`,
+/// or a backslash followed by a line break) are never touched, nor is anything else but
+/// whitespace. Some grammars split content at escape sequences or interpolations, in
+/// which case each piece is collapsed and trimmed on its own.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, CollapseStringWhitespace};
+///
+/// let action = CollapseStringWhitespace::default();
+/// assert_eq!(action.act("  Hello, \t  World!\\n "), "Hello, World!\\n");
+/// assert_eq!(action.act("\"  Hello,  World! \""), "\"Hello, World!\"");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CollapseStringWhitespace {}
+
+impl Action for CollapseStringWhitespace {
+    fn act(&self, input: &str) -> String {
+        let (opening, content, closing) = delimiters(input);
+
+        format!("{opening}{}{closing}", collapse_whitespace(units(content)))
+    }
+}
+
+/// Joins `units`, collapsing runs of whitespace-only units into a single space and
+/// dropping leading and trailing ones.
+pub(super) fn collapse_whitespace<'a, I: IntoIterator<Item = &'a str>>(units: I) -> String {
+    let mut out = String::new();
+    let mut pending_space = false;
+
+    for unit in units {
+        if unit.chars().all(char::is_whitespace) {
+            pending_space = true;
+            continue;
+        }
+
+        if pending_space && !out.is_empty() {
+            out.push(' ');
+        }
+        pending_space = false;
+
+        out.push_str(unit);
+    }
+
+    out
+}
+
+/// Splits a complete string literal into its opening delimiter (quotes plus any short
+/// prefix, like `L"`, `@"` or `"""`), content and closing delimiter. Input which isn't
+/// enclosed in matching quotes is all content.
+fn delimiters(input: &str) -> (&str, &str, &str) {
+    let body = input.trim_start_matches(|c: char| c.is_ascii_alphanumeric() || "@$".contains(c));
+    let prefix = input.len() - body.len();
+
+    let Some(quote) = body.chars().next().filter(|c| "\"'`".contains(*c)) else {
+        return ("", input, "");
+    };
+    let inner = body.trim_matches(quote);
+    if prefix > 3 || inner.is_empty() {
+        return ("", input, "");
+    }
+
+    let leading = body.len() - body.trim_start_matches(quote).len();
+    let trailing = body.len() - body.trim_end_matches(quote).len();
+    let n = leading.min(trailing);
+    if n == 0 {
+        return ("", input, "");
+    }
+
+    let (opening, rest) = input.split_at(prefix + n);
+    let (content, closing) = rest.split_at(rest.len() - n);
+    (opening, content, closing)
+}
+
+/// Splits `content` into pieces of at most `budget` characters (unless a single unit
+/// is wider), preferring to break right after whitespace.
+fn split(content: &str, budget: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+
+    let mut start = 0;
+    let mut width = 0;
+    // Byte offset and piece width right after the last whitespace in the current
+    // piece.
+    let mut last_break: Option<(usize, usize)> = None;
+
+    for unit in units(content) {
+        let unit_width = unit.chars().count();
+        let unit_start = unit.as_ptr() as usize - content.as_ptr() as usize;
+
+        while width > 0 && width + unit_width > budget {
+            let (end, end_width) = last_break.unwrap_or((unit_start, width));
+            pieces.push(&content[start..end]);
+            start = end;
+            width -= end_width;
+            last_break = None;
+        }
+
+        width += unit_width;
+
+        if unit.chars().all(char::is_whitespace) {
+            last_break = Some((unit_start + unit.len(), width));
+        }
+    }
+
+    if start < content.len() {
+        pieces.push(&content[start..]);
+    }
+
+    pieces
+}
+
+/// Splits a string literal's content into units which must not be broken up: single
+/// characters and entire escape sequences.
+fn units(content: &str) -> impl Iterator<Item = &str> {
+    let mut rest = content;
+
+    std::iter::from_fn(move || {
+        let mut chars = rest.char_indices();
+        let (_, c) = chars.next()?;
+
+        let len = if c == '\\' {
+            match chars.next() {
+                Some((i, 'x')) => {
+                    i + 1 + count_leading(&rest[i + 1..], char::is_ascii_hexdigit, usize::MAX)
+                }
+                Some((i, 'u')) => i + 1 + count_leading(&rest[i + 1..], char::is_ascii_hexdigit, 4),
+                Some((i, 'U')) => i + 1 + count_leading(&rest[i + 1..], char::is_ascii_hexdigit, 8),
+                Some((i, '0'..='7')) => {
+                    i + count_leading(&rest[i..], |c| matches!(c, '0'..='7'), 3)
+                }
+                Some((i, c)) => i + c.len_utf8(),
+                None => 1,
+            }
+        } else {
+            c.len_utf8()
+        };
+
+        let (unit, remainder) = rest.split_at(len);
+        rest = remainder;
+        Some(unit)
+    })
+}
+
+/// Byte length of the leading ASCII characters matching `predicate`, at most `max`.
+fn count_leading(s: &str, predicate: impl Fn(&char) -> bool, max: usize) -> usize {
+    s.chars().take_while(predicate).take(max).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::fits(r#""short""#, 20, r#""short""#)]
+    #[case::not_a_literal(
+        "some identifier which is rather long",
+        10,
+        "some identifier which is rather long"
+    )]
+    #[case::empty(r#""""#, 1, r#""""#)]
+    #[case::break_after_whitespace(
+        r#""The quick brown fox jumps""#,
+        12,
+        "\"The quick \"\n\"brown fox \"\n\"jumps\""
+    )]
+    #[case::hard_break_without_whitespace(r#""abcdefghij""#, 6, "\"abcd\"\n\"efgh\"\n\"ij\"")]
+    #[case::escapes_kept_whole(r#""ab\ncd\x41gh""#, 6, "\"ab\\n\"\n\"cd\"\n\"\\x41\"\n\"gh\"")]
+    #[case::octal_escape_kept_whole(r#""a\101b""#, 6, "\"a\"\n\"\\101\"\n\"b\"")]
+    #[case::multibyte(r#""äöü ßäö""#, 6, "\"äöü \"\n\"ßäö\"")]
+    fn test_rewrap_c_adjacent(#[case] input: &str, #[case] width: usize, #[case] expected: &str) {
+        let action = RewrapStrings::new(width, Concatenation::Adjacent);
+
+        let result = action.act(input);
+        assert_eq!(result, expected);
+
+        if result == input {
+            return;
+        }
+
+        for line in result.lines() {
+            assert!(
+                line.chars().count() <= width,
+                "line '{line}' exceeds width {width}"
+            );
+        }
+    }
+
+    #[rstest]
+    #[case::fits(r#""short""#, 20, r#""short""#)]
+    #[case::break_after_whitespace(
+        r#""The quick brown fox jumps over""#,
+        14,
+        "\"The quick \" +\n\"brown fox \" +\n\"jumps over\""
+    )]
+    #[case::hard_break_without_whitespace(r#""abcdefghij""#, 8, "\"abcd\" +\n\"efgh\" +\n\"ij\"")]
+    #[case::escaped_quote_kept_whole(
+        r#""say \"hi\" now""#,
+        8,
+        "\"say \" +\n\"\\\"hi\" +\n\"\\\" \" +\n\"now\""
+    )]
+    fn test_rewrap_java_plus(#[case] input: &str, #[case] width: usize, #[case] expected: &str) {
+        let action = RewrapStrings::new(width, Concatenation::Plus);
+
+        let result = action.act(input);
+        assert_eq!(result, expected);
+
+        if result == input {
+            return;
+        }
+
+        for line in result.lines() {
+            assert!(
+                line.chars().count() <= width,
+                "line '{line}' exceeds width {width}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rewrap_is_idempotent_per_piece() {
+        let action = RewrapStrings::new(12, Concatenation::Adjacent);
+
+        let result = action.act(r#""The quick brown fox jumps""#);
+        for line in result.lines() {
+            assert_eq!(action.act(line), line);
+        }
+    }
+
+    #[rstest]
+    #[case::empty("", "")]
+    #[case::untouched("Hello, World", "Hello, World")]
+    #[case::collapse_runs("a  b\t\tc", "a b c")]
+    #[case::collapse_mixed("a \t\n b", "a b")]
+    #[case::trim("  a b  ", "a b")]
+    #[case::only_whitespace(" \t ", "")]
+    #[case::unicode_whitespace("a\u{a0}\u{2003}b", "a b")]
+    #[case::escapes_kept(r"a \n  \t b", r"a \n \t b")]
+    #[case::escaped_quote_kept(r#"say  \"hi\"  "#, r#"say \"hi\""#)]
+    #[case::escaped_backslash_kept(r"a\\  b", r"a\\ b")]
+    #[case::line_continuation_kept("a \\\n  b", "a \\\n b")]
+    // Entire literals, including quotes
+    #[case::quoted(r#""  a  b  ""#, r#""a b""#)]
+    #[case::quoted_single("'  a '", "'a'")]
+    #[case::quoted_backtick("`  a `", "`a`")]
+    #[case::quoted_prefixed(r#"L"  a ""#, r#"L"a""#)]
+    #[case::quoted_verbatim(r#"@"  a ""#, r#"@"a""#)]
+    #[case::quoted_triple("\"\"\"\n  a\n  \"\"\"", "\"\"\"a\"\"\"")]
+    #[case::quoted_empty(r#""""#, r#""""#)]
+    #[case::quoted_blank(r#"" ""#, r#""""#)]
+    #[case::quoted_escaped_quote_kept(r#"" a \" ""#, r#""a \"""#)]
+    #[case::unbalanced_quote("\"  a  ", "\" a")]
+    #[case::long_prefix_not_quote(r#"word"  a  ""#, r#"word" a ""#)]
+    fn test_collapse_string_whitespace(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(CollapseStringWhitespace::default().act(input), expected);
+    }
+}
@@ -0,0 +1,110 @@
+use log::info;
+
+use super::{Action, Replacement};
+use crate::scoping::regex::{capture_context, capture_groups, CaptureGroup};
+use crate::RegexPattern;
+
+/// Replaces all matches of a regex pattern *within* input, like `sed 's/.../.../g'`.
+///
+/// As opposed to [`Replacement`], which replaces its entire input, only the parts of
+/// the input matching [`Substitution::new`]'s pattern are replaced. This matters if
+/// input (say, an entire comment found via a language query) contains multiple
+/// matches. Variables in the replacement refer to the capture groups of *this*
+/// pattern.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::RegexPattern;
+/// use srgn::actions::{Action, Replacement, Substitution};
+///
+/// let action = Substitution::new(
+///     RegexPattern::new(r"(\w+)@(\w+)").unwrap(),
+///     Replacement::try_from("$2 at $1".to_owned()).unwrap(),
+/// );
+///
+/// assert_eq!(
+///     action.act("// Ask bob@home or alice@work"),
+///     "// Ask home at bob or work at alice"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Substitution {
+    pattern: RegexPattern,
+    captures: Vec<CaptureGroup>,
+    replacement: Replacement,
+}
+
+impl Substitution {
+    /// Creates a new substitution replacing all matches of `pattern` with
+    /// `replacement`.
+    #[must_use]
+    pub fn new(pattern: RegexPattern, replacement: Replacement) -> Self {
+        let captures = capture_groups(&pattern);
+
+        Self {
+            pattern,
+            captures,
+            replacement,
+        }
+    }
+}
+
+impl Action for Substitution {
+    fn act(&self, input: &str) -> String {
+        info!("Substituting matches of '{}' in '{}'", self.pattern, input);
+
+        let mut out = String::with_capacity(input.len());
+        let mut last = 0;
+
+        for cap in self.pattern.captures_iter(input) {
+            // Same as for scoping: blow up on purpose instead of silently continuing.
+            let cap = cap.unwrap_or_else(|e| panic!("regex exceeded runtime limits: {e}"));
+            let whole = cap
+                .get(0)
+                .expect("index 0 guaranteed to contain whole match");
+
+            out.push_str(&input[last..whole.start()]);
+            out.push_str(
+                &self
+                    .replacement
+                    .render(&capture_context(&self.captures, &cap)),
+            );
+            last = whole.end();
+        }
+
+        out.push_str(&input[last..]);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::no_match("hello", "x", "y", "hello")]
+    #[case::single_match("hello", "l+", "L", "heLo")]
+    #[case::all_matches("a-b-c", "-", "+", "a+b+c")]
+    #[case::numbered_variables("k=v, x=y", r"(\w)=(\w)", "$2=$1", "v=k, y=x")]
+    #[case::named_variables("f(a)", r"(?<name>\w)\((?<arg>\w)\)", "$arg.$name()", "a.f()")]
+    #[case::whole_match("ab", "[ab]", "<$0>", "<a><b>")]
+    #[case::optional_group_is_empty("ab a", r"a(b)?", "[$1]", "[b] []")]
+    #[case::empty_matches("ab", "x*", "-", "-a-b-")]
+    #[case::unicode("über öl", "ö|ü", "oe", "oeber oel")]
+    fn test_substitution(
+        #[case] input: &str,
+        #[case] pattern: &str,
+        #[case] replacement: &str,
+        #[case] expected: &str,
+    ) {
+        let action = Substitution::new(
+            RegexPattern::new(pattern).unwrap(),
+            Replacement::try_from(replacement.to_owned()).unwrap(),
+        );
+
+        assert_eq!(action.act(input), expected);
+    }
+}
@@ -0,0 +1,285 @@
+use itertools::Itertools;
+
+use super::Action;
+
+/// Aligns and formats Markdown tables.
+///
+/// Column widths are normalized, cells padded and the delimiter row (`|---|---|`)
+/// rewritten, all according to the alignment markers found in the delimiter row
+/// (`:---`, `:---:`, `---:`). Ragged rows are padded with empty cells to the widest
+/// row.
+///
+/// Any lines not part of a table (a block of consecutive lines containing `|`, with
+/// a delimiter row in second position) are left alone, so this action can be applied
+/// to entire documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MarkdownTable {}
+
+/// Alignment of a table column, as indicated by the delimiter row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Alignment {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+impl Alignment {
+    /// Parses a single delimiter row cell, like `:---`.
+    fn parse(cell: &str) -> Option<Self> {
+        let left = cell.starts_with(':');
+        let right = cell.ends_with(':');
+        let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+
+        if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+            return None;
+        }
+
+        Some(match (left, right) {
+            (false, false) => Self::None,
+            (true, false) => Self::Left,
+            (true, true) => Self::Center,
+            (false, true) => Self::Right,
+        })
+    }
+
+    fn delimiter(self, width: usize) -> String {
+        match self {
+            Self::None => "-".repeat(width),
+            Self::Left => format!(":{}", "-".repeat(width - 1)),
+            Self::Center => format!(":{}:", "-".repeat(width - 2)),
+            Self::Right => format!("{}:", "-".repeat(width - 1)),
+        }
+    }
+
+    fn pad(self, cell: &str, width: usize) -> String {
+        let padding = width.saturating_sub(cell.chars().count());
+
+        match self {
+            Self::None | Self::Left => format!("{cell}{}", " ".repeat(padding)),
+            Self::Right => format!("{}{cell}", " ".repeat(padding)),
+            Self::Center => {
+                let left = padding / 2;
+                format!("{}{cell}{}", " ".repeat(left), " ".repeat(padding - left))
+            }
+        }
+    }
+}
+
+/// The minimum column width, such that delimiters like `:-:` still fit.
+const MIN_WIDTH: usize = 3;
+
+/// Splits a table row into its trimmed cells, honoring escaped pipes (`\|`).
+fn cells(row: &str) -> Vec<&str> {
+    let row = row.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = match row.strip_suffix('|') {
+        Some(stripped) if !stripped.ends_with('\\') => stripped,
+        _ => row,
+    };
+
+    let mut cells = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+
+    for (i, c) in row.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '|' => {
+                cells.push(row[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    cells.push(row[start..].trim());
+
+    cells
+}
+
+/// Formats a single table, given as its lines (without line endings). Returns `None`
+/// if the lines do not form a table.
+fn format_table(lines: &[&str]) -> Option<Vec<String>> {
+    let [header, delimiter, body @ ..] = lines else {
+        return None;
+    };
+
+    let alignments = cells(delimiter)
+        .into_iter()
+        .map(Alignment::parse)
+        .collect::<Option<Vec<_>>>()?;
+
+    let rows = std::iter::once(header)
+        .chain(body)
+        .map(|row| cells(row))
+        .collect_vec();
+
+    let n_columns = rows
+        .iter()
+        .map(Vec::len)
+        .chain([alignments.len()])
+        .max()
+        .unwrap_or_default();
+
+    let alignment = |i: usize| alignments.get(i).copied().unwrap_or(Alignment::None);
+    let widths = (0..n_columns)
+        .map(|i| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.chars().count())
+                .chain([MIN_WIDTH])
+                .max()
+                .unwrap_or(MIN_WIDTH)
+        })
+        .collect_vec();
+
+    let indent = &header[..header.len() - header.trim_start().len()];
+    let render = |cells: Vec<String>| format!("{indent}| {} |", cells.join(" | "));
+
+    let mut res = Vec::with_capacity(lines.len());
+    let mut rows = rows.into_iter();
+
+    let render_row = |row: Vec<&str>| {
+        render(
+            (0..n_columns)
+                .map(|i| alignment(i).pad(row.get(i).copied().unwrap_or_default(), widths[i]))
+                .collect(),
+        )
+    };
+
+    res.push(render_row(rows.next().expect("header to be present")));
+    res.push(render(
+        (0..n_columns)
+            .map(|i| alignment(i).delimiter(widths[i]))
+            .collect(),
+    ));
+    res.extend(rows.map(render_row));
+
+    Some(res)
+}
+
+impl Action for MarkdownTable {
+    fn act(&self, input: &str) -> String {
+        let mut out = String::with_capacity(input.len());
+
+        // Group into blocks of consecutive lines which look like they might belong to a
+        // table, and all others.
+        for (is_table, block) in &input
+            .split_inclusive('\n')
+            .chunk_by(|line| line.contains('|'))
+        {
+            let block = block.collect_vec();
+
+            let formatted = if is_table {
+                let lines = block
+                    .iter()
+                    .map(|line| line.trim_end_matches(['\n', '\r']))
+                    .collect_vec();
+
+                format_table(&lines)
+            } else {
+                None
+            };
+
+            match formatted {
+                Some(formatted) => {
+                    for (line, original) in formatted.into_iter().zip(block) {
+                        out.push_str(&line);
+                        // Restore original line ending, if any
+                        out.push_str(&original[original.trim_end_matches(['\n', '\r']).len()..]);
+                    }
+                }
+                None => out.extend(block),
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("no table here\n", "no table here\n")]
+    #[case("a | b\n", "a | b\n")] // No delimiter row
+    #[case("a | b\nc | d\n", "a | b\nc | d\n")] // Not a delimiter row
+    //
+    #[case(
+        "|a|b|\n|-|-|\n|ccc|dddd|\n",
+        "| a   | b    |\n| --- | ---- |\n| ccc | dddd |\n"
+    )]
+    #[case(
+        // Outer pipes optional; no trailing newline
+        "a|b\n-|-\nccc|dddd",
+        "| a   | b    |\n| --- | ---- |\n| ccc | dddd |"
+    )]
+    #[case(
+        // Already formatted: idempotent
+        "| a   | b    |\n| --- | ---- |\n| ccc | dddd |\n",
+        "| a   | b    |\n| --- | ---- |\n| ccc | dddd |\n"
+    )]
+    fn test_markdown_table_basic(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(MarkdownTable::default().act(input), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "| Left | Center | Right |\n|:-|:-:|-:|\n| a | b | c |\n| longer | longest cell | x |\n",
+        concat!(
+            "| Left   |    Center    | Right |\n",
+            "| :----- | :----------: | ----: |\n",
+            "| a      |      b       |     c |\n",
+            "| longer | longest cell |     x |\n",
+        )
+    )]
+    #[case("|x|\n|:--:|\n|abcd|\n", "|  x   |\n| :--: |\n| abcd |\n")]
+    fn test_markdown_table_alignment(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(MarkdownTable::default().act(input), expected);
+    }
+
+    #[rstest]
+    #[case(
+        // Body rows shorter and longer than header
+        "| a | b |\n|---|---:|\n| 1 |\n| 1 | 2 | 3 |\n",
+        concat!(
+            "| a   |   b |     |\n",
+            "| --- | --: | --- |\n",
+            "| 1   |     |     |\n",
+            "| 1   |   2 | 3   |\n",
+        )
+    )]
+    #[case(
+        // Header shorter than delimiter row
+        "| a |\n|---|---|\n| 1 | 2 |\n",
+        "| a   |     |\n| --- | --- |\n| 1   | 2   |\n"
+    )]
+    fn test_markdown_table_ragged(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(MarkdownTable::default().act(input), expected);
+    }
+
+    #[rstest]
+    #[case(
+        // Surrounding prose is left alone, line endings preserved
+        "Intro:\r\n\r\n|a|b|\r\n|-|-|\r\n|1|2|\r\n\r\nOutro.\r\n",
+        "Intro:\r\n\r\n| a   | b   |\r\n| --- | --- |\r\n| 1   | 2   |\r\n\r\nOutro.\r\n"
+    )]
+    #[case(
+        // Escaped pipes are not cell separators
+        "|a|b|\n|-|-|\n|x \\| y|z|\n",
+        "| a      | b   |\n| ------ | --- |\n| x \\| y | z   |\n"
+    )]
+    #[case(
+        // Indentation is kept
+        "  |a|\n  |-|\n",
+        "  | a   |\n  | --- |\n"
+    )]
+    fn test_markdown_table_in_document(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(MarkdownTable::default().act(input), expected);
+    }
+}
@@ -0,0 +1,153 @@
+use super::Action;
+
+/// Expands tabs into spaces, except inside string and character literals.
+///
+/// Naive tab expansion corrupts literals: a tab inside `"a\tb"`-like source text (a
+/// *literal* tab, not the escape sequence) is part of the string's value. This action
+/// uses a quick, language-agnostic lexer to only expand tabs in code, preserving
+/// literal contents byte-for-byte:
+///
+/// - double-quoted (`"..."`) and backtick (`` `...` ``) literals may span lines,
+/// - single-quoted (`'...'`) literals are only recognized if closed on the same line,
+///   so that apostrophes in comments and lifetimes like `'a` are not mistaken for
+///   literals,
+/// - backslash escapes are honored in all literals.
+///
+/// Tabs are expanded to the next tab stop. Columns are counted from the start of the
+/// input and of each line, so scope should cover entire lines.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, ExpandTabs};
+///
+/// let action = ExpandTabs::new(4);
+/// assert_eq!(action.act("\tx = \"a\tb\";"), "    x = \"a\tb\";");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpandTabs {
+    width: usize,
+}
+
+impl ExpandTabs {
+    /// Creates a new instance expanding tabs to tab stops every `width` columns.
+    ///
+    /// A `width` of zero removes tabs outside of literals.
+    #[must_use]
+    pub const fn new(width: usize) -> Self {
+        Self { width }
+    }
+}
+
+impl Default for ExpandTabs {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+impl Action for ExpandTabs {
+    fn act(&self, input: &str) -> String {
+        let mut out = String::with_capacity(input.len());
+
+        let mut column = 0;
+        // The quote character of the literal we're currently in, if any.
+        let mut literal: Option<char> = None;
+        let mut escaped = false;
+
+        for (i, c) in input.char_indices() {
+            match c {
+                '\t' => {
+                    let spaces = if self.width == 0 {
+                        0
+                    } else {
+                        self.width - column % self.width
+                    };
+                    column += spaces;
+
+                    if literal.is_some() {
+                        out.push(c);
+                    } else {
+                        out.extend(std::iter::repeat(' ').take(spaces));
+                    }
+                    escaped = false;
+                    continue;
+                }
+                '\n' => column = 0,
+                _ => column += 1,
+            }
+
+            out.push(c);
+
+            match literal {
+                Some(_) if escaped => escaped = false,
+                Some(_) if c == '\\' => escaped = true,
+                Some(quote) if c == quote => literal = None,
+                None if c == '"' || c == '`' => literal = Some(c),
+                None if c == '\'' && closes_on_same_line(&input[i + 1..]) => {
+                    literal = Some(c);
+                }
+                Some(_) | None => {}
+            }
+        }
+
+        out
+    }
+}
+
+/// Whether the rest of a single-quoted literal contains its closing quote before the
+/// end of the line.
+fn closes_on_same_line(rest: &str) -> bool {
+    let mut escaped = false;
+
+    for c in rest.chars() {
+        match c {
+            '\n' => return false,
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '\'' => return true,
+            _ => {}
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("", "")]
+    #[case::no_tabs("let x = 1;", "let x = 1;")]
+    #[case::leading_tabs("\t\tx", "        x")]
+    #[case::tab_stops("a\tbc\tdefg\th", "a   bc  defg    h")]
+    #[case::code_and_double_quoted("\tlet s = \"a\tb\";\t// done", "    let s = \"a\tb\"; // done")]
+    #[case::code_and_single_quoted("\tc = '\t';\tx", "    c = '\t';  x")]
+    #[case::escaped_quote_in_literal("\"a\\\"\tb\"\tc", "\"a\\\"\tb\"  c")]
+    #[case::escaped_backslash_closes_literal("\"\\\\\"\tc", "\"\\\\\"    c")]
+    #[case::multiline_literal(
+        "s = \"\"\"\n\tkept\n\"\"\"\n\texpanded",
+        "s = \"\"\"\n\tkept\n\"\"\"\n    expanded"
+    )]
+    #[case::backtick_literal("\tq := `a\tb`", "    q := `a\tb`")]
+    #[case::apostrophe_in_comment("\t// don't\tdo this\n\tx", "    // don't    do this\n    x")]
+    #[case::rust_lifetime("fn f<'a>(x: &'a str) {\n\tx\n}", "fn f<'a>(x: &'a str) {\n    x\n}")]
+    #[case::columns_count_through_literals("\"ab\"\tc", "\"ab\"    c")]
+    fn test_expand_tabs(#[case] input: &str, #[case] expected: &str) {
+        let action = ExpandTabs::new(4);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case(0, "\ta\t\"\t\"", "a\"\t\"")]
+    #[case(1, "\ta\t\"\t\"", " a \"\t\"")]
+    #[case(8, "\ta", "        a")]
+    fn test_expand_tabs_widths(#[case] width: usize, #[case] input: &str, #[case] expected: &str) {
+        let action = ExpandTabs::new(width);
+
+        assert_eq!(action.act(input), expected);
+    }
+}
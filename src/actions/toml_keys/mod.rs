@@ -0,0 +1,236 @@
+use itertools::Itertools;
+
+use super::Action;
+
+/// Sorts the keys of TOML tables alphabetically, such as the entries of a `Cargo.toml`
+/// `[dependencies]` table.
+///
+/// Works line-wise. Table headers (`[dependencies]`) and blank lines stay in place and
+/// separate groups of entries, which are sorted individually. Comment lines stick with
+/// the entry below them. Values spanning multiple lines, such as inline tables or
+/// arrays wrapping using brackets and multi-line strings (`"""`, `'''`), are kept
+/// together. Lines are moved as-is, keeping their indentation and any trailing
+/// whitespace.
+///
+/// Best applied to a scope containing only the tables to sort, as for example
+/// `[package]` keys are conventionally *not* sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SortTomlKeys {}
+
+impl SortTomlKeys {
+    /// Splits a group of lines into entries, each made up of any leading comment lines,
+    /// the line containing the key, and any bracketed continuation lines.
+    fn entries<'a>(lines: &[&'a str]) -> Vec<Vec<&'a str>> {
+        let mut entries = Vec::new();
+        let mut current = Vec::new();
+        let mut nesting = Nesting::default();
+
+        for line in lines {
+            current.push(*line);
+
+            if nesting.is_top_level() && line.trim_start().starts_with('#') {
+                // Comment; belongs to whatever comes next.
+                continue;
+            }
+
+            nesting.update(line);
+
+            if nesting.is_top_level() {
+                entries.push(std::mem::take(&mut current));
+                nesting = Nesting::default();
+            }
+        }
+
+        if !current.is_empty() {
+            // Unbalanced or comment-only tail, keep as-is.
+            entries.push(current);
+        }
+
+        entries
+    }
+
+    /// Sorts a group of lines by the keys of its entries. Lines not belonging to any
+    /// entry go last.
+    fn sorted<'a>(group: &[&'a str]) -> Vec<&'a str> {
+        let (entries, tail): (Vec<_>, Vec<_>) = Self::entries(group)
+            .into_iter()
+            .partition(|entry| Self::key(entry).is_some());
+
+        entries
+            .into_iter()
+            .sorted_by(|a, b| Self::key(a).cmp(&Self::key(b)))
+            .chain(tail)
+            .flatten()
+            .collect()
+    }
+
+    /// The key of an entry, without surrounding quotes, for sorting.
+    fn key<'a>(entry: &[&'a str]) -> Option<&'a str> {
+        let line = entry
+            .iter()
+            .find(|line| !line.trim_start().starts_with('#'))?;
+        let (key, _) = line.split_once('=')?;
+
+        Some(key.trim().trim_matches(['"', '\'']))
+    }
+}
+
+/// Nesting state across lines: open brackets and multi-line strings, ignoring
+/// brackets inside strings and comments.
+#[derive(Debug, Default)]
+struct Nesting {
+    depth: isize,
+    string: Option<&'static str>,
+}
+
+impl Nesting {
+    /// Whether a line starting in this state starts a new entry.
+    const fn is_top_level(&self) -> bool {
+        self.depth <= 0 && self.string.is_none()
+    }
+
+    /// Advances the state over `line`.
+    fn update(&mut self, line: &str) {
+        let mut quote = None;
+        let mut i = 0;
+
+        while let Some(c) = line[i..].chars().next() {
+            let rest = &line[i..];
+
+            match (self.string, quote, c) {
+                (Some(r#"""""#), _, '\\') | (None, Some('"'), '\\') => {
+                    // Escaped, whatever it is
+                    i += c.len_utf8() + rest[1..].chars().next().map_or(0, char::len_utf8);
+                    continue;
+                }
+                (Some(delimiter), _, _) if rest.starts_with(delimiter) => {
+                    self.string = None;
+                    i += delimiter.len();
+                    continue;
+                }
+                (None, Some(q), c) if c == q => quote = None,
+                (None, None, '"' | '\'') => {
+                    let delimiter = if c == '"' { r#"""""# } else { "'''" };
+                    if rest.starts_with(delimiter) {
+                        self.string = Some(delimiter);
+                        i += delimiter.len();
+                        continue;
+                    }
+                    quote = Some(c);
+                }
+                (None, None, '#') => break,
+                (None, None, '[' | '{') => self.depth += 1,
+                (None, None, ']' | '}') => self.depth -= 1,
+                _ => {}
+            }
+
+            i += c.len_utf8();
+        }
+    }
+}
+
+/// Whether `line` is a table header like `[dependencies]` or `[[bin]]`.
+fn is_table_header(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('[') && !line.contains('=')
+}
+
+impl Action for SortTomlKeys {
+    fn act(&self, input: &str) -> String {
+        let line_ending = if input.contains("\r\n") { "\r\n" } else { "\n" };
+        let trailing_line_ending = input.ends_with('\n');
+
+        let mut out = Vec::new();
+        let mut group = Vec::new();
+        let mut nesting = Nesting::default();
+
+        for line in input.lines() {
+            if nesting.is_top_level() && (line.trim().is_empty() || is_table_header(line)) {
+                out.extend(Self::sorted(&group));
+                group.clear();
+                out.push(line);
+                continue;
+            }
+
+            nesting.update(line);
+            if nesting.is_top_level() {
+                nesting = Nesting::default();
+            }
+            group.push(line);
+        }
+        out.extend(Self::sorted(&group));
+
+        let mut res = out.join(line_ending);
+        if trailing_line_ending {
+            res.push_str(line_ending);
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("\n", "\n")]
+    #[case("a = 1", "a = 1")]
+    #[case("[dependencies]\n", "[dependencies]\n")]
+    //
+    #[case("b = 1\na = 2\n", "a = 2\nb = 1\n")]
+    #[case(
+        // Mixed simple and inline-table entries; comments stick with their entry
+        "[dependencies]\nserde = { version = \"1\", features = [\"derive\"] }\n# Pinned, see #123\nanyhow = \"1\"\nitertools = \"0.13\"\n",
+        "[dependencies]\n# Pinned, see #123\nanyhow = \"1\"\nitertools = \"0.13\"\nserde = { version = \"1\", features = [\"derive\"] }\n"
+    )]
+    #[case(
+        // Multi-line values stick together
+        "[dependencies]\nclap = { version = \"4\", features = [\n    \"derive\",\n    \"env\",\n] }\nbase64 = \"0.22\"\n",
+        "[dependencies]\nbase64 = \"0.22\"\nclap = { version = \"4\", features = [\n    \"derive\",\n    \"env\",\n] }\n"
+    )]
+    #[case(
+        // Brackets inside strings and comments don't count
+        "b = \"[\" # ]\na = 1\n",
+        "a = 1\nb = \"[\" # ]\n"
+    )]
+    #[case(
+        // Tables are sorted individually, headers and blank lines stay in place
+        "[dependencies]\nb = 1\na = 1\n\nd = 1\nc = 1\n\n[dev-dependencies]\nf = 1\ne = 1\n",
+        "[dependencies]\na = 1\nb = 1\n\nc = 1\nd = 1\n\n[dev-dependencies]\ne = 1\nf = 1\n"
+    )]
+    #[case(
+        // Quoted keys sort by their contents
+        "\"b\" = 1\na = 1\n",
+        "a = 1\n\"b\" = 1\n"
+    )]
+    #[case(
+        // Trailing comment without entry stays at the end
+        "b = 1\na = 1\n# The end\n",
+        "a = 1\nb = 1\n# The end\n"
+    )]
+    #[case("b = 1\r\na = 1\r\n", "a = 1\r\nb = 1\r\n")]
+    #[case(
+        // Multi-line strings stick together, whatever their lines look like
+        "b = \"\"\"\nz = 1\n\n[table]\n# not a comment\n\"\"\"\na = 1\n",
+        "a = 1\nb = \"\"\"\nz = 1\n\n[table]\n# not a comment\n\"\"\"\n"
+    )]
+    #[case(
+        // Literal multi-line strings, and escaped quotes in basic ones
+        "c = '''\n]\n'''\nb = \"\"\"\n\\\"\"\"\n\"\"\"\na = 1\n",
+        "a = 1\nb = \"\"\"\n\\\"\"\"\n\"\"\"\nc = '''\n]\n'''\n"
+    )]
+    #[case(
+        // Indentation and trailing whitespace are kept
+        "[a]\n  b = 1  \n  a = \"\"\"\n    x  \n  \"\"\"\n",
+        "[a]\n  a = \"\"\"\n    x  \n  \"\"\"\n  b = 1  \n"
+    )]
+    fn test_sort_toml_keys(#[case] input: &str, #[case] expected: &str) {
+        let result = SortTomlKeys::default().act(input);
+
+        assert_eq!(result, expected);
+    }
+}
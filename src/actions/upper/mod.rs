@@ -1,6 +1,9 @@
 use super::Action;
 
 /// Renders in uppercase.
+///
+/// Unlike [`str::to_uppercase`], `ß` becomes the capital `ẞ` instead of `SS`, so
+/// [lowercasing](super::Lower) again restores it.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct Upper {}
 
@@ -0,0 +1,114 @@
+use log::{info, warn};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use super::Action;
+
+/// Characters *not* to percent-encode: the 'unreserved' ones as per
+/// [RFC 3986](https://datatracker.ietf.org/doc/html/rfc3986#section-2.3).
+const UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Percent-encodes everything but unreserved characters (alphanumerics, `-`, `.`, `_`
+/// and `~`), as used in URLs.
+///
+/// Non-ASCII characters are encoded as their UTF-8 bytes.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, UrlEncode};
+///
+/// assert_eq!(UrlEncode::default().act("a b/ä"), "a%20b%2F%C3%A4");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UrlEncode {}
+
+impl Action for UrlEncode {
+    fn act(&self, input: &str) -> String {
+        info!("Percent-encoding: '{}'", input);
+        utf8_percent_encode(input, UNRESERVED).to_string()
+    }
+}
+
+/// Decodes percent-encoded (`%XX`) sequences, as used in URLs.
+///
+/// Malformed sequences (like `%ZZ`) are left untouched. Input which does not decode to
+/// valid UTF-8 is returned unchanged.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::actions::{Action, UrlDecode};
+///
+/// assert_eq!(UrlDecode::default().act("a%20b%2F%C3%A4"), "a b/ä");
+/// assert_eq!(UrlDecode::default().act("100%ZZ"), "100%ZZ");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UrlDecode {}
+
+impl Action for UrlDecode {
+    fn act(&self, input: &str) -> String {
+        info!("Percent-decoding: '{}'", input);
+
+        match percent_decode_str(input).decode_utf8() {
+            Ok(decoded) => decoded.into_owned(),
+            Err(e) => {
+                warn!("Decoded input is not valid UTF-8, leaving unchanged: '{input}' ({e})");
+                input.to_owned()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("abcXYZ019", "abcXYZ019")]
+    #[case("-._~", "-._~")]
+    #[case("a b", "a%20b")]
+    #[case("a+b=c&d", "a%2Bb%3Dc%26d")]
+    #[case("https://x.org/?q=1", "https%3A%2F%2Fx.org%2F%3Fq%3D1")]
+    #[case("100%", "100%25")]
+    #[case("äß", "%C3%A4%C3%9F")]
+    #[case("🎉", "%F0%9F%8E%89")]
+    fn test_url_encode(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(UrlEncode::default().act(input), expected);
+    }
+
+    #[rstest]
+    #[case("", "")]
+    #[case("no escapes", "no escapes")]
+    #[case("a%20b", "a b")]
+    #[case("a%2bb%2Bc", "a+b+c")]
+    #[case("%C3%A4%C3%9F", "äß")]
+    // `+` is not special outside of form encoding
+    #[case("a+b", "a+b")]
+    // Malformed sequences are left intact
+    #[case("%ZZ", "%ZZ")]
+    #[case("100%", "100%")]
+    #[case("%2", "%2")]
+    #[case("%2%20", "%2 ")]
+    // Not valid UTF-8 once decoded
+    #[case("%FF%20", "%FF%20")]
+    fn test_url_decode(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(UrlDecode::default().act(input), expected);
+    }
+
+    #[rstest]
+    #[case("https://example.com/path?query=a b&x=ä#frag")]
+    #[case("%ZZ 100% -._~")]
+    #[case("Grüße 你好 🎉")]
+    fn test_url_roundtrip(#[case] input: &str) {
+        let encoded = UrlEncode::default().act(input);
+
+        assert_eq!(UrlDecode::default().act(&encoded), input);
+    }
+}
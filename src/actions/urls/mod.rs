@@ -0,0 +1,195 @@
+use std::sync::LazyLock;
+
+use clap::ValueEnum;
+use fancy_regex::Regex;
+
+use super::Action;
+
+/// Matches, in order of precedence: already-wrapped Markdown links, already-wrapped
+/// autolinks (`<https://...>`), and finally bare URLs, which are the only ones
+/// captured.
+static URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?x)
+        \[[^\]]*\]\([^)\s]*\)
+        |
+        <[a-zA-Z][a-zA-Z0-9+.-]*://[^>\s]*>
+        |
+        (?<url>\b(?:https?|ftp)://[^\s<>]+)
+        ",
+    )
+    .expect("URL regex to be valid")
+});
+
+/// How to wrap bare URLs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum UrlWrapping {
+    /// Wrap in angle brackets, like `<https://example.com>`.
+    #[default]
+    Angle,
+    /// Turn into a Markdown link, like `[https://example.com](https://example.com)`.
+    Link,
+}
+
+/// Wraps bare URLs, leaving already-wrapped ones alone.
+///
+/// URLs already in angle brackets or part of a Markdown link are not touched, so
+/// applying this action repeatedly is a no-op. Trailing punctuation (a sentence's
+/// final period, an unbalanced closing parenthesis, ...) is not considered part of
+/// the URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WrapUrls {
+    wrapping: UrlWrapping,
+}
+
+impl WrapUrls {
+    /// Creates a new instance wrapping URLs as indicated.
+    #[must_use]
+    pub const fn new(wrapping: UrlWrapping) -> Self {
+        Self { wrapping }
+    }
+}
+
+impl Action for WrapUrls {
+    fn act(&self, input: &str) -> String {
+        let mut out = String::with_capacity(input.len());
+        let mut last = 0;
+
+        for caps in URL.captures_iter(input) {
+            let Ok(caps) = caps else {
+                // Backtracking limit exceeded; leave the rest untouched.
+                break;
+            };
+
+            let Some(m) = caps.name("url") else {
+                // Already wrapped.
+                continue;
+            };
+
+            let url = trim_trailing_punctuation(m.as_str());
+
+            out.push_str(&input[last..m.start()]);
+            match self.wrapping {
+                UrlWrapping::Angle => {
+                    out.push('<');
+                    out.push_str(url);
+                    out.push('>');
+                }
+                UrlWrapping::Link => {
+                    out.push('[');
+                    out.push_str(url);
+                    out.push_str("](");
+                    out.push_str(url);
+                    out.push(')');
+                }
+            }
+            last = m.start() + url.len();
+        }
+
+        out.push_str(&input[last..]);
+        out
+    }
+}
+
+/// Strips characters off the end of `url` which are much more likely to belong to
+/// the surrounding prose than to the URL itself.
+///
+/// Closing brackets are only stripped if unbalanced, such that URLs like
+/// `https://en.wikipedia.org/wiki/Rust_(programming_language)` survive intact.
+fn trim_trailing_punctuation(mut url: &str) -> &str {
+    loop {
+        let Some(last) = url.chars().last() else {
+            return url;
+        };
+
+        let strip = match last {
+            '.' | ',' | ':' | ';' | '!' | '?' | '\'' | '"' | '*' | '_' => true,
+            ')' => url.matches('(').count() < url.matches(')').count(),
+            ']' => url.matches('[').count() < url.matches(']').count(),
+            '}' => url.matches('{').count() < url.matches('}').count(),
+            _ => false,
+        };
+
+        if !strip {
+            return url;
+        }
+
+        url = &url[..url.len() - last.len_utf8()];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("no urls here", "no urls here")]
+    #[case("https://example.com", "<https://example.com>")]
+    #[case("see https://example.com now", "see <https://example.com> now")]
+    #[case("ftp://example.com/file", "<ftp://example.com/file>")]
+    #[case(
+        "https://a.com and http://b.org/x?y=1#z",
+        "<https://a.com> and <http://b.org/x?y=1#z>"
+    )]
+    //
+    // Already wrapped
+    #[case("<https://example.com>", "<https://example.com>")]
+    #[case(
+        "[https://example.com](https://example.com)",
+        "[https://example.com](https://example.com)"
+    )]
+    #[case("[docs](https://example.com/docs)", "[docs](https://example.com/docs)")]
+    #[case(
+        "<https://a.com> then https://b.com",
+        "<https://a.com> then <https://b.com>"
+    )]
+    //
+    // Adjacent punctuation
+    #[case("Visit https://example.com.", "Visit <https://example.com>.")]
+    #[case("Visit https://example.com, then", "Visit <https://example.com>, then")]
+    #[case("(see https://example.com)", "(see <https://example.com>)")]
+    #[case("https://example.com/a?!", "<https://example.com/a>?!")]
+    #[case("\"https://example.com\"", "\"<https://example.com>\"")]
+    #[case(
+        "https://en.wikipedia.org/wiki/Rust_(programming_language).",
+        "<https://en.wikipedia.org/wiki/Rust_(programming_language)>."
+    )]
+    //
+    #[case("not a url: https:/example.com", "not a url: https:/example.com")]
+    #[case("xhttps://example.com", "xhttps://example.com")]
+    fn test_wrap_urls_angle(#[case] input: &str, #[case] expected: &str) {
+        let action = WrapUrls::new(UrlWrapping::Angle);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case("https://example.com", "[https://example.com](https://example.com)")]
+    #[case(
+        "Visit https://example.com.",
+        "Visit [https://example.com](https://example.com)."
+    )]
+    #[case("<https://example.com>", "<https://example.com>")]
+    #[case("[docs](https://example.com/docs)", "[docs](https://example.com/docs)")]
+    fn test_wrap_urls_link(#[case] input: &str, #[case] expected: &str) {
+        let action = WrapUrls::new(UrlWrapping::Link);
+
+        assert_eq!(action.act(input), expected);
+    }
+
+    #[rstest]
+    #[case(UrlWrapping::Angle)]
+    #[case(UrlWrapping::Link)]
+    fn test_wrap_urls_is_idempotent(#[case] wrapping: UrlWrapping) {
+        let action = WrapUrls::new(wrapping);
+        let input = "Go to https://example.com. Or (https://example.org/a_(b)), or <https://x.y>!";
+
+        let once = action.act(input);
+        let twice = action.act(&once);
+
+        assert_eq!(once, twice);
+    }
+}
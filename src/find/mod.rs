@@ -1,6 +1,10 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+
+use fancy_regex::Regex;
 
 /// A trait to facilitate finding corresponding, in one sense or another, files.
 ///
@@ -10,6 +14,17 @@ pub trait Find {
     /// The file suffixes aka extensions corresponding files carry.
     fn extensions(&self) -> &'static [&'static str];
 
+    /// Names corresponding files carry, for files identified by name rather than
+    /// extension, like `Dockerfile` or `.gitignore`.
+    ///
+    /// A name also matches with any further suffix, like `Dockerfile.dev`. Names
+    /// containing glob metacharacters, like `*.config.js`, are instead matched as a
+    /// [`glob::Pattern`] against the entire file name. Matching is case-sensitive,
+    /// and takes precedence over [`Find::extensions`].
+    fn filenames(&self) -> &'static [&'static str] {
+        &[]
+    }
+
     /// Valid interpreters as found in the shebang line corresponding to the files.
     ///
     /// For example, in
@@ -25,6 +40,25 @@ pub trait Find {
         None
     }
 
+    /// Language names as found in editor modelines of corresponding files.
+    ///
+    /// For example, in
+    ///
+    /// ```python
+    /// # -*- mode: python -*-
+    /// ```
+    ///
+    /// or
+    ///
+    /// ```python
+    /// # vim: set ft=python:
+    /// ```
+    ///
+    /// the name is `python`. See [`find_modeline`] for details.
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+
     /// Paths matching the criteria will not be considered valid.
     ///
     /// Can be used to override based on arbitrary characteristics of the [`Path`].
@@ -32,27 +66,153 @@ pub trait Find {
         false
     }
 
+    /// Whether `path` matches by its name alone, either by one of [`Find::filenames`]
+    /// or by one of [`Find::extensions`]. The file itself is not accessed.
+    fn matches(&self, path: &Path) -> bool {
+        if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, |name| has_filename(name, self.filenames()))
+        {
+            return true;
+        }
+
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map_or(false, |ext| self.extensions().contains(&ext))
+    }
+
     /// According to the hints and metadata provided by this trait, is the provided
     /// `path` valid?
+    ///
+    /// Files are matched by name, see [`Find::matches`]. Files without extension are
+    /// then inspected for a shebang line, then for an editor modeline.
     fn is_valid_path(&self, path: &Path) -> bool {
+        self.is_valid_path_with(path, true)
+    }
+
+    /// Same as [`Find::is_valid_path`], but allows to turn off modeline detection.
+    fn is_valid_path_with(&self, path: &Path, use_modelines: bool) -> bool {
         if self.is_path_invalid(path) {
             return false;
         }
 
-        match (path.extension(), self.interpreters()) {
-            (Some(ext), _) => ext
-                .to_str()
-                .map_or(false, |ext| self.extensions().contains(&ext)),
-            (None, Some(interpreters)) => File::open(path).map_or(false, |mut fh| {
-                find_interpreter(&mut fh).map_or(false, |interpreter| {
-                    interpreters.contains(&interpreter.as_str())
-                })
-            }),
-            _ => false,
+        if self.matches(path) {
+            return true;
+        }
+
+        if path.extension().is_some() {
+            return false;
+        }
+
+        let use_modelines = use_modelines && !self.modeline_names().is_empty();
+        if self.interpreters().is_none() && !use_modelines {
+            // Save opening the file
+            return false;
+        }
+
+        // Files without extension are rarely large, but don't blow up if they are.
+        let mut source = Vec::new();
+        if File::open(path)
+            .and_then(|fh| fh.take(MAX_INSPECTED_BYTES).read_to_end(&mut source))
+            .is_err()
+        {
+            return false;
+        }
+
+        if let Some(interpreters) = self.interpreters() {
+            if find_interpreter(&mut source.as_slice()).map_or(false, |interpreter| {
+                interpreters.contains(&interpreter.as_str())
+            }) {
+                return true;
+            }
         }
+
+        use_modelines
+            && find_modeline(&String::from_utf8_lossy(&source))
+                .map_or(false, |name| self.modeline_names().contains(&name.as_str()))
     }
 }
 
+/// Whether `name` matches one of `filenames`, see [`Find::filenames`].
+///
+/// Glob patterns are compiled once and cached, as this runs for every path walked.
+fn has_filename(name: &str, filenames: &[&'static str]) -> bool {
+    static PATTERNS: LazyLock<Mutex<HashMap<&str, Option<glob::Pattern>>>> =
+        LazyLock::new(Mutex::default);
+
+    filenames.iter().any(|filename| {
+        if filename.contains(['*', '?', '[']) {
+            return PATTERNS
+                .lock()
+                .expect("no other thread to have panicked holding the lock")
+                .entry(filename)
+                .or_insert_with(|| glob::Pattern::new(filename).ok())
+                .as_ref()
+                .map_or(false, |pattern| pattern.matches(name));
+        }
+
+        name.strip_prefix(filename)
+            .map_or(false, |rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+/// Maximum number of bytes read from files when inspecting their contents.
+const MAX_INSPECTED_BYTES: u64 = 64 * 1024;
+
+/// Number of lines at the start and end of a file searched for modelines. Matches
+/// Vim's default.
+const MODELINE_SEARCH_LINES: usize = 5;
+
+/// Emacs-style modelines, like `-*- mode: python -*-` or `-*- python -*-`.
+static EMACS_MODELINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"-\*-\s*(?:(?:.*?;\s*)?mode:\s*([\w+#.-]+)|([\w+#.-]+))\s*(?:;.*?)?-\*-")
+        .expect("regex to be valid")
+});
+
+/// Vim-style modelines, like `vim: ft=rust` or `vim: set filetype=rust:`.
+static VIM_MODELINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|\s)(?:vim?|ex):(?:.*?[\s:])?(?:ft|filetype|syntax|syn)=([\w+#.-]+)")
+        .expect("regex to be valid")
+});
+
+/// Finds the language name declared in an editor modeline (Emacs or Vim style) in the
+/// first or last few lines of `source`, if any. The name is lowercased.
+///
+/// ## Example
+///
+/// ```rust
+/// use srgn::find::find_modeline;
+///
+/// assert_eq!(find_modeline("# -*- mode: python -*-\n"), Some("python".into()));
+/// assert_eq!(find_modeline("code\n// vim: set ft=rust:\n"), Some("rust".into()));
+/// assert_eq!(find_modeline("no modeline\n"), None);
+/// ```
+#[must_use]
+pub fn find_modeline(source: &str) -> Option<String> {
+    let lines = source.lines().collect::<Vec<_>>();
+    let head = lines.iter().take(MODELINE_SEARCH_LINES);
+    let tail = lines
+        .iter()
+        .skip(MODELINE_SEARCH_LINES)
+        .rev()
+        .take(MODELINE_SEARCH_LINES);
+
+    head.chain(tail).find_map(|line| {
+        let caps = EMACS_MODELINE
+            .captures(line)
+            .ok()
+            .flatten()
+            .or_else(|| VIM_MODELINE.captures(line).ok().flatten())?;
+
+        caps.iter()
+            .skip(1) // Entire match
+            .flatten()
+            .next()
+            .map(|m| m.as_str().to_lowercase())
+    })
+}
+
 pub(crate) fn find_interpreter(source: &mut impl Read) -> Option<String> {
     let mut interpreter = String::new();
     let mut seen_space = false;
@@ -143,4 +303,88 @@ mod test {
             expected
         );
     }
+
+    #[rstest]
+    #[case("Dockerfile", true)]
+    #[case("Dockerfile.dev", true)]
+    #[case("Dockerfile.dev.local", true)]
+    #[case("Containerfile", true)]
+    #[case("dockerfile", false)]
+    #[case("Dockerfiles", false)]
+    #[case("app.Dockerfile", false)]
+    #[case(".gitignore", true)]
+    #[case("a.gitignore", false)]
+    #[case("webpack.config.js", true)]
+    #[case(".config.js", true)]
+    #[case("webpack.config.jsx", false)]
+    #[case("config.js", false)]
+    #[case("", false)]
+    fn test_has_filename(#[case] name: &str, #[case] expected: bool) {
+        assert_eq!(
+            has_filename(
+                name,
+                &["Dockerfile", "Containerfile", ".gitignore", "*.config.js"]
+            ),
+            expected
+        );
+    }
+
+    struct Makefiles;
+
+    impl Find for Makefiles {
+        fn extensions(&self) -> &'static [&'static str] {
+            &["mk"]
+        }
+
+        fn filenames(&self) -> &'static [&'static str] {
+            &["Makefile", "GNUmakefile"]
+        }
+    }
+
+    #[rstest]
+    #[case("Makefile", true)]
+    #[case("sub/dir/GNUmakefile", true)]
+    #[case("Makefile.am", true)]
+    #[case("rules.mk", true)]
+    #[case("sub/dir/rules.mk", true)]
+    #[case("makefile", false)]
+    #[case("Makefile/rules.txt", false)]
+    #[case("rules.txt", false)]
+    #[case("mk", false)]
+    fn test_matches(#[case] path: &str, #[case] expected: bool) {
+        assert_eq!(Makefiles.matches(Path::new(path)), expected);
+    }
+
+    #[rstest]
+    #[case("", None)]
+    #[case("print('hello')", None)]
+    #[case("-*- -*-", None)]
+    #[case("vim:", None)]
+    #[case("# ft=rust", None)]
+    #[case("# novim: ft=rust", None)]
+    //
+    // Emacs
+    #[case("# -*- mode: python -*-", Some("python"))]
+    #[case("# -*-mode:python-*-", Some("python"))]
+    #[case("# -*- python -*-", Some("python"))]
+    #[case("// -*- mode: Rust; indent-tabs-mode: nil -*-", Some("rust"))]
+    #[case("// -*- coding: utf-8; mode: go -*-", Some("go"))]
+    #[case("#!/bin/sh\n# -*- mode: python -*-\n", Some("python"))]
+    //
+    // Vim
+    #[case("# vim: ft=python", Some("python"))]
+    #[case("# vim: set ft=python:", Some("python"))]
+    #[case("// vim: set ts=4 sw=4 filetype=rust :", Some("rust"))]
+    #[case("// vi: syntax=go", Some("go"))]
+    #[case("/* ex: set ft=cs: */", Some("cs"))]
+    #[case("// vim:ft=typescript", Some("typescript"))]
+    //
+    // Position in file
+    #[case("1\n2\n3\n4\n# vim: ft=python\n", Some("python"))]
+    #[case("1\n2\n3\n4\n5\n# vim: ft=python\n", Some("python"))]
+    #[case("1\n2\n3\n4\n5\n# vim: ft=python\n7\n8\n9\n10\n11\n", None)]
+    #[case("1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n// vim: ft=rust", Some("rust"))]
+    fn test_find_modeline(#[case] input: &str, #[case] expected: Option<&str>) {
+        assert_eq!(find_modeline(input), expected.map(ToOwned::to_owned));
+    }
 }
@@ -157,8 +157,12 @@ use crate::{
 pub mod actions;
 /// Utilities around finding files.
 pub mod find;
+/// Chaining scopers and actions into pipelines.
+pub mod pipeline;
 /// Components to work with collections of [`Range`]s.
 pub mod ranges;
+/// Reports extracted from scoped views, such as `TODO` markers.
+pub mod report;
 /// Main components around [`ScopedView`].
 pub mod scoping;
 
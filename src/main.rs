@@ -3,12 +3,14 @@
 //! It mainly draws from `srgn`, the library, for actual implementations. This file then
 //! deals with CLI argument handling, I/O, threading, and more.
 
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::fs::File;
-use std::io::{self, stdout, Read, Write};
+use std::io::{self, stdout, BufRead, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
-use std::{env, fmt};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::{env, fmt, thread};
 
 use anyhow::{Context, Result};
 use colored::{Color, Colorize, Styles};
@@ -16,24 +18,49 @@ use ignore::{WalkBuilder, WalkState};
 use itertools::Itertools;
 use log::{debug, error, info, trace, LevelFilter};
 use pathdiff::diff_paths;
+use serde::Serialize;
+use similar::TextDiff;
 #[cfg(feature = "german")]
 use srgn::actions::German;
+#[cfg(feature = "hash")]
+use srgn::actions::Hash;
 use srgn::actions::{
-    Action, ActionError, Deletion, Lower, Normalization, Replacement, Style, Titlecase, Upper,
+    Action, ActionError, Base64Decode, Base64Encode, CaseConvert, CollapseStringWhitespace,
+    ConvertComments, ConvertProperties, ConvertSeparators, Deletion, ExpandEnv, ExpandTabs,
+    FixMixedIndentation, Indent, Lower, MarkdownTable, Normalization, NormalizeKeywords, NumberOp,
+    PadNumbers, Redact, RegexEscape, Replacement, Reverse, RewrapStrings, SkipIfMatches,
+    SortImports, SortLines, SortTomlKeys, Squeeze, SqueezeMode, Style, Substitution, Titlecase,
+    Upper, UrlDecode, UrlEncode, WrapUrls,
 };
 #[cfg(feature = "symbols")]
 use srgn::actions::{Symbols, SymbolsInversion};
+use srgn::report::{MarkerReport, MixedIndentationReport, Report};
+use srgn::scoping::langs::c::{CQuery, C};
+use srgn::scoping::langs::cpp::{Cpp, CppQuery};
 use srgn::scoping::langs::csharp::{CSharp, CSharpQuery};
+use srgn::scoping::langs::css::{Css, CssQuery};
+use srgn::scoping::langs::dockerfile::{Dockerfile, DockerfileQuery};
 use srgn::scoping::langs::go::{Go, GoQuery};
 use srgn::scoping::langs::hcl::{Hcl, HclQuery};
+use srgn::scoping::langs::html::{Html, HtmlQuery};
+use srgn::scoping::langs::ini::Ini;
+use srgn::scoping::langs::java::{Java, JavaQuery};
+use srgn::scoping::langs::json::{Json, JsonQuery};
+use srgn::scoping::langs::markdown::{Markdown, MarkdownQuery};
+use srgn::scoping::langs::perl::{Perl, PerlQuery};
 use srgn::scoping::langs::python::{Python, PythonQuery};
+use srgn::scoping::langs::ruby::{Ruby, RubyQuery};
 use srgn::scoping::langs::rust::{Rust, RustQuery};
+use srgn::scoping::langs::sql::{Sql, SqlQuery};
+use srgn::scoping::langs::toml::{Toml, TomlQuery};
 use srgn::scoping::langs::typescript::{TypeScript, TypeScriptQuery};
-use srgn::scoping::langs::LanguageScoper;
+use srgn::scoping::langs::yaml::{Yaml, YamlQuery};
+use srgn::scoping::langs::{in_file, FileScoper, LanguageScoper, NthCapture};
 use srgn::scoping::literal::{Literal, LiteralError};
-use srgn::scoping::regex::{Regex, RegexError};
-use srgn::scoping::view::ScopedViewBuilder;
+use srgn::scoping::regex::{self, Regex, RegexError};
+use srgn::scoping::view::{Match, ScopedView, ScopedViewBuilder};
 use srgn::scoping::Scoper;
+use srgn::RegexPattern;
 
 #[allow(clippy::too_many_lines)] // Only slightly above.
 fn main() -> Result<()> {
@@ -67,59 +94,37 @@ fn main() -> Result<()> {
     let mut actions = assemble_actions(&args)?;
     debug!("Done assembling actions.");
 
-    let is_readable_stdin = grep_cli::is_readable_stdin();
-    info!("Detected stdin as readable: {is_readable_stdin}.");
+    debug!("Assembling further pipeline stages.");
+    let stages = assemble_stages(&args)?;
+    debug!(
+        "Done assembling {} further pipeline stage(s).",
+        stages.len()
+    );
 
-    // See where we're reading from
-    let input = match (
-        args.options.stdin_override_to.unwrap_or(is_readable_stdin),
-        args.options.glob.clone(),
-        &language_scopers.is_empty(),
-    ) {
-        // stdin considered viable: always use it.
-        (true, None, _)
-        // Nothing explicitly available: this should open an interactive stdin prompt.
-        | (false, None, true) => Input::Stdin,
-        (true, Some(..), _) => {
-            // Usage error... warn loudly, the user is likely interested.
-            error!("Detected stdin, and request for files: will use stdin and ignore files.");
-            Input::Stdin
-        }
+    let input = select_input(&args, &language_scopers);
 
-        // When a pattern is specified, it takes precedence.
-        (false, Some(pattern), _) => Input::WalkOn(Box::new(move |path| {
-            let res = pattern.matches_path(path);
-            trace!("Path '{}' matches: {}.", path.display(), res);
-            res
-        })),
+    let report = assemble_report(&args);
 
-        // If pattern wasn't manually overridden, consult the language scoper itself, if
-        // any.
-        (false, None, false) => {
-            let language_scopers = Arc::clone(&language_scopers);
-            Input::WalkOn(Box::new(move |path| {
-                // TODO: perform this work only once (it's super fast but in the hot
-                // path).
-                let res = language_scopers
-                    .iter()
-                    .map(|s| s.is_valid_path(path))
-                    .all_equal_value()
-                    .expect("all language scopers to agree on path validity");
+    let audit_log = args
+        .options
+        .audit_log
+        .as_deref()
+        .map(AuditLog::open)
+        .transpose()
+        .context("Failed opening audit log")?;
 
-                trace!(
-                    "Language scoper considers path '{}' valid: {}",
-                    path.display(),
-                    res
-                );
-                res
-            }))
-        },
-    };
+    // Some actions operate on the view as a whole, and aren't regular `Action`s.
+    let has_actions = !actions.is_empty()
+        || args.composable_actions.prepend_attribute.is_some()
+        || !stages.is_empty();
 
     // Only have this kick in if a language scoper is in play; otherwise, we'd just be a
     // poor imitation of ripgrep itself. Plus, this retains the `tr`-like behavior,
-    // setting it apart from other utilities.
-    let search_mode = actions.is_empty() && !language_scopers.is_empty();
+    // setting it apart from other utilities. Reports and patches replace search output.
+    let search_mode = !has_actions
+        && !language_scopers.is_empty()
+        && report.is_none()
+        && args.options.patch.is_none();
 
     if search_mode {
         info!("Will use search mode."); // Modelled after ripgrep!
@@ -136,18 +141,51 @@ fn main() -> Result<()> {
         args.options.fail_none = true;
     }
 
-    if actions.is_empty() && !search_mode {
+    if !has_actions && !search_mode && report.is_none() {
         // Also kind of an error users will likely want to know about.
         error!(
             "No actions specified, and not in search mode. Will return input unchanged, if any."
         );
     }
 
+    let n_threads = args.options.threads.map_or(
+        thread::available_parallelism().map_or(1, std::num::NonZero::get),
+        std::num::NonZero::get,
+    );
+
+    // Where output of file processing goes: usually stdout, but patches can be
+    // redirected.
+    let sink: Mutex<Box<dyn Write + Send>> = Mutex::new(match args.options.patch.as_deref() {
+        Some(path) if path != Path::new("-") => {
+            info!("Will write patch to file: {}", path.display());
+            Box::new(BufWriter::new(
+                File::create(path).context("Failed creating patch file")?,
+            ))
+        }
+        _ => Box::new(stdout()),
+    });
+
+    // Patches are meant for review, so must be reproducible: always walk sorted.
+    let sorted = args.options.sorted || args.options.patch.is_some();
+
     // Now write out
-    match (input, args.options.sorted) {
+    match (input, sorted) {
         (Input::Stdin, _ /* no effect */) => {
+            if args.options.patch.is_some() {
+                // Usage error... warn loudly, the user is likely interested.
+                error!("Detected stdin, but patches require files: will write output as usual.");
+            }
+
             info!("Will read from stdin and write to stdout, applying actions.");
-            handle_actions_on_stdin(&general_scoper, &language_scopers, &actions, &args)?;
+            handle_actions_on_stdin(
+                &general_scoper,
+                &language_scopers,
+                &actions,
+                &stages,
+                &args,
+                report.as_deref(),
+                audit_log.as_ref(),
+            )?;
         }
         (Input::WalkOn(validator), false) => {
             info!("Will walk file tree, applying actions.");
@@ -156,31 +194,104 @@ fn main() -> Result<()> {
                 &general_scoper,
                 &language_scopers,
                 &actions,
+                &stages,
                 &args,
-                search_mode,
-                args.options.threads.map_or(
-                    std::thread::available_parallelism().map_or(1, std::num::NonZero::get),
-                    std::num::NonZero::get,
-                ),
+                // Reports are read-only, so walk as leniently as in search mode.
+                search_mode || report.is_some(),
+                report.as_deref(),
+                audit_log.as_ref(),
+                n_threads,
+                &sink,
             )?;
         }
         (Input::WalkOn(validator), true) => {
-            info!("Will walk file tree, applying actions.");
+            info!("Will walk file tree in sorted order, applying actions.");
             handle_actions_on_many_files_sorted(
                 &validator,
                 &general_scoper,
                 &language_scopers,
                 &actions,
+                &stages,
                 &args,
-                search_mode,
+                // Reports are read-only, so walk as leniently as in search mode.
+                search_mode || report.is_some(),
+                report.as_deref(),
+                audit_log.as_ref(),
+                n_threads,
+                &sink,
             )?;
         }
     };
 
+    sink.into_inner()
+        .expect("no other thread to have panicked holding the lock")
+        .flush()?;
+    audit_log.map(AuditLog::flush).transpose()?;
+
     info!("Done, exiting");
     Ok(())
 }
 
+/// Determines where to read input from.
+fn select_input(args: &cli::Cli, language_scopers: &Arc<Vec<Box<dyn FileScoper>>>) -> Input {
+    let is_readable_stdin = grep_cli::is_readable_stdin();
+    info!("Detected stdin as readable: {is_readable_stdin}.");
+
+    // See where we're reading from
+    let input = match (
+        args.options.stdin_override_to.unwrap_or(is_readable_stdin),
+        args.options.glob.clone(),
+        &language_scopers.is_empty(),
+    ) {
+        // stdin considered viable: always use it.
+        (true, None, _)
+        // Nothing explicitly available: this should open an interactive stdin prompt.
+        | (false, None, true) => Input::Stdin,
+        (true, Some(..), _) => {
+            // Usage error... warn loudly, the user is likely interested.
+            error!("Detected stdin, and request for files: will use stdin and ignore files.");
+            Input::Stdin
+        }
+
+        // When a pattern is specified, it takes precedence.
+        (false, Some(pattern), _) => Input::WalkOn(Box::new(move |path| {
+            let res = pattern.matches_path(path);
+            trace!("Path '{}' matches: {}.", path.display(), res);
+            res
+        })),
+
+        // If pattern wasn't manually overridden, consult the language scoper itself, if
+        // any.
+        (false, None, false) => {
+            let language_scopers = Arc::clone(language_scopers);
+            let use_modelines = !args.options.no_modelines;
+            Input::WalkOn(Box::new(move |path| {
+                // TODO: perform this work only once (it's super fast but in the hot
+                // path).
+                let res = language_scopers
+                    .iter()
+                    .map(|s| s.is_valid_path_with(path, use_modelines))
+                    .all_equal_value()
+                    .expect("all language scopers to agree on path validity");
+
+                trace!(
+                    "Language scoper considers path '{}' valid: {}",
+                    path.display(),
+                    res
+                );
+                res
+            }))
+        },
+    };
+
+    if args.options.stream && !matches!(input, Input::Stdin) {
+        // Usage error... warn loudly, the user is likely interested.
+        error!("Streaming requires stdin, but working on files: will ignore.");
+    }
+
+    input
+}
+
 /// Indicates whether a filesystem path is valid according to some criteria (glob
 /// pattern, ...).
 type Validator = Box<dyn Fn(&Path) -> bool + Send + Sync>;
@@ -195,17 +306,71 @@ enum Input {
     WalkOn(Validator),
 }
 
+/// A further scope -> action pipeline, applied to the output of the previous one
+/// (see `--then`).
+struct Stage {
+    general_scoper: Box<dyn Scoper>,
+    language_scopers: Vec<Box<dyn FileScoper>>,
+    actions: Vec<Box<dyn Action>>,
+    args: cli::Cli,
+}
+
+/// Assembles all further pipeline stages requested via `--then`, in order.
+fn assemble_stages(args: &cli::Cli) -> Result<Vec<Stage>> {
+    args.options
+        .then
+        .iter()
+        .map(|stage_args| {
+            let args = cli::Cli::parse_stage(stage_args)
+                .with_context(|| format!("Failed parsing pipeline stage '{stage_args}'"))?;
+
+            Ok(Stage {
+                general_scoper: get_general_scoper(&args)?,
+                language_scopers: get_language_scopers(&args),
+                actions: assemble_actions(&args)?,
+                args,
+            })
+        })
+        .collect()
+}
+
 /// Main entrypoint for simple `stdin` -> `stdout` processing.
 #[allow(clippy::borrowed_box)] // Used throughout, not much of a pain
 fn handle_actions_on_stdin(
     general_scoper: &Box<dyn Scoper>,
-    language_scopers: &[Box<dyn LanguageScoper>],
+    language_scopers: &[Box<dyn FileScoper>],
     actions: &[Box<dyn Action>],
+    stages: &[Stage],
     args: &cli::Cli,
+    report: Option<&dyn Report>,
+    audit_log: Option<&AuditLog>,
 ) -> Result<(), ProgramError> {
+    if args.options.stream {
+        return handle_actions_on_stdin_streaming(
+            general_scoper,
+            language_scopers,
+            actions,
+            stages,
+            args,
+            report,
+            audit_log,
+        );
+    }
+
     info!("Will use stdin to stdout.");
     let mut source = String::new();
     io::stdin().lock().read_to_string(&mut source)?;
+
+    if let Some(report) = report {
+        let view = build_view(&source, general_scoper, language_scopers, args);
+        let mut stdout = stdout().lock();
+        for finding in report.findings(&view) {
+            writeln!(stdout, "{finding}")?;
+        }
+
+        return Ok(());
+    }
+
     let mut destination = String::new();
 
     apply(
@@ -214,7 +379,9 @@ fn handle_actions_on_stdin(
         general_scoper,
         language_scopers,
         actions,
+        stages,
         args,
+        audit_log.map(|log| (log, None)),
     )?;
 
     stdout().lock().write_all(destination.as_bytes())?;
@@ -222,93 +389,139 @@ fn handle_actions_on_stdin(
     Ok(())
 }
 
-/// Main entrypoint for processing using strictly sequential, *single-threaded*
-/// processing.
+/// Entrypoint for `stdin` -> `stdout` processing, record by record (see `--stream`).
+///
+/// Only a single record is held in memory at a time, bounding memory use for inputs
+/// of any size. Language scopers need the entire input to parse, and reports look at
+/// it as a whole, so neither is supported.
+#[allow(clippy::borrowed_box)] // Used throughout, not much of a pain
+fn handle_actions_on_stdin_streaming(
+    general_scoper: &Box<dyn Scoper>,
+    language_scopers: &[Box<dyn FileScoper>],
+    actions: &[Box<dyn Action>],
+    stages: &[Stage],
+    args: &cli::Cli,
+    report: Option<&dyn Report>,
+    audit_log: Option<&AuditLog>,
+) -> Result<(), ProgramError> {
+    if !language_scopers.is_empty() {
+        return Err(ProgramError::StreamingUnsupported("language scopes"));
+    }
+
+    if report.is_some() {
+        return Err(ProgramError::StreamingUnsupported("reports"));
+    }
+
+    info!("Will stream stdin to stdout, record by record.");
+
+    // Line-based output is only used in search mode, which requires language scopers,
+    // so `apply` seeing one record at a time does not throw off line numbers.
+    let separator = args.options.record_separator;
+    let mut stdin = io::stdin().lock();
+    let mut stdout = stdout().lock();
+
+    let mut record = String::new();
+    let mut destination = String::new();
+    let mut any_in_scope = false;
+
+    while read_record(&mut stdin, separator, &mut record)? {
+        destination.clear();
+
+        match apply(
+            &record,
+            &mut destination,
+            general_scoper,
+            language_scopers,
+            actions,
+            stages,
+            args,
+            audit_log.map(|log| (log, None)),
+        ) {
+            Ok(_) => any_in_scope = true,
+            // Only an error if no record at all turns out to be in scope.
+            Err(ApplicationError::NoneInScope) => destination.push_str(&record),
+            Err(e) => return Err(e.into()),
+        }
+
+        stdout.write_all(destination.as_bytes())?;
+    }
+
+    if args.options.fail_none && !any_in_scope {
+        return Err(ApplicationError::NoneInScope.into());
+    }
+
+    Ok(())
+}
+
+/// Reads the next record, up to and including `separator`, from `source` into
+/// `record`, replacing its contents. Returns whether anything was read, which is
+/// only not the case at the end of input.
+fn read_record(
+    source: &mut impl BufRead,
+    separator: char,
+    record: &mut String,
+) -> io::Result<bool> {
+    let mut separator_bytes = [0; 4];
+    let separator = separator.encode_utf8(&mut separator_bytes).as_bytes();
+    let last = *separator.last().expect("encoded char to be non-empty");
+
+    let mut buffer = std::mem::take(record).into_bytes();
+    buffer.clear();
+
+    // Multi-byte separators end in a byte which might also end other characters, so
+    // keep going until the actual separator is seen.
+    while source.read_until(last, &mut buffer)? > 0 && !buffer.ends_with(separator) {}
+
+    *record =
+        String::from_utf8(buffer).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    Ok(!record.is_empty())
+}
+
+/// Main entrypoint for processing in lexicographically sorted order, by file path.
 ///
-/// If it's good enough for [ripgrep], it's good enough for us :-). Main benefit it full
-/// control of output for testing anyway.
+/// Walking the file tree is cheap and done sequentially upfront, so the sorted order is
+/// known before any work starts. Processing itself is fanned out to `n_threads` workers.
+/// Their output is buffered per file and flushed to stdout strictly in walk order, so
+/// output is identical to a [single-threaded run][ripgrep], just faster. With a single
+/// thread, this is sequential processing.
 ///
 /// [ripgrep]:
 ///     https://github.com/BurntSushi/ripgrep/blob/71d71d2d98964653cdfcfa315802f518664759d7/GUIDE.md#L1016-L1017
 #[allow(clippy::borrowed_box)] // Used throughout, not much of a pain
+#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_arguments)]
 fn handle_actions_on_many_files_sorted(
     validator: &Validator,
     general_scoper: &Box<dyn Scoper>,
-    language_scopers: &[Box<dyn LanguageScoper>],
+    language_scopers: &[Box<dyn FileScoper>],
     actions: &[Box<dyn Action>],
+    stages: &[Stage],
     args: &cli::Cli,
     search_mode: bool,
+    report: Option<&dyn Report>,
+    audit_log: Option<&AuditLog>,
+    n_threads: usize,
+    sink: &Mutex<Box<dyn Write + Send>>,
 ) -> Result<(), ProgramError> {
     let root = env::current_dir()?;
     info!(
-        "Will walk file tree sequentially, in sorted order, starting from: {:?}",
+        "Will walk file tree using {:?} thread(s), in sorted order, starting from: {:?}",
+        n_threads,
         root.canonicalize()
     );
 
-    let mut n_files_processed: usize = 0;
-    let mut n_files_seen: usize = 0;
+    let patch_file = patch_file(args);
+    let mut paths = Vec::new();
     for entry in WalkBuilder::new(&root)
         .hidden(!args.options.hidden)
         .git_ignore(!args.options.gitignored)
+        .filter_entry(move |entry| !is_patch_file(entry.path(), patch_file.as_deref()))
         .sort_by_file_path(Ord::cmp)
         .build()
     {
         match entry {
-            Ok(entry) => {
-                let path = entry.path();
-                let res = process_path(
-                    path,
-                    &root,
-                    validator,
-                    general_scoper,
-                    language_scopers,
-                    actions,
-                    args,
-                    search_mode,
-                );
-
-                n_files_seen += match res {
-                    Err(PathProcessingError::NotAFile | PathProcessingError::InvalidFile) => 0,
-                    _ => 1,
-                };
-
-                n_files_processed += match res {
-                    Ok(()) => 1,
-                    Err(PathProcessingError::NotAFile | PathProcessingError::InvalidFile) => 0,
-                    Err(PathProcessingError::ApplicationError(ApplicationError::SomeInScope))
-                        if args.options.fail_any =>
-                    {
-                        // Early-out
-                        info!("Match at {}, exiting early", path.display());
-                        return Err(ProgramError::SomethingProcessed);
-                    }
-                    #[allow(clippy::match_same_arms)]
-                    Err(PathProcessingError::ApplicationError(
-                        ApplicationError::NoneInScope | ApplicationError::SomeInScope,
-                    )) => 0,
-                    Err(PathProcessingError::IoError(e, _))
-                        if e.kind() == io::ErrorKind::BrokenPipe && search_mode =>
-                    {
-                        trace!("Detected broken pipe, stopping search.");
-                        break;
-                    }
-                    Err(
-                        e @ (PathProcessingError::ApplicationError(ApplicationError::ActionError(
-                            ..,
-                        ))
-                        | PathProcessingError::IoError(..)),
-                    ) => {
-                        // Hard errors we should do something about.
-                        if search_mode {
-                            error!("Error walking at {}: {}", path.display(), e);
-                            0
-                        } else {
-                            error!("Aborting walk at {} due to: {}", path.display(), e);
-                            return Err(e.into());
-                        }
-                    }
-                }
-            }
+            Ok(entry) => paths.push(entry.into_path()),
             Err(e) => {
                 if search_mode {
                     error!("Error walking: {}", e);
@@ -320,6 +533,156 @@ fn handle_actions_on_many_files_sorted(
         }
     }
 
+    let next_index = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    // Number of files whose results were flushed so far. Files are only written to
+    // once all earlier ones were flushed, so no file is modified past one aborting
+    // the walk, just like in a sequential run.
+    let n_flushed = (Mutex::new(0usize), Condvar::new());
+
+    let (n_files_seen, n_files_processed) = thread::scope(|s| {
+        let (tx, rx) = mpsc::channel();
+
+        for _ in 0..n_threads {
+            let tx = tx.clone();
+            let (paths, root, next_index, stop, n_flushed) =
+                (&paths, &root, &next_index, &stop, &n_flushed);
+
+            s.spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let i = next_index.fetch_add(1, Ordering::Relaxed);
+                    let Some(path) = paths.get(i) else {
+                        break;
+                    };
+
+                    let may_write = || {
+                        let (flushed, turn) = n_flushed;
+                        drop(
+                            turn.wait_while(flushed.lock().unwrap(), |flushed| {
+                                *flushed < i && !stop.load(Ordering::Relaxed)
+                            })
+                            .unwrap(),
+                        );
+
+                        !stop.load(Ordering::Relaxed)
+                    };
+
+                    let mut output = Vec::new();
+                    let res = process_path(
+                        path,
+                        root,
+                        validator,
+                        general_scoper,
+                        language_scopers,
+                        actions,
+                        stages,
+                        args,
+                        search_mode,
+                        report,
+                        audit_log,
+                        &may_write,
+                        &mut output,
+                    );
+
+                    if tx.send((i, res, output)).is_err() {
+                        // Receiver hung up, no point in continuing.
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx); // Only workers hold senders now, so `rx` terminates once they're done
+
+        let flush = || -> Result<(usize, usize), ProgramError> {
+            let mut n_files_processed: usize = 0;
+            let mut n_files_seen: usize = 0;
+
+            // Results arrive in arbitrary order; park them until it's their turn.
+            let mut pending = BTreeMap::new();
+            let mut next_to_flush = 0;
+
+            for (i, res, output) in &rx {
+                pending.insert(i, (res, output));
+
+                while let Some((res, output)) = pending.remove(&next_to_flush) {
+                    let path = &paths[next_to_flush];
+                    next_to_flush += 1;
+
+                    let res = sink
+                        .lock()
+                        .unwrap()
+                        .write_all(&output)
+                        .map_err(PathProcessingError::from)
+                        .and(res);
+
+                    n_files_seen += match res {
+                        Err(PathProcessingError::NotAFile | PathProcessingError::InvalidFile) => 0,
+                        _ => 1,
+                    };
+
+                    n_files_processed += match res {
+                        Ok(()) => 1,
+                        Err(PathProcessingError::NotAFile | PathProcessingError::InvalidFile) => 0,
+                        Err(PathProcessingError::ApplicationError(
+                            ApplicationError::SomeInScope,
+                        )) if args.options.fail_any => {
+                            // Early-out
+                            info!("Match at {}, exiting early", path.display());
+                            return Err(ProgramError::SomethingProcessed);
+                        }
+                        #[allow(clippy::match_same_arms)]
+                        Err(PathProcessingError::ApplicationError(
+                            ApplicationError::NoneInScope | ApplicationError::SomeInScope,
+                        )) => 0,
+                        Err(PathProcessingError::IoError(e, _))
+                            if e.kind() == io::ErrorKind::BrokenPipe && search_mode =>
+                        {
+                            trace!("Detected broken pipe, stopping search.");
+                            return Ok((n_files_seen, n_files_processed));
+                        }
+                        Err(
+                            e @ (PathProcessingError::ApplicationError(
+                                ApplicationError::ActionError(..)
+                                | ApplicationError::AuditLogError(..)
+                                | ApplicationError::SyntaxBroken { .. },
+                            )
+                            | PathProcessingError::IoError(..)),
+                        ) => {
+                            // Hard errors we should do something about.
+                            if search_mode {
+                                error!("Error walking at {}: {}", path.display(), e);
+                                0
+                            } else {
+                                error!("Aborting walk at {} due to: {}", path.display(), e);
+                                return Err(e.into());
+                            }
+                        }
+                    };
+
+                    let (flushed, turn) = &n_flushed;
+                    *flushed.lock().unwrap() = next_to_flush;
+                    turn.notify_all();
+                }
+            }
+
+            Ok((n_files_seen, n_files_processed))
+        };
+
+        let res = flush();
+        // Signal any workers still busy to wind down; in the success case, they are
+        // done anyway. Set while holding the lock, so workers waiting for their turn to
+        // write cannot miss it.
+        {
+            let (flushed, turn) = &n_flushed;
+            let _flushed = flushed.lock().unwrap();
+            stop.store(true, Ordering::Relaxed);
+            turn.notify_all();
+        }
+        drop(rx);
+
+        res
+    })?;
+
     info!("Saw {} items", n_files_seen);
     info!("Processed {} files", n_files_processed);
 
@@ -335,14 +698,19 @@ fn handle_actions_on_many_files_sorted(
 /// Main entrypoint for processing using at least 1 thread.
 #[allow(clippy::borrowed_box)] // Used throughout, not much of a pain
 #[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_arguments)]
 fn handle_actions_on_many_files_threaded(
     validator: &Validator,
     general_scoper: &Box<dyn Scoper>,
-    language_scopers: &[Box<dyn LanguageScoper>],
+    language_scopers: &[Box<dyn FileScoper>],
     actions: &[Box<dyn Action>],
+    stages: &[Stage],
     args: &cli::Cli,
     search_mode: bool,
+    report: Option<&dyn Report>,
+    audit_log: Option<&AuditLog>,
     n_threads: usize,
+    sink: &Mutex<Box<dyn Write + Send>>,
 ) -> Result<(), ProgramError> {
     let root = env::current_dir()?;
     info!(
@@ -355,6 +723,7 @@ fn handle_actions_on_many_files_threaded(
     let n_files_seen = Arc::new(Mutex::new(0usize));
     let err: Arc<Mutex<Option<ProgramError>>> = Arc::new(Mutex::new(None));
 
+    let patch_file = patch_file(args);
     WalkBuilder::new(&root)
         .threads(
             // https://github.com/BurntSushi/ripgrep/issues/2854
@@ -362,11 +731,13 @@ fn handle_actions_on_many_files_threaded(
         )
         .hidden(!args.options.hidden)
         .git_ignore(!args.options.gitignored)
+        .filter_entry(move |entry| !is_patch_file(entry.path(), patch_file.as_deref()))
         .build_parallel()
         .run(|| {
             Box::new(|entry| match entry {
                 Ok(entry) => {
                     let path = entry.path();
+                    let mut output = Vec::new();
                     let res = process_path(
                         path,
                         &root,
@@ -374,10 +745,24 @@ fn handle_actions_on_many_files_threaded(
                         general_scoper,
                         language_scopers,
                         actions,
+                        stages,
                         args,
                         search_mode,
+                        report,
+                        audit_log,
+                        &|| err.lock().unwrap().is_none(),
+                        &mut output,
                     );
 
+                    // Hold the lock only while writing, so results aren't intertwined
+                    // but processing isn't serialized either.
+                    let res = sink
+                        .lock()
+                        .unwrap()
+                        .write_all(&output)
+                        .map_err(PathProcessingError::from)
+                        .and(res);
+
                     match res {
                         Err(PathProcessingError::NotAFile | PathProcessingError::InvalidFile) => (),
                         _ => *n_files_seen.lock().unwrap() += 1,
@@ -467,10 +852,15 @@ fn process_path(
     root: &Path,
     validator: &Validator,
     general_scoper: &Box<dyn Scoper>,
-    language_scopers: &[Box<dyn LanguageScoper>],
+    language_scopers: &[Box<dyn FileScoper>],
     actions: &[Box<dyn Action>],
+    stages: &[Stage],
     args: &cli::Cli,
     search_mode: bool,
+    report: Option<&dyn Report>,
+    audit_log: Option<&AuditLog>,
+    may_write: &dyn Fn() -> bool,
+    stdout: &mut impl Write,
 ) -> std::result::Result<(), PathProcessingError> {
     if !path.is_file() {
         trace!("Skipping path (not a file): {:?}", path);
@@ -486,7 +876,21 @@ fn process_path(
 
     debug!("Processing path: {:?}", path);
 
-    let (new_contents, filesize, changed) = {
+    if let Some(report) = report {
+        let mut source = String::new();
+        File::open(&path)?.read_to_string(&mut source)?;
+
+        let view = in_file(&path, || {
+            build_view(&source, general_scoper, language_scopers, args)
+        });
+        for finding in report.findings(&view) {
+            writeln!(stdout, "{}:{finding}", path.display())?;
+        }
+
+        return Ok(());
+    }
+
+    let (source, new_contents, filesize, changed) = {
         let mut file = File::open(&path)?;
 
         let filesize = file.metadata().map_or(0, |m| m.len());
@@ -495,20 +899,31 @@ fn process_path(
         let mut source = String::new();
         file.read_to_string(&mut source)?;
 
-        let changed = apply(
-            &source,
-            &mut destination,
-            general_scoper,
-            language_scopers,
-            actions,
-            args,
-        )?;
+        let changed = in_file(&path, || {
+            apply(
+                &source,
+                &mut destination,
+                general_scoper,
+                language_scopers,
+                actions,
+                stages,
+                args,
+                audit_log.map(|log| (log, Some(path.as_path()))),
+            )
+        })?;
 
-        (destination, filesize, changed)
+        (source, destination, filesize, changed)
     };
 
-    // Hold the lock so results aren't intertwined
-    let mut stdout = stdout().lock();
+    if args.options.patch.is_some() {
+        if changed {
+            debug!("Got new file contents, writing patch for: {:?}", path);
+            let mode = file_mode(&path)?;
+            write!(stdout, "{}", git_patch(&path, mode, &source, &new_contents))?;
+        }
+
+        return Ok(());
+    }
 
     if search_mode {
         if !new_contents.is_empty() {
@@ -534,6 +949,11 @@ fn process_path(
         }
 
         if changed {
+            if !may_write() {
+                debug!("Walk aborted, not writing file: {}", path.display());
+                return Ok(());
+            }
+
             writeln!(stdout, "{}", path.display())?;
 
             debug!("Got new file contents, writing to file: {:?}", path);
@@ -558,38 +978,150 @@ fn process_path(
     Ok(())
 }
 
-/// Runs the actual core processing, returning whether anything changed in the output
-/// compared to the input.
+/// The file patches are written to, if any, canonicalized.
 ///
-/// TODO: The way this interacts with [`process_path`] etc. is just **awful** spaghetti
-/// of the most imperative, procedural kind. Refactor needed.
-#[allow(clippy::borrowed_box)] // Used throughout, not much of a pain
-fn apply(
-    source: &str,
-    // Use a string to avoid repeated and unnecessary bytes -> utf8 conversions and
-    // corresponding checks.
-    destination: &mut String,
-    general_scoper: &Box<dyn Scoper>,
-    language_scopers: &[Box<dyn LanguageScoper>],
-    actions: &[Box<dyn Action>],
-    args: &cli::Cli,
-) -> std::result::Result<bool, ApplicationError> {
-    debug!("Building view.");
-    let mut builder = ScopedViewBuilder::new(source);
+/// It is created before walking, and must not be walked itself: it's no input, and is
+/// being written to concurrently.
+fn patch_file(args: &cli::Cli) -> Option<PathBuf> {
+    args.options
+        .patch
+        .as_deref()
+        .filter(|path| *path != Path::new("-"))
+        .and_then(|path| path.canonicalize().ok())
+}
 
-    if args.options.join_language_scopes {
-        // All at once, as a slice: hits a specific, 'joining' `impl`
-        builder.explode(&language_scopers);
+/// Whether `path` is the `patch_file`, see [`patch_file`].
+fn is_patch_file(path: &Path, patch_file: Option<&Path>) -> bool {
+    patch_file.map_or(false, |patch_file| {
+        // Cheap check first, as this runs for every path walked.
+        path.file_name() == patch_file.file_name()
+            && path.canonicalize().is_ok_and(|path| path == patch_file)
+    })
+}
+
+/// Renders the change of the file at `path` (relative to the repository root) from
+/// `old` to `new` contents as a patch in `git diff` format, applicable via `git apply`
+/// or `patch -p1`.
+///
+/// Files whose contents are removed entirely are deleted by the patch.
+fn git_patch(path: &Path, mode: u32, old: &str, new: &str) -> String {
+    // Patches always use forward slashes, regardless of platform.
+    let path = path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .join("/");
+
+    let mut diff = format!("diff --git a/{path} b/{path}\n");
+    let to = if new.is_empty() {
+        diff.push_str(&format!("deleted file mode {mode:o}\n"));
+        "/dev/null".to_owned()
     } else {
-        // One by one: hits a different, 'intersecting' `impl`
-        for scoper in language_scopers {
-            builder.explode(scoper);
+        format!("b/{path}")
+    };
+
+    diff.push_str(
+        &TextDiff::from_lines(old, new)
+            .unified_diff()
+            .header(&format!("a/{path}"), &to)
+            .to_string(),
+    );
+
+    diff
+}
+
+/// Sidecar log of all scopes matched, before any transformation, as JSON lines.
+///
+/// Written to concurrently, but each file's records end up contiguous.
+struct AuditLog {
+    writer: Mutex<BufWriter<File>>,
+}
+
+/// A single record of [`AuditLog`].
+#[derive(Debug, Serialize)]
+struct AuditRecord<'a> {
+    /// The file the scope was found in, or none for stdin.
+    path: Option<&'a Path>,
+    #[serde(flatten)]
+    r#match: Match<'a>,
+}
+
+impl AuditLog {
+    /// Opens the log at `path`, appending to it if it exists.
+    fn open(path: &Path) -> io::Result<Self> {
+        info!(
+            "Will append matched scopes to audit log: {}",
+            path.display()
+        );
+
+        let file = File::options().create(true).append(true).open(path)?;
+
+        Ok(Self {
+            writer: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    /// Records all [matches](ScopedView::matches) of `view`, found in the file at
+    /// `path`.
+    fn record(&self, path: Option<&Path>, view: &ScopedView<'_>) -> io::Result<()> {
+        let mut buffer = Vec::new();
+
+        for r#match in view.matches() {
+            serde_json::to_writer(&mut buffer, &AuditRecord { path, r#match })?;
+            buffer.push(b'\n');
         }
+
+        self.writer
+            .lock()
+            .expect("no other thread to have panicked holding the lock")
+            .write_all(&buffer)
     }
 
-    builder.explode(general_scoper);
-    let mut view = builder.build();
-    debug!("Done building view: {view:?}");
+    /// Flushes all buffered records.
+    fn flush(self) -> io::Result<()> {
+        self.writer
+            .into_inner()
+            .expect("no other thread to have panicked holding the lock")
+            .flush()
+    }
+}
+
+/// The file mode of `path`, as recorded by git: either regular or executable.
+fn file_mode(path: &Path) -> io::Result<u32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let executable = path.metadata()?.permissions().mode() & 0o111 != 0;
+        Ok(if executable { 0o100_755 } else { 0o100_644 })
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        Ok(0o100_644)
+    }
+}
+
+/// Runs the actual core processing, returning whether anything changed in the output
+/// compared to the input.
+///
+/// TODO: The way this interacts with [`process_path`] etc. is just **awful** spaghetti
+/// of the most imperative, procedural kind. Refactor needed.
+#[allow(clippy::borrowed_box)] // Used throughout, not much of a pain
+#[allow(clippy::too_many_arguments)]
+fn apply(
+    source: &str,
+    // Use a string to avoid repeated and unnecessary bytes -> utf8 conversions and
+    // corresponding checks.
+    destination: &mut String,
+    general_scoper: &Box<dyn Scoper>,
+    language_scopers: &[Box<dyn FileScoper>],
+    actions: &[Box<dyn Action>],
+    stages: &[Stage],
+    args: &cli::Cli,
+    audit: Option<(&AuditLog, Option<&Path>)>,
+) -> std::result::Result<bool, ApplicationError> {
+    let mut view = build_view(source, general_scoper, language_scopers, args);
 
     if args.options.fail_none && !view.has_any_in_scope() {
         return Err(ApplicationError::NoneInScope);
@@ -599,19 +1131,42 @@ fn apply(
         return Err(ApplicationError::SomeInScope);
     };
 
-    debug!("Applying actions to view.");
-    if args.standalone_actions.squeeze {
-        view.squeeze();
+    if let Some((log, path)) = audit {
+        log.record(path, &view)
+            .map_err(ApplicationError::AuditLogError)?;
     }
 
-    for action in actions {
-        view.map_with_context(action)?;
+    apply_actions(&mut view, actions, args)?;
+
+    if !stages.is_empty() {
+        let mut current = view.to_string();
+        for stage in stages {
+            debug!("Applying next pipeline stage.");
+            let mut view = build_view(
+                &current,
+                &stage.general_scoper,
+                &stage.language_scopers,
+                &stage.args,
+            );
+            apply_actions(&mut view, &stage.actions, &stage.args)?;
+            current = view.to_string();
+        }
+
+        guard_syntax(source, &current, language_scopers, args)?;
+
+        // Line-based output conflicts with stages, so no need to consider it here.
+        destination.push_str(&current);
+        return Ok(source != *destination);
     }
 
     debug!("Writing to destination.");
     let line_based = args.options.only_matching || args.options.line_numbers;
     if line_based {
-        for (i, line) in view.lines().into_iter().enumerate() {
+        for (i, line) in view
+            .records(args.options.record_separator)
+            .into_iter()
+            .enumerate()
+        {
             let i = i + 1;
             if !args.options.only_matching || line.has_any_in_scope() {
                 if args.options.line_numbers {
@@ -625,13 +1180,100 @@ fn apply(
             }
         }
     } else {
-        destination.push_str(&view.to_string());
+        let output = view.to_string();
+        guard_syntax(source, &output, language_scopers, args)?;
+        destination.push_str(&output);
     };
     debug!("Done writing to destination.");
 
     Ok(source != *destination)
 }
 
+/// Fails if `output` contains more syntax errors than `source`, according to any of
+/// the `language_scopers`, if guarding syntax was requested at all.
+fn guard_syntax(
+    source: &str,
+    output: &str,
+    language_scopers: &[Box<dyn FileScoper>],
+    args: &cli::Cli,
+) -> std::result::Result<(), ApplicationError> {
+    if !args.options.guard_syntax || source == output {
+        return Ok(());
+    }
+
+    for scoper in language_scopers {
+        if let (Some(before), Some(after)) =
+            (scoper.syntax_errors(source), scoper.syntax_errors(output))
+        {
+            if after > before {
+                debug!("Output has {after} syntax errors, input had {before}.");
+                return Err(ApplicationError::SyntaxBroken { before, after });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies all `actions`, plus those operating on the view as a whole, to `view`.
+fn apply_actions(
+    view: &mut ScopedView<'_>,
+    actions: &[Box<dyn Action>],
+    args: &cli::Cli,
+) -> std::result::Result<(), ApplicationError> {
+    debug!("Applying actions to view.");
+    if args.standalone_actions.squeeze {
+        view.squeeze();
+    }
+
+    for action in actions {
+        view.map_with_context(action)?;
+    }
+
+    if let Some(attribute) = &args.composable_actions.prepend_attribute {
+        view.prepend_attribute(attribute);
+    }
+
+    Ok(())
+}
+
+/// Builds the view of `source` according to all given scopers.
+#[allow(clippy::borrowed_box)] // Used throughout, not much of a pain
+fn build_view<'viewee>(
+    source: &'viewee str,
+    general_scoper: &Box<dyn Scoper>,
+    language_scopers: &[Box<dyn FileScoper>],
+    args: &cli::Cli,
+) -> ScopedView<'viewee> {
+    debug!("Building view.");
+    let mut builder = ScopedViewBuilder::new(source);
+
+    if args.options.join_language_scopes {
+        // All at once, as a slice: hits a specific, 'joining' `impl`
+        builder.explode(&language_scopers);
+    } else {
+        // One by one: hits a different, 'intersecting' `impl`
+        for scoper in language_scopers {
+            builder.explode(scoper);
+        }
+    }
+
+    builder.explode(general_scoper);
+
+    if args.options.coalesce_lines {
+        builder.coalesce_lines();
+    }
+
+    if args.options.exclude_header {
+        builder.exclude_header();
+    }
+
+    let view = builder.build();
+    debug!("Done building view: {view:?}");
+
+    view
+}
+
 /// Top-level, user-facing errors, affecting and possibly terminating program execution
 /// as a whole.
 #[derive(Debug)]
@@ -650,6 +1292,8 @@ enum ProgramError {
     IoError(io::Error),
     /// Error while processing files for walking.
     IgnoreError(ignore::Error),
+    /// Streaming was requested, alongside something requiring the entire input.
+    StreamingUnsupported(&'static str),
 }
 
 impl fmt::Display for ProgramError {
@@ -662,6 +1306,12 @@ impl fmt::Display for ProgramError {
             Self::SomethingProcessed => write!(f, "Some input was in scope"),
             Self::IoError(e) => write!(f, "I/O error: {e}"),
             Self::IgnoreError(e) => write!(f, "Error walking files: {e}"),
+            Self::StreamingUnsupported(what) => {
+                write!(
+                    f,
+                    "Streaming is not supported with {what}, as they require the entire input"
+                )
+            }
         }
     }
 }
@@ -701,6 +1351,15 @@ enum ApplicationError {
     NoneInScope,
     /// Error with an [`Action`].
     ActionError(ActionError),
+    /// Error writing to the audit log.
+    AuditLogError(io::Error),
+    /// The output contains syntax errors the input did not.
+    SyntaxBroken {
+        /// Number of syntax errors in the input.
+        before: usize,
+        /// Number of syntax errors in the output.
+        after: usize,
+    },
 }
 
 impl fmt::Display for ApplicationError {
@@ -709,6 +1368,11 @@ impl fmt::Display for ApplicationError {
             Self::SomeInScope => write!(f, "Some input was in scope"),
             Self::NoneInScope => write!(f, "No input was in scope"),
             Self::ActionError(e) => write!(f, "Error in an action: {e}"),
+            Self::AuditLogError(e) => write!(f, "Error writing to audit log: {e}"),
+            Self::SyntaxBroken { before, after } => write!(
+                f,
+                "Output would contain syntax errors (found {after}, input had {before}), refusing to write"
+            ),
         }
     }
 }
@@ -796,14 +1460,14 @@ impl fmt::Display for ScoperBuildError {
 impl Error for ScoperBuildError {}
 
 #[allow(clippy::cognitive_complexity)] // 🤷‍♀️ macros
-fn get_language_scopers(args: &cli::Cli) -> Vec<Box<dyn LanguageScoper>> {
+fn get_language_scopers(args: &cli::Cli) -> Vec<Box<dyn FileScoper>> {
     // We have `LanguageScoper: Scoper`, but we cannot upcast
     // (https://github.com/rust-lang/rust/issues/65991), so hack around the limitation
     // by providing both.
-    let mut scopers: Vec<Box<dyn LanguageScoper>> = Vec::new();
+    let mut scopers: Vec<Box<dyn FileScoper>> = Vec::new();
 
     macro_rules! handle_language_scope {
-        ($lang:ident, $lang_query:ident, $query_type:ident, $lang_type:ident) => {
+        ($lang:ident, $lang_query:ident, $lang_query_file:ident, $query_type:ident, $lang_type:ident) => {
             if let Some(lang_scope) = &args.languages_scopes.$lang {
                 if !scopers.is_empty() {
                     let mut cmd = cli::Cli::command();
@@ -817,12 +1481,16 @@ fn get_language_scopers(args: &cli::Cli) -> Vec<Box<dyn LanguageScoper>> {
 
                 for query in &lang_scope.$lang {
                     let query = $query_type::Prepared(query.clone());
-                    scopers.push(Box::new($lang_type::new(query.clone())));
+                    scopers.push(boxed_language_scoper(args, $lang_type::new(query.clone())));
                 }
 
-                for query in &lang_scope.$lang_query {
+                for query in lang_scope
+                    .$lang_query
+                    .iter()
+                    .chain(&lang_scope.$lang_query_file)
+                {
                     let query = $query_type::Custom(query.clone());
-                    scopers.push(Box::new($lang_type::new(query.clone())));
+                    scopers.push(boxed_language_scoper(args, $lang_type::new(query.clone())));
                 }
 
                 assert!(!scopers.is_empty(), "Language specified, but no scope."); // Internal bug
@@ -830,19 +1498,95 @@ fn get_language_scopers(args: &cli::Cli) -> Vec<Box<dyn LanguageScoper>> {
         };
     }
 
-    handle_language_scope!(csharp, csharp_query, CSharpQuery, CSharp);
-    handle_language_scope!(hcl, hcl_query, HclQuery, Hcl);
-    handle_language_scope!(go, go_query, GoQuery, Go);
-    handle_language_scope!(python, python_query, PythonQuery, Python);
-    handle_language_scope!(rust, rust_query, RustQuery, Rust);
-    handle_language_scope!(typescript, typescript_query, TypeScriptQuery, TypeScript);
+    handle_language_scope!(c, c_query, c_query_file, CQuery, C);
+    handle_language_scope!(cpp, cpp_query, cpp_query_file, CppQuery, Cpp);
+    handle_language_scope!(csharp, csharp_query, csharp_query_file, CSharpQuery, CSharp);
+    handle_language_scope!(css, css_query, css_query_file, CssQuery, Css);
+    handle_language_scope!(
+        dockerfile,
+        dockerfile_query,
+        dockerfile_query_file,
+        DockerfileQuery,
+        Dockerfile
+    );
+    handle_language_scope!(hcl, hcl_query, hcl_query_file, HclQuery, Hcl);
+    handle_language_scope!(go, go_query, go_query_file, GoQuery, Go);
+    handle_language_scope!(html, html_query, html_query_file, HtmlQuery, Html);
+    handle_language_scope!(java, java_query, java_query_file, JavaQuery, Java);
+    handle_language_scope!(json, json_query, json_query_file, JsonQuery, Json);
+    handle_language_scope!(
+        markdown,
+        markdown_query,
+        markdown_query_file,
+        MarkdownQuery,
+        Markdown
+    );
+    handle_language_scope!(perl, perl_query, perl_query_file, PerlQuery, Perl);
+    handle_language_scope!(python, python_query, python_query_file, PythonQuery, Python);
+    handle_language_scope!(ruby, ruby_query, ruby_query_file, RubyQuery, Ruby);
+    handle_language_scope!(rust, rust_query, rust_query_file, RustQuery, Rust);
+    handle_language_scope!(sql, sql_query, sql_query_file, SqlQuery, Sql);
+    handle_language_scope!(toml, toml_query, toml_query_file, TomlQuery, Toml);
+    handle_language_scope!(
+        typescript,
+        typescript_query,
+        typescript_query_file,
+        TypeScriptQuery,
+        TypeScript
+    );
+    handle_language_scope!(yaml, yaml_query, yaml_query_file, YamlQuery, Yaml);
+
+    // Not a tree-sitter language, so no queries.
+    if let Some(ini_scope) = &args.languages_scopes.ini {
+        if !scopers.is_empty() {
+            let mut cmd = cli::Cli::command();
+            cmd.error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "Can only use one language at a time.",
+            )
+            .exit();
+        }
+
+        for item in &ini_scope.ini {
+            scopers.push(Box::new(Ini::new(*item)));
+        }
+    }
 
     scopers
 }
 
+/// Boxes up the given language scoper, selecting only the requested capture
+/// occurrences if asked to.
+fn boxed_language_scoper<L>(args: &cli::Cli, scoper: L) -> Box<dyn FileScoper>
+where
+    L: LanguageScoper + 'static,
+{
+    let Some((capture, n)) = &args.options.nth_capture else {
+        return Box::new(scoper);
+    };
+
+    match NthCapture::new(scoper, capture.clone(), *n) {
+        Ok(scoper) => Box::new(scoper),
+        Err(e) => {
+            let mut cmd = cli::Cli::command();
+            cmd.error(clap::error::ErrorKind::ValueValidation, e).exit();
+        }
+    }
+}
+
 fn get_general_scoper(args: &cli::Cli) -> Result<Box<dyn Scoper>> {
     Ok(if args.options.literal_string {
         Box::new(Literal::try_from(args.scope.clone()).context("Failed building literal string")?)
+    } else if args.options.multiline {
+        let scope = if args.options.anchored {
+            regex::anchor(&args.scope)
+        } else {
+            args.scope.clone()
+        };
+
+        Box::new(Regex::multiline(&scope).context("Failed building regex")?)
+    } else if args.options.anchored {
+        Box::new(Regex::anchored(&args.scope).context("Failed building regex")?)
     } else {
         Box::new(Regex::try_from(args.scope.clone()).context("Failed building regex")?)
     })
@@ -858,6 +1602,35 @@ fn assemble_actions(args: &cli::Cli) -> Result<Vec<Box<dyn Action>>> {
         debug!("Loaded action: Replacement");
     }
 
+    if let Some([pattern, replacement]) = args.composable_actions.substitute.as_deref() {
+        actions.push(Box::new(Substitution::new(
+            RegexPattern::new(pattern).context("Failed building substitution pattern")?,
+            Replacement::try_from(replacement.clone())
+                .context("Failed building substitution replacement string")?,
+        )));
+        debug!("Loaded action: Substitution");
+    }
+
+    if let Some(operation) = args.composable_actions.number_op {
+        let operand = args.composable_actions.number_operand.as_deref();
+        actions.push(Box::new(
+            NumberOp::new(
+                operation,
+                operand.unwrap_or_default(),
+                args.composable_actions.number_preserve_width,
+            )
+            .context("Failed building number operation")?,
+        ));
+        debug!("Loaded action: NumberOp");
+    }
+
+    if args.composable_actions.expand_env {
+        actions.push(Box::new(ExpandEnv::new(
+            args.composable_actions.expand_env_strict,
+        )));
+        debug!("Loaded action: ExpandEnv");
+    }
+
     #[cfg(feature = "german")]
     if args.composable_actions.german {
         actions.push(Box::new(German::new(
@@ -884,6 +1657,43 @@ fn assemble_actions(args: &cli::Cli) -> Result<Vec<Box<dyn Action>>> {
         debug!("Loaded action: Deletion");
     }
 
+    assemble_casing_actions(args, &mut actions);
+
+    if args.composable_actions.reverse {
+        actions.push(Box::<Reverse>::default());
+        debug!("Loaded action: Reverse");
+    }
+
+    if args.composable_actions.normalize {
+        actions.push(Box::<Normalization>::default());
+        debug!("Loaded action: Normalization");
+    }
+
+    assemble_encoding_actions(args, &mut actions);
+    assemble_formatting_actions(args, &mut actions);
+    assemble_code_actions(args, &mut actions);
+
+    guard_actions(args, actions)
+}
+
+/// Wraps all `actions` into a single chain skipping anything recognized as already
+/// transformed, if requested.
+///
+/// The chain as a whole is guarded, so recognition happens once per scope, against
+/// its original content: output of one action cannot cause later ones to skip.
+fn guard_actions(args: &cli::Cli, actions: Vec<Box<dyn Action>>) -> Result<Vec<Box<dyn Action>>> {
+    let Some(recognizer) = &args.options.skip_if else {
+        return Ok(actions);
+    };
+
+    let recognizer = RegexPattern::new(recognizer).context("Failed building skip-if pattern")?;
+    debug!("Guarding all actions against already transformed input");
+
+    Ok(vec![Box::new(SkipIfMatches::new(actions, recognizer))])
+}
+
+/// Adds actions which change the casing of whatever is in scope.
+fn assemble_casing_actions(args: &cli::Cli, actions: &mut Vec<Box<dyn Action>>) {
     if args.composable_actions.upper {
         actions.push(Box::<Upper>::default());
         debug!("Loaded action: Upper");
@@ -899,12 +1709,186 @@ fn assemble_actions(args: &cli::Cli) -> Result<Vec<Box<dyn Action>>> {
         debug!("Loaded action: Titlecase");
     }
 
-    if args.composable_actions.normalize {
-        actions.push(Box::<Normalization>::default());
-        debug!("Loaded action: Normalization");
+    if let Some(style) = args.composable_actions.convert_case {
+        actions.push(Box::new(CaseConvert::new(style)));
+        debug!("Loaded action: CaseConvert");
+    }
+}
+
+/// Adds actions which encode, escape or mask whatever is in scope.
+fn assemble_encoding_actions(args: &cli::Cli, actions: &mut Vec<Box<dyn Action>>) {
+    if args.composable_actions.regex_escape {
+        actions.push(Box::<RegexEscape>::default());
+        debug!("Loaded action: RegexEscape");
+    }
+
+    if args.composable_actions.base64_decode {
+        actions.push(Box::new(Base64Decode::new(
+            args.composable_actions.base64_url_safe,
+        )));
+        debug!("Loaded action: Base64Decode");
+    }
+
+    if args.composable_actions.base64_encode {
+        actions.push(Box::new(Base64Encode::new(
+            args.composable_actions.base64_url_safe,
+        )));
+        debug!("Loaded action: Base64Encode");
+    }
+
+    if args.composable_actions.url_decode {
+        actions.push(Box::<UrlDecode>::default());
+        debug!("Loaded action: UrlDecode");
+    }
+
+    if args.composable_actions.url_encode {
+        actions.push(Box::<UrlEncode>::default());
+        debug!("Loaded action: UrlEncode");
+    }
+
+    if args.composable_actions.redact {
+        actions.push(Box::<Redact>::default());
+        debug!("Loaded action: Redact");
+    }
+
+    #[cfg(feature = "hash")]
+    if let Some(algorithm) = args.composable_actions.hash {
+        actions.push(Box::new(args.composable_actions.hash_length.map_or_else(
+            || Hash::new(algorithm),
+            |length| Hash::truncated(algorithm, length),
+        )));
+        debug!("Loaded action: Hash");
+    }
+}
+
+/// The report to collect instead of applying actions, if any was requested.
+fn assemble_report(args: &cli::Cli) -> Option<Box<dyn Report>> {
+    if let Some(markers) = args.standalone_actions.markers.as_deref() {
+        info!("Will collect markers {markers:?} into a report.");
+        return Some(Box::new(MarkerReport::new(markers)));
+    }
+
+    if args.standalone_actions.mixed_indentation {
+        info!("Will collect lines with mixed indentation into a report.");
+        return Some(Box::new(MixedIndentationReport));
+    }
+
+    None
+}
+
+/// Adds actions which reformat, rather than transform, whatever is in scope.
+fn assemble_formatting_actions(args: &cli::Cli, actions: &mut Vec<Box<dyn Action>>) {
+    if args.composable_actions.sort_imports {
+        actions.push(Box::new(
+            SortImports::default().with_separator(args.options.record_separator),
+        ));
+        debug!("Loaded action: SortImports");
+    }
+
+    if args.composable_actions.sort_lines {
+        actions.push(Box::new(
+            SortLines::new(
+                args.composable_actions.sort_order,
+                args.composable_actions.sort_case_insensitive,
+                args.composable_actions.sort_numeric,
+            )
+            .with_separator(args.options.record_separator),
+        ));
+        debug!("Loaded action: SortLines");
+    }
+
+    if args.composable_actions.sort_toml_keys {
+        actions.push(Box::<SortTomlKeys>::default());
+        debug!("Loaded action: SortTomlKeys");
+    }
+
+    if args.composable_actions.markdown_table {
+        actions.push(Box::<MarkdownTable>::default());
+        debug!("Loaded action: MarkdownTable");
+    }
+
+    if let Some(wrapping) = args.composable_actions.wrap_urls {
+        actions.push(Box::new(WrapUrls::new(wrapping)));
+        debug!("Loaded action: WrapUrls");
+    }
+
+    if args.composable_actions.squeeze_whitespace {
+        actions.push(Box::new(Squeeze::new(SqueezeMode::Whitespace)));
+        debug!("Loaded action: Squeeze (whitespace)");
+    }
+
+    if args.composable_actions.squeeze_blank_lines {
+        actions.push(Box::new(Squeeze::new(SqueezeMode::BlankLines)));
+        debug!("Loaded action: Squeeze (blank lines)");
+    }
+
+    if let Some(width) = args.composable_actions.expand_tabs {
+        actions.push(Box::new(ExpandTabs::new(width)));
+        debug!("Loaded action: ExpandTabs");
+    }
+
+    if let Some(unit) = args.composable_actions.fix_mixed_indentation {
+        actions.push(Box::new(FixMixedIndentation::new(
+            unit,
+            args.composable_actions.indent_width,
+        )));
+        debug!("Loaded action: FixMixedIndentation");
+    }
+
+    if let Some(amount) = args.composable_actions.indent {
+        actions.push(Box::new(Indent::new(
+            amount,
+            args.composable_actions.indent_unit,
+            args.composable_actions.indent_width,
+        )));
+        debug!("Loaded action: Indent");
     }
 
-    Ok(actions)
+    if let Some(width) = args.composable_actions.pad_numbers {
+        actions.push(Box::new(PadNumbers::new(
+            width,
+            args.composable_actions.pad_char,
+            args.composable_actions.pad_alignment,
+        )));
+        debug!("Loaded action: PadNumbers");
+    }
+}
+
+/// Adds actions which restyle source code constructs, like comments and strings, in
+/// scope.
+fn assemble_code_actions(args: &cli::Cli, actions: &mut Vec<Box<dyn Action>>) {
+    if let Some(language) = args.composable_actions.normalize_keywords {
+        actions.push(Box::new(NormalizeKeywords::new(language)));
+        debug!("Loaded action: NormalizeKeywords");
+    }
+
+    if args.composable_actions.collapse_string_whitespace {
+        actions.push(Box::<CollapseStringWhitespace>::default());
+        debug!("Loaded action: CollapseStringWhitespace");
+    }
+
+    if let Some(width) = args.composable_actions.rewrap_strings {
+        actions.push(Box::new(RewrapStrings::new(
+            width.get(),
+            args.composable_actions.concatenation,
+        )));
+        debug!("Loaded action: RewrapStrings");
+    }
+
+    if let Some(style) = args.composable_actions.convert_comments {
+        actions.push(Box::new(ConvertComments::new(style)));
+        debug!("Loaded action: ConvertComments");
+    }
+
+    if let Some(style) = args.composable_actions.convert_properties {
+        actions.push(Box::new(ConvertProperties::new(style)));
+        debug!("Loaded action: ConvertProperties");
+    }
+
+    if let Some([from, to]) = args.composable_actions.convert_separators.as_deref() {
+        actions.push(Box::new(ConvertSeparators::new(from, to)));
+        debug!("Loaded action: ConvertSeparators");
+    }
 }
 
 /// To the default log level found in the environment, adds the requested additional
@@ -931,17 +1915,43 @@ fn level_filter_from_env_and_verbosity(additional_verbosity: u8) -> LevelFilter
 }
 
 mod cli {
+    use std::iter;
     use std::num::NonZero;
+    use std::path::PathBuf;
+    use std::str::FromStr;
 
-    use clap::builder::ArgPredicate;
-    use clap::{ArgAction, Command, CommandFactory, Parser};
+    use clap::builder::{ArgPredicate, NonEmptyStringValueParser};
+    use clap::error::ErrorKind;
+    use clap::{ArgAction, Command, CommandFactory, FromArgMatches, Parser};
     use clap_complete::{generate, Generator, Shell};
+    use itertools::Itertools;
+    #[cfg(feature = "hash")]
+    use srgn::actions::HashAlgorithm;
+    use srgn::actions::{
+        Alignment, CaseStyle, CommentStyle, Concatenation, IndentUnit, KeywordLanguage,
+        NumberOperation, PropertyStyle, SortOrder, UrlWrapping,
+    };
+    use srgn::scoping::langs::c::{CustomCQuery, PreparedCQuery};
+    use srgn::scoping::langs::cpp::{CustomCppQuery, PreparedCppQuery};
     use srgn::scoping::langs::csharp::{CustomCSharpQuery, PreparedCSharpQuery};
+    use srgn::scoping::langs::css::{CustomCssQuery, PreparedCssQuery};
+    use srgn::scoping::langs::dockerfile::{CustomDockerfileQuery, PreparedDockerfileQuery};
     use srgn::scoping::langs::go::{CustomGoQuery, PreparedGoQuery};
     use srgn::scoping::langs::hcl::{CustomHclQuery, PreparedHclQuery};
+    use srgn::scoping::langs::html::{CustomHtmlQuery, PreparedHtmlQuery};
+    use srgn::scoping::langs::ini::IniItem;
+    use srgn::scoping::langs::java::{CustomJavaQuery, PreparedJavaQuery};
+    use srgn::scoping::langs::json::{CustomJsonQuery, PreparedJsonQuery};
+    use srgn::scoping::langs::markdown::{CustomMarkdownQuery, PreparedMarkdownQuery};
+    use srgn::scoping::langs::perl::{CustomPerlQuery, PreparedPerlQuery};
     use srgn::scoping::langs::python::{CustomPythonQuery, PreparedPythonQuery};
+    use srgn::scoping::langs::ruby::{CustomRubyQuery, PreparedRubyQuery};
     use srgn::scoping::langs::rust::{CustomRustQuery, PreparedRustQuery};
+    use srgn::scoping::langs::sql::{CustomSqlQuery, PreparedSqlQuery};
+    use srgn::scoping::langs::toml::{CustomTomlQuery, PreparedTomlQuery};
     use srgn::scoping::langs::typescript::{CustomTypeScriptQuery, PreparedTypeScriptQuery};
+    use srgn::scoping::langs::yaml::{CustomYamlQuery, PreparedYamlQuery};
+    use srgn::scoping::langs::{read_query_file, QueryFileError, TSQueryError};
     use srgn::GLOBAL_SCOPE;
 
     /// Main CLI entrypoint.
@@ -1013,6 +2023,35 @@ mod cli {
         );
     }
 
+    /// Parses a single character, after processing escape sequences.
+    fn parse_record_separator(s: &str) -> Result<char, String> {
+        if s == r"\0" {
+            // Not supported by `unescape`, but the most common case by far.
+            return Ok('\0');
+        }
+
+        let unescaped =
+            unescape::unescape(s).ok_or_else(|| format!("invalid escape sequences in '{s}'"))?;
+
+        unescaped
+            .chars()
+            .exactly_one()
+            .map_err(|_| format!("expected a single character, got '{unescaped}'"))
+    }
+
+    /// Parses `NAME=N` into a capture name and a (one-based) occurrence.
+    fn parse_nth_capture(s: &str) -> Result<(String, NonZero<usize>), String> {
+        let (name, n) = s
+            .rsplit_once('=')
+            .ok_or_else(|| format!("expected 'NAME=N', got '{s}'"))?;
+        let name = name.strip_prefix('@').unwrap_or(name);
+        let n = n
+            .parse()
+            .map_err(|e| format!("invalid occurrence '{n}': {e}"))?;
+
+        Ok((name.to_owned(), n))
+    }
+
     #[derive(Parser, Debug)]
     #[group(required = false, multiple = true)]
     #[command(next_help_heading = "Options (global)")]
@@ -1034,6 +2073,33 @@ mod cli {
         /// unexpected outcome in some contexts. This flag makes the condition explicit.
         #[arg(long, verbatim_doc_comment, alias = "fail-empty-glob")]
         pub fail_no_files: bool,
+        /// Instead of modifying files in-place, emit all changes as a single patch.
+        ///
+        /// The patch is in `git diff` format, so it can be reviewed and then applied
+        /// using `git apply` (or `patch -p1`). It is written to stdout, or to the given
+        /// file, like `--patch=changes.patch`. Files whose contents are removed
+        /// entirely are deleted by the patch. Files are listed sorted by path, as if
+        /// `--sorted` was given.
+        ///
+        /// Only applies when working on files.
+        #[arg(
+            long,
+            value_name = "FILE",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "-",
+            verbatim_doc_comment
+        )]
+        pub patch: Option<PathBuf>,
+        /// Append each scope matched, before any transformation, to this file as a
+        /// JSON line.
+        ///
+        /// Records carry the `path` (`null` for stdin), byte offsets `start` and `end`,
+        /// one-based `line` and `column` (in characters) and the original `text`, so
+        /// changes can be traced and recovered. Only scopes of the first pipeline are
+        /// recorded (see `--then`).
+        #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+        pub audit_log: Option<PathBuf>,
         /// Undo the effects of passed actions, where applicable.
         ///
         /// Requires a 1:1 mapping between replacements and original, which is currently
@@ -1057,7 +2123,29 @@ mod cli {
         /// string. Will require a scope to be passed.
         #[arg(short('L'), long, env, verbatim_doc_comment)]
         pub literal_string: bool,
-        /// If anything at all is found to be in scope, fail.
+        /// Let the scope regex match across line boundaries.
+        ///
+        /// `.` then also matches newlines, and `^` and `$` match at the start and end
+        /// of each line. Capture groups may span multiple lines.
+        #[arg(long, env, conflicts_with = "literal_string", verbatim_doc_comment)]
+        pub multiline: bool,
+        /// Let the scope regex only match entire scopes, not parts of them.
+        ///
+        /// Matching is anchored to the start and end of each scope left by a language
+        /// scoper, as if wrapped in `^(?:...)$`. For example, `--python comments
+        /// --anchored '# TODO'` finds comments consisting of just `# TODO`. Without a
+        /// language scoper, the pattern has to match the entire input.
+        #[arg(long, env, conflicts_with = "literal_string", verbatim_doc_comment)]
+        pub anchored: bool,
+        /// Leave anything in scope matching this regex alone, as it is recognized as
+        /// already transformed.
+        ///
+        /// Makes repeated runs of actions which would otherwise keep changing their
+        /// output no-ops, reporting no changes. For example, `'(?s)\A.*' --skip-if
+        /// '\A// License' -- '// License\n$0'` prepends a header only once.
+        #[arg(long, value_name = "REGEX", verbatim_doc_comment)]
+        pub skip_if: Option<String>,
+        /// If anything at all is found to be in scope, fail.
         ///
         /// The default is to continue processing normally.
         #[arg(long, verbatim_doc_comment)]
@@ -1067,6 +2155,16 @@ mod cli {
         /// The default is to return the input unchanged (without failure).
         #[arg(long, verbatim_doc_comment)]
         pub fail_none: bool,
+        /// Refuse to write output containing syntax errors not present in the input.
+        ///
+        /// The output is parsed again using the grammar of each language scope given.
+        /// If that turns up more syntax errors than found in the original input, the
+        /// transformation is considered broken: the input is left untouched, and
+        /// processing fails.
+        ///
+        /// No effect if no language scope is given.
+        #[arg(long, verbatim_doc_comment)]
+        pub guard_syntax: bool,
         /// Join (logical 'OR') multiple language scopes, instead of intersecting them.
         ///
         /// The default when multiple language scopes are given is to intersect their
@@ -1080,6 +2178,37 @@ mod cli {
         /// non-language scopers (regex pattern etc.), which always intersect.
         #[arg(short('j'), long, verbatim_doc_comment)]
         pub join_language_scopes: bool,
+        /// Of each language query match, scope only the `N`th (one-based) occurrence of
+        /// the capture `NAME`.
+        ///
+        /// Useful for custom queries capturing repeated nodes. For example,
+        /// `--python-query '(call arguments: (argument_list ((_) @arg ","?)+))'
+        /// --nth-capture arg=2` scopes the second argument of each call. Matches with
+        /// fewer occurrences are skipped, as are all other captures of the query
+        /// (`_SRGN_IGNORE` still applies). Quantifiers only repeat over adjacent
+        /// siblings, hence separators (`,`) need to be part of the repeated group.
+        ///
+        /// No effect if no language scope is given.
+        #[arg(long, value_name = "NAME=N", value_parser = parse_nth_capture, verbatim_doc_comment)]
+        pub nth_capture: Option<(String, NonZero<usize>)>,
+        /// Run a further pipeline on the output of this one, given as a single,
+        /// shell-quoted string of scopes and actions.
+        ///
+        /// For example, `srgn --python comments --upper --then "--python strings
+        /// --lower"` applies both within a single read (and write) of each file,
+        /// equivalent to two consecutive runs. May be given multiple times, applied in
+        /// order. Scopes, capture groups etc. of each pipeline refer to its own
+        /// arguments only. Input selection (files, stdin) is determined by the first
+        /// pipeline only, and global options (such as `--glob`) have no effect within
+        /// further ones.
+        #[arg(
+            long,
+            value_name = "ARGS",
+            allow_hyphen_values = true,
+            conflicts_with_all = ["line_numbers", "only_matching"],
+            verbatim_doc_comment
+        )]
+        pub then: Vec<String>,
         /// Prepend line numbers to output.
         #[arg(long, hide(true), verbatim_doc_comment)]
         // Hidden: internal use. Not really useful to expose.
@@ -1088,6 +2217,48 @@ mod cli {
         #[arg(long, hide(true), verbatim_doc_comment)]
         // Hidden: internal use. Not really useful to expose.
         pub only_matching: bool,
+        /// Separator of records, for record-oriented processing (such as prepending
+        /// line numbers in search mode, `--sort-lines` and `--sort-imports`).
+        ///
+        /// Defaults to newline. Escape sequences are supported, so `--record-separator
+        /// '\0'` handles NUL-separated input, as produced by `find -print0` and
+        /// similar.
+        #[arg(
+            long,
+            value_name = "CHAR",
+            default_value = r"\n",
+            value_parser = parse_record_separator,
+            verbatim_doc_comment
+        )]
+        pub record_separator: char,
+        /// Do not detect the language of files without extension by their editor
+        /// modeline.
+        ///
+        /// By default, such files are inspected for a shebang line (`#!/usr/bin/env
+        /// python3`) and Emacs- or Vim-style modelines (`-*- mode: python -*-`, `vim:
+        /// ft=python`) in their first or last five lines.
+        ///
+        /// To force a language regardless of extension and modeline, select files
+        /// using `--glob`: language scopes then apply to all matching files.
+        #[arg(long, verbatim_doc_comment)]
+        pub no_modelines: bool,
+        /// Exclude the leading block of anything in scope at the top of input from
+        /// scope.
+        ///
+        /// Combined with a comments scope, this spares license headers from
+        /// modification: `--rust comments --exclude-header` scopes all comments but the
+        /// first block. The block ends at the first blank line or non-scoped content. A
+        /// leading shebang line is skipped.
+        #[arg(long, verbatim_doc_comment)]
+        pub exclude_header: bool,
+        /// Join anything in scope on consecutive lines into a single scope.
+        ///
+        /// Combined with a comments scope, this has actions treat a run of line
+        /// comments (`//`, `#`, ...) as a unit, like a single block comment: `--python
+        /// comments --coalesce-lines`. Scopes are joined if separated by nothing but a
+        /// line break and indentation.
+        #[arg(long, verbatim_doc_comment)]
+        pub coalesce_lines: bool,
         /// Do not ignore hidden files and directories.
         #[arg(short('H'), long, verbatim_doc_comment)]
         pub hidden: bool,
@@ -1099,9 +2270,21 @@ mod cli {
         /// In search mode, this emits results in sorted order. Otherwise, it processes
         /// files in sorted order.
         ///
-        /// Sorted processing disables parallel processing.
+        /// Files are still processed in parallel (see `--threads`), but output is
+        /// buffered and emitted in sorted order, identical to a sequential run.
         #[arg(long, verbatim_doc_comment)]
         pub sorted: bool,
+        /// Process stdin record by record (see `--record-separator`), instead of
+        /// reading it entirely first.
+        ///
+        /// Keeps memory use bounded, for example for piping in huge logs. Scopes and
+        /// actions then only ever see a single record, so patterns cannot match across
+        /// records, and offsets in the audit log are relative to each record.
+        ///
+        /// Language scopes parse and reports inspect the entire input, so require it to
+        /// be buffered fully: they cannot be combined with this option.
+        #[arg(long, verbatim_doc_comment)]
+        pub stream: bool,
         /// Override detection heuristics for stdin readability, and force to value.
         ///
         /// `true` will always attempt to read from stdin. `false` will never read from
@@ -1139,7 +2322,10 @@ mod cli {
         /// Variables are supported: if a regex pattern was used for scoping and
         /// captured content in named or numbered capture groups, access these in the
         /// replacement value using `$1` etc. for numbered, `$NAME` etc. for named
-        /// capture groups.
+        /// capture groups. Groups not participating in a match expand to nothing, or to
+        /// a default given like `${NAME:-default}` (also used for empty groups).
+        /// Values can be recased using `${NAME:upper}`, `${NAME:lower}` or
+        /// `${NAME:title}`.
         ///
         /// This action is specially treated as a positional argument for ergonomics and
         /// compatibility with `tr`.
@@ -1147,6 +2333,34 @@ mod cli {
         /// If given, will run before any other action.
         #[arg(value_name = "REPLACEMENT", env, verbatim_doc_comment)]
         pub replace: Option<String>,
+        /// Replace all matches of a regex pattern *within* anything in scope, like
+        /// `sed 's/PATTERN/REPLACEMENT/g'`.
+        ///
+        /// As opposed to REPLACEMENT, which replaces scopes entirely, this rewrites
+        /// each match inside of scopes, for example all occurrences of a word in a
+        /// comment. Variables in the replacement refer to capture groups of this
+        /// pattern, see REPLACEMENT.
+        ///
+        /// Runs after REPLACEMENT, before any other action.
+        #[arg(
+            long,
+            num_args = 2,
+            value_names = ["PATTERN", "REPLACEMENT"],
+            verbatim_doc_comment
+        )]
+        pub substitute: Option<Vec<String>>,
+        /// Expand references to environment variables, like `$HOME` or `${HOME}`, in
+        /// anything in scope.
+        ///
+        /// Undefined variables, numbered references like `$1` and invalid expressions
+        /// are left unchanged. `${NAME:-default}` falls back to `default` for undefined
+        /// or empty variables. `$$` expands to a literal `$`. Runs after SUBSTITUTE.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub expand_env: bool,
+        /// Fail if expanding environment variables encounters an undefined variable
+        /// or an invalid expression.
+        #[arg(long, env, requires = "expand_env", verbatim_doc_comment)]
+        pub expand_env_strict: bool,
         /// Uppercase anything in scope.
         #[arg(short, long, env, verbatim_doc_comment)]
         pub upper: bool,
@@ -1156,9 +2370,311 @@ mod cli {
         /// Titlecase anything in scope.
         #[arg(short, long, env, verbatim_doc_comment)]
         pub titlecase: bool,
+        /// Reverse anything in scope, by user-perceived character.
+        ///
+        /// Characters made up of multiple code points, such as letters with combining
+        /// accents, flags and other emoji sequences, are kept intact.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub reverse: bool,
+        /// Convert identifiers in scope to this naming convention.
+        ///
+        /// Identifiers are split into words at underscores, hyphens and case
+        /// boundaries. Runs of uppercase letters are acronyms and form a single word,
+        /// except for a last letter followed by a lowercase one: `HTTPServer` becomes
+        /// `http_server`. Leading and trailing underscores are kept.
+        #[arg(long, env, value_name = "STYLE", verbatim_doc_comment)]
+        pub convert_case: Option<CaseStyle>,
         /// Normalize (Normalization Form D) anything in scope, and throw away marks.
         #[arg(short, long, env, verbatim_doc_comment)]
         pub normalize: bool,
+        /// Escape regex metacharacters in anything in scope, such that the result
+        /// matches the original text literally when used as a regex pattern.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub regex_escape: bool,
+        /// Mask anything in scope, replacing every character (except line breaks)
+        /// with `*`.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub redact: bool,
+        /// Replace anything in scope with the lowercase hex digest of its hash.
+        ///
+        /// Deterministic, so equal inputs map to equal outputs: useful for
+        /// pseudonymizing data like emails while keeping it correlatable. Runs after
+        /// any other encoding.
+        #[cfg(feature = "hash")]
+        #[arg(long, value_name = "ALGORITHM", verbatim_doc_comment)]
+        pub hash: Option<HashAlgorithm>,
+        /// Keep only the first this many hex characters of the digest.
+        #[cfg(feature = "hash")]
+        #[arg(long, value_name = "N", requires = "hash", verbatim_doc_comment)]
+        pub hash_length: Option<usize>,
+        /// Decode anything in scope from base64.
+        ///
+        /// Anything not valid base64, or not decoding to valid UTF-8, is left
+        /// unchanged. Runs before any encoding.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub base64_decode: bool,
+        /// Encode anything in scope as base64.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub base64_encode: bool,
+        /// Use the URL-safe base64 alphabet (`-` and `_` instead of `+` and `/`) for
+        /// decoding and encoding.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub base64_url_safe: bool,
+        /// Decode percent-encoded (`%XX`) sequences in anything in scope, as used in
+        /// URLs.
+        ///
+        /// Malformed sequences are left unchanged. Runs before any encoding.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub url_decode: bool,
+        /// Percent-encode anything in scope, except for unreserved characters
+        /// (alphanumerics, `-`, `.`, `_` and `~`), as used in URLs.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub url_encode: bool,
+        /// Sort import statements in scope, removing exact duplicates.
+        ///
+        /// Works line-wise. Blank lines separate groups of imports, which are sorted
+        /// individually. Statements spanning multiple lines using brackets are kept
+        /// together.
+        #[arg(long, verbatim_doc_comment)]
+        pub sort_imports: bool,
+        /// Sort lines in scope.
+        ///
+        /// Lines comparing equal keep their original order. A trailing newline is kept
+        /// if present. Combine with a scope covering a block of lines, such as
+        /// `--multiline '(?:^use [^\n]*\n)+'`. See also `--sort-order`,
+        /// `--sort-case-insensitive` and `--sort-numeric`.
+        #[arg(long, verbatim_doc_comment)]
+        pub sort_lines: bool,
+        /// The order to sort in for `--sort-lines`.
+        #[arg(
+            long,
+            value_name = "ORDER",
+            requires = "sort_lines",
+            default_value_t,
+            value_enum,
+            verbatim_doc_comment
+        )]
+        pub sort_order: SortOrder,
+        /// Ignore case when sorting for `--sort-lines`.
+        #[arg(long, requires = "sort_lines", verbatim_doc_comment)]
+        pub sort_case_insensitive: bool,
+        /// Compare runs of digits by their numeric value when sorting for
+        /// `--sort-lines`, such that `item2` sorts before `item10`.
+        #[arg(long, requires = "sort_lines", verbatim_doc_comment)]
+        pub sort_numeric: bool,
+        /// Sort keys of TOML tables in scope, such as `Cargo.toml` dependencies.
+        ///
+        /// Works line-wise. Table headers and blank lines separate groups of keys,
+        /// which are sorted individually. Comments stick with the key below them, and
+        /// values spanning multiple lines (bracketed values, multi-line strings) are
+        /// kept together. Lines are moved as-is, indentation included. Combine
+        /// with a scope such as `--multiline '^\[dependencies\].*?(?=^\[|\z)'`.
+        #[arg(long, verbatim_doc_comment)]
+        pub sort_toml_keys: bool,
+        /// Normalize the casing of keywords in scope, per the conventions of the given
+        /// language.
+        ///
+        /// SQL keywords are uppercased, Python's `True`, `False` and `None`
+        /// proper-cased, and HTML tag names lowercased. Works on identifier-like
+        /// tokens, so keywords in strings and comments are converted as well: scope
+        /// accordingly.
+        #[arg(long, value_name = "LANGUAGE", value_enum, verbatim_doc_comment)]
+        pub normalize_keywords: Option<KeywordLanguage>,
+        /// Align and format Markdown tables in scope.
+        ///
+        /// Column widths are normalized and cells padded according to the alignment
+        /// markers of the delimiter row (`:---`, `:---:`, `---:`). Ragged rows are
+        /// padded with empty cells.
+        #[arg(long, verbatim_doc_comment)]
+        pub markdown_table: bool,
+        /// Prepend an attribute to anything in scope, on its own line.
+        ///
+        /// Intended for Rust items, for example `--rust fn --prepend-attribute
+        /// 'cfg(feature = "x")'` gates functions behind a feature. The attribute is
+        /// indented like the item, and inserted above any existing attributes. The
+        /// surrounding `#[...]` may be omitted.
+        ///
+        /// Runs after all other actions.
+        #[arg(long, value_name = "ATTRIBUTE", verbatim_doc_comment)]
+        pub prepend_attribute: Option<String>,
+        /// Wrap bare URLs found in scope, leaving already-wrapped ones alone.
+        ///
+        /// URLs already in angle brackets or part of a Markdown link are not touched.
+        /// Trailing punctuation is not considered part of a URL.
+        #[arg(long, value_name = "STYLE", verbatim_doc_comment)]
+        pub wrap_urls: Option<UrlWrapping>,
+        /// Collapse runs of whitespace inside string literals in scope into a single
+        /// space, and trim leading and trailing whitespace.
+        ///
+        /// Scope must cover string literals, as the `strings` language queries do.
+        /// If a scope includes the quotes, only the content between them is
+        /// collapsed and trimmed. Escape sequences are kept as-is.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub collapse_string_whitespace: bool,
+        /// Collapse runs of whitespace in scope into a single space, and trim leading
+        /// and trailing whitespace.
+        ///
+        /// Each scope is squeezed on its own. Whitespace includes Unicode whitespace,
+        /// such as non-breaking spaces. Not to be confused with `--squeeze`, which
+        /// squeezes repeated scopes.
+        #[arg(long, verbatim_doc_comment)]
+        pub squeeze_whitespace: bool,
+        /// Collapse runs of blank lines in scope into a single one.
+        ///
+        /// Lines containing only whitespace count as blank.
+        #[arg(long, verbatim_doc_comment)]
+        pub squeeze_blank_lines: bool,
+        /// Split string literals in scope wider than this many characters into
+        /// multiple concatenated literals, one per line.
+        ///
+        /// Scope must cover entire double-quoted literals, including their quotes,
+        /// for example using `--typescript-query '(string) @s'`. Pieces preferably
+        /// break after whitespace; escape sequences are never split. See also
+        /// `--concatenation`.
+        #[arg(long, value_name = "WIDTH", verbatim_doc_comment)]
+        pub rewrap_strings: Option<NonZero<usize>>,
+        /// How to join pieces of string literals split by `--rewrap-strings`.
+        #[arg(
+            long,
+            value_name = "STYLE",
+            requires = "rewrap_strings",
+            default_value_t,
+            value_enum,
+            verbatim_doc_comment
+        )]
+        pub concatenation: Concatenation,
+        /// Expand tabs in scope to spaces, up to tab stops every this many columns,
+        /// except inside string and character literals.
+        ///
+        /// Literal contents are preserved byte-for-byte. Columns are counted from the
+        /// start of each line, so scope should cover entire lines.
+        #[arg(long, value_name = "WIDTH", verbatim_doc_comment)]
+        pub expand_tabs: Option<usize>,
+        /// Convert the indentation of lines in scope mixing tabs and spaces to this
+        /// unit, keeping its width.
+        ///
+        /// Lines indented using only tabs or only spaces are left alone. Scope should
+        /// cover entire lines. See also `--indent-width`, and `--mixed-indentation`
+        /// for only reporting such lines.
+        #[arg(long, value_name = "UNIT", verbatim_doc_comment)]
+        pub fix_mixed_indentation: Option<IndentUnit>,
+        /// Indent lines in scope by this many units, or dedent if negative.
+        ///
+        /// Dedenting never removes anything but leading whitespace. Lines consisting of
+        /// only whitespace are not indented. Scope should cover entire lines. See also
+        /// `--indent-unit` and `--indent-width`.
+        #[arg(
+            long,
+            value_name = "N",
+            allow_negative_numbers = true,
+            verbatim_doc_comment
+        )]
+        pub indent: Option<isize>,
+        /// The unit to indent by for `--indent`.
+        #[arg(
+            long,
+            value_name = "UNIT",
+            requires = "indent",
+            default_value_t,
+            value_enum,
+            verbatim_doc_comment
+        )]
+        pub indent_unit: IndentUnit,
+        /// The width of a tab, in columns, for `--fix-mixed-indentation` and
+        /// `--indent`.
+        #[arg(long, value_name = "WIDTH", default_value_t = 4, verbatim_doc_comment)]
+        pub indent_width: usize,
+        /// Convert comments in scope to this style, for C-family languages (`//` and
+        /// `/* */`).
+        ///
+        /// Scope should cover entire comments, for example using `--rust comments
+        /// '(?s).*'` (the pattern allows scopes to span lines). Runs of line comments
+        /// on consecutive lines in a single scope are coalesced into one block comment,
+        /// for example when scoping with `'//.*(\n\s*//.*)*'`. Doc comments keep their
+        /// flavor (`///` and `/**`). Comments which cannot be converted safely are left
+        /// alone.
+        #[arg(long, value_name = "STYLE", verbatim_doc_comment)]
+        pub convert_comments: Option<CommentStyle>,
+        /// Convert properties of JavaScript/TypeScript object literals in scope to
+        /// this style (shorthand `{ x }`, or longhand `{ x: x }`).
+        ///
+        /// Scope should cover entire object literals, for example using `--typescript
+        /// object`. Nested literals are converted as well. Computed, spread and method
+        /// properties are left alone, as are objects containing comments.
+        #[arg(long, value_name = "STYLE", verbatim_doc_comment)]
+        pub convert_properties: Option<PropertyStyle>,
+        /// Convert separators between segments of paths in scope, such as between file
+        /// paths (`/`) and module paths (`::` in Rust, `.` in Python).
+        ///
+        /// Only separators with a letter, digit or underscore immediately on both
+        /// sides are converted, so for example `a / b` and `//` are left alone.
+        #[arg(
+            long,
+            num_args = 2,
+            value_names = ["FROM", "TO"],
+            verbatim_doc_comment
+        )]
+        pub convert_separators: Option<Vec<String>>,
+        /// Perform arithmetic on numbers in scope, for example to bump ports or
+        /// version components. Requires `--number-operand`.
+        ///
+        /// The entire scope (ignoring surrounding whitespace) must be a number, like
+        /// `-12.5`, else it is left unchanged. Scope to numbers accordingly, for
+        /// example using JSON or YAML values. Results are written in canonical form:
+        /// `007` plus `1` is `8`, see `--number-preserve-width`.
+        ///
+        /// Example: bump all numbers in a JSON document by 1000:
+        ///
+        ///     srgn --json numbers --number-op add --number-operand 1000
+        #[arg(
+            long,
+            value_name = "OPERATION",
+            requires = "number_operand",
+            value_enum,
+            verbatim_doc_comment
+        )]
+        pub number_op: Option<NumberOperation>,
+        /// The operand for `--number-op`, like `1` or `-0.5`.
+        #[arg(
+            long,
+            value_name = "NUMBER",
+            requires = "number_op",
+            allow_negative_numbers = true,
+            verbatim_doc_comment
+        )]
+        pub number_operand: Option<String>,
+        /// Keep the width of numbers with leading zeros for `--number-op`, such that
+        /// `007` plus `1` is `008`.
+        #[arg(long, requires = "number_op", verbatim_doc_comment)]
+        pub number_preserve_width: bool,
+        /// Pad numbers in scope to this many characters, for example to make IDs
+        /// sort correctly or to align columns.
+        ///
+        /// Numbers are runs of digits, optionally with a fractional part. Digits which
+        /// are part of a word (`v2`, `0x1f`) are left alone, as are numbers already as
+        /// wide as requested. A leading sign counts towards the width. See also
+        /// `--pad-char` and `--pad-alignment`.
+        #[arg(long, value_name = "WIDTH", verbatim_doc_comment)]
+        pub pad_numbers: Option<usize>,
+        /// The character to pad numbers with for `--pad-numbers`.
+        #[arg(
+            long,
+            value_name = "CHAR",
+            requires = "pad_numbers",
+            default_value_t = '0',
+            verbatim_doc_comment
+        )]
+        pub pad_char: char,
+        /// Where to align numbers within their padded width for `--pad-numbers`.
+        #[arg(
+            long,
+            value_name = "ALIGNMENT",
+            requires = "pad_numbers",
+            default_value_t,
+            value_enum,
+            verbatim_doc_comment
+        )]
+        pub pad_alignment: Alignment,
         /// Perform substitutions on German words, such as 'Abenteuergruesse' to
         /// 'Abenteuergrüße', for anything in scope.
         ///
@@ -1215,27 +2731,141 @@ mod cli {
             verbatim_doc_comment
         )]
         pub squeeze: bool,
+        /// Report markers like `TODO` found in scope, instead of modifying anything.
+        ///
+        /// Each marker is reported on its own line, as `path:line:marker:text`. Combine
+        /// with a comments scope (like `--rust comments`) to only consider markers in
+        /// comments.
+        ///
+        /// Markers are matched as whole words. Defaults to `TODO`, `FIXME` and `HACK`;
+        /// pass a comma-separated list to override, like `--markers=TODO,XXX`.
+        #[arg(
+            long,
+            value_name = "MARKER",
+            num_args = 0..,
+            require_equals = true,
+            value_delimiter = ',',
+            default_missing_value = "TODO,FIXME,HACK",
+            value_parser = NonEmptyStringValueParser::new(),
+            conflicts_with = stringify!(ComposableActions),
+            verbatim_doc_comment
+        )]
+        pub markers: Option<Vec<String>>,
+        /// Report lines whose indentation mixes tabs and spaces, instead of modifying
+        /// anything.
+        ///
+        /// Each line is reported on its own, as `path:line:mixed indentation:INDENT`.
+        /// Only indentation in scope is considered. See `--fix-mixed-indentation` for
+        /// fixing such lines instead.
+        #[arg(
+            long,
+            conflicts_with_all = [stringify!(ComposableActions), "markers"],
+            verbatim_doc_comment
+        )]
+        pub mixed_indentation: bool,
     }
 
     /// For use as <https://docs.rs/clap/latest/clap/struct.Arg.html#method.value_name>
     const TREE_SITTER_QUERY_VALUE_NAME: &str = "TREE-SITTER-QUERY";
 
+    /// Reads a custom query from the file at `path`, see [`read_query_file`].
+    fn parse_query_file<Q>(path: &str) -> Result<Q, QueryFileError>
+    where
+        Q: FromStr<Err = TSQueryError>,
+    {
+        read_query_file(path)
+    }
+
     #[derive(Parser, Debug)]
     #[group(required = false, multiple = false)]
     #[command(next_help_heading = "Language scopes")]
     pub struct LanguageScopes {
+        #[command(flatten)]
+        pub c: Option<CScope>,
+        #[command(flatten)]
+        pub cpp: Option<CppScope>,
         #[command(flatten)]
         pub csharp: Option<CSharpScope>,
         #[command(flatten)]
+        pub css: Option<CssScope>,
+        #[command(flatten)]
+        pub dockerfile: Option<DockerfileScope>,
+        #[command(flatten)]
         pub go: Option<GoScope>,
         #[command(flatten)]
         pub hcl: Option<HclScope>,
         #[command(flatten)]
+        pub html: Option<HtmlScope>,
+        #[command(flatten)]
+        pub ini: Option<IniScope>,
+        #[command(flatten)]
+        pub java: Option<JavaScope>,
+        #[command(flatten)]
+        pub json: Option<JsonScope>,
+        #[command(flatten)]
+        pub markdown: Option<MarkdownScope>,
+        #[command(flatten)]
+        pub perl: Option<PerlScope>,
+        #[command(flatten)]
         pub python: Option<PythonScope>,
         #[command(flatten)]
+        pub ruby: Option<RubyScope>,
+        #[command(flatten)]
         pub rust: Option<RustScope>,
         #[command(flatten)]
+        pub sql: Option<SqlScope>,
+        #[command(flatten)]
+        pub toml: Option<TomlScope>,
+        #[command(flatten)]
         pub typescript: Option<TypeScriptScope>,
+        #[command(flatten)]
+        pub yaml: Option<YamlScope>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct CScope {
+        /// Scope C code using a prepared query.
+        // Not `C`, which commonly holds a compiler command (for `make` etc.).
+        #[arg(long, env = "SRGN_C", verbatim_doc_comment)]
+        pub c: Vec<PreparedCQuery>,
+
+        /// Scope C code using a custom tree-sitter query.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub c_query: Vec<CustomCQuery>,
+
+        /// Scope C code using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomCQuery>
+        )]
+        pub c_query_file: Vec<CustomCQuery>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct CppScope {
+        /// Scope C++ code using a prepared query.
+        // Not `CPP`, which commonly holds a compiler command (for `make` etc.).
+        #[arg(long, env = "SRGN_CPP", verbatim_doc_comment)]
+        pub cpp: Vec<PreparedCppQuery>,
+
+        /// Scope C++ code using a custom tree-sitter query.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub cpp_query: Vec<CustomCppQuery>,
+
+        /// Scope C++ code using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomCppQuery>
+        )]
+        pub cpp_query_file: Vec<CustomCppQuery>,
     }
 
     #[derive(Parser, Debug, Clone)]
@@ -1248,6 +2878,38 @@ mod cli {
         /// Scope C# code using a custom tree-sitter query.
         #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
         pub csharp_query: Vec<CustomCSharpQuery>,
+
+        /// Scope C# code using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomCSharpQuery>
+        )]
+        pub csharp_query_file: Vec<CustomCSharpQuery>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct CssScope {
+        /// Scope CSS (and SCSS) using a prepared query.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub css: Vec<PreparedCssQuery>,
+
+        /// Scope CSS (and SCSS) using a custom tree-sitter query.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub css_query: Vec<CustomCssQuery>,
+
+        /// Scope CSS (and SCSS) using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomCssQuery>
+        )]
+        pub css_query_file: Vec<CustomCssQuery>,
     }
 
     #[derive(Parser, Debug, Clone)]
@@ -1262,6 +2924,61 @@ mod cli {
         /// Scope HashiCorp Configuration Language code using a custom tree-sitter query.
         #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
         pub hcl_query: Vec<CustomHclQuery>,
+
+        #[allow(clippy::doc_markdown)] // CamelCase detected as 'needs backticks'
+        /// Scope HashiCorp Configuration Language code using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomHclQuery>
+        )]
+        pub hcl_query_file: Vec<CustomHclQuery>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct HtmlScope {
+        /// Scope HTML documents using a prepared query.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub html: Vec<PreparedHtmlQuery>,
+
+        /// Scope HTML documents using a custom tree-sitter query.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub html_query: Vec<CustomHtmlQuery>,
+
+        /// Scope HTML documents using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomHtmlQuery>
+        )]
+        pub html_query_file: Vec<CustomHtmlQuery>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct DockerfileScope {
+        /// Scope Dockerfiles using a prepared query.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub dockerfile: Vec<PreparedDockerfileQuery>,
+
+        /// Scope Dockerfiles using a custom tree-sitter query.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub dockerfile_query: Vec<CustomDockerfileQuery>,
+
+        /// Scope Dockerfiles using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomDockerfileQuery>
+        )]
+        pub dockerfile_query_file: Vec<CustomDockerfileQuery>,
     }
 
     #[derive(Parser, Debug, Clone)]
@@ -1274,6 +2991,118 @@ mod cli {
         /// Scope Go code using a custom tree-sitter query.
         #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
         pub go_query: Vec<CustomGoQuery>,
+
+        /// Scope Go code using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomGoQuery>
+        )]
+        pub go_query_file: Vec<CustomGoQuery>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct IniScope {
+        /// Scope INI configuration files using a prepared item.
+        ///
+        /// INI has no tree-sitter grammar, so custom queries are not available.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub ini: Vec<IniItem>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct JavaScope {
+        /// Scope Java code using a prepared query.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub java: Vec<PreparedJavaQuery>,
+
+        /// Scope Java code using a custom tree-sitter query.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub java_query: Vec<CustomJavaQuery>,
+
+        /// Scope Java code using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomJavaQuery>
+        )]
+        pub java_query_file: Vec<CustomJavaQuery>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct JsonScope {
+        /// Scope JSON documents using a prepared query.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub json: Vec<PreparedJsonQuery>,
+
+        /// Scope JSON documents using a custom tree-sitter query.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub json_query: Vec<CustomJsonQuery>,
+
+        /// Scope JSON documents using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomJsonQuery>
+        )]
+        pub json_query_file: Vec<CustomJsonQuery>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct MarkdownScope {
+        /// Scope Markdown documents using a prepared query.
+        #[arg(long, env, verbatim_doc_comment, visible_alias = "md")]
+        pub markdown: Vec<PreparedMarkdownQuery>,
+
+        /// Scope Markdown documents using a custom tree-sitter query.
+        ///
+        /// Queries are for either the block grammar (headings, code blocks, ...) or the
+        /// inline grammar (code spans, links, ...) of Markdown, never both.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub markdown_query: Vec<CustomMarkdownQuery>,
+
+        /// Scope Markdown documents using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomMarkdownQuery>
+        )]
+        pub markdown_query_file: Vec<CustomMarkdownQuery>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct PerlScope {
+        /// Scope Perl code using a prepared query.
+        // Not `PERL`, which commonly holds the interpreter path (for `make` etc.).
+        #[arg(long, env = "SRGN_PERL", verbatim_doc_comment, visible_alias = "pl")]
+        pub perl: Vec<PreparedPerlQuery>,
+
+        /// Scope Perl code using a custom tree-sitter query.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub perl_query: Vec<CustomPerlQuery>,
+
+        /// Scope Perl code using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomPerlQuery>
+        )]
+        pub perl_query_file: Vec<CustomPerlQuery>,
     }
 
     #[derive(Parser, Debug, Clone)]
@@ -1286,6 +3115,38 @@ mod cli {
         /// Scope Python code using a custom tree-sitter query.
         #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
         pub python_query: Vec<CustomPythonQuery>,
+
+        /// Scope Python code using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomPythonQuery>
+        )]
+        pub python_query_file: Vec<CustomPythonQuery>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct RubyScope {
+        /// Scope Ruby code using a prepared query.
+        #[arg(long, env, verbatim_doc_comment, visible_alias = "rb")]
+        pub ruby: Vec<PreparedRubyQuery>,
+
+        /// Scope Ruby code using a custom tree-sitter query.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub ruby_query: Vec<CustomRubyQuery>,
+
+        /// Scope Ruby code using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomRubyQuery>
+        )]
+        pub ruby_query_file: Vec<CustomRubyQuery>,
     }
 
     #[derive(Parser, Debug, Clone)]
@@ -1298,6 +3159,60 @@ mod cli {
         /// Scope Rust code using a custom tree-sitter query.
         #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
         pub rust_query: Vec<CustomRustQuery>,
+
+        /// Scope Rust code using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomRustQuery>
+        )]
+        pub rust_query_file: Vec<CustomRustQuery>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct SqlScope {
+        /// Scope SQL code using a prepared query.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub sql: Vec<PreparedSqlQuery>,
+
+        /// Scope SQL code using a custom tree-sitter query.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub sql_query: Vec<CustomSqlQuery>,
+
+        /// Scope SQL code using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomSqlQuery>
+        )]
+        pub sql_query_file: Vec<CustomSqlQuery>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct TomlScope {
+        /// Scope TOML documents using a prepared query.
+        #[arg(long, env, verbatim_doc_comment)]
+        pub toml: Vec<PreparedTomlQuery>,
+
+        /// Scope TOML documents using a custom tree-sitter query.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub toml_query: Vec<CustomTomlQuery>,
+
+        /// Scope TOML documents using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomTomlQuery>
+        )]
+        pub toml_query_file: Vec<CustomTomlQuery>,
     }
 
     #[derive(Parser, Debug, Clone)]
@@ -1310,6 +3225,38 @@ mod cli {
         /// Scope TypeScript code using a custom tree-sitter query.
         #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
         pub typescript_query: Vec<CustomTypeScriptQuery>,
+
+        /// Scope TypeScript code using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomTypeScriptQuery>
+        )]
+        pub typescript_query_file: Vec<CustomTypeScriptQuery>,
+    }
+
+    #[derive(Parser, Debug, Clone)]
+    #[group(required = false, multiple = false)]
+    pub struct YamlScope {
+        /// Scope YAML documents using a prepared query.
+        #[arg(long, env, verbatim_doc_comment, visible_alias = "yml")]
+        pub yaml: Vec<PreparedYamlQuery>,
+
+        /// Scope YAML documents using a custom tree-sitter query.
+        #[arg(long, env, verbatim_doc_comment, value_name = TREE_SITTER_QUERY_VALUE_NAME)]
+        pub yaml_query: Vec<CustomYamlQuery>,
+
+        /// Scope YAML documents using a custom tree-sitter query read from a file.
+        #[arg(
+            long,
+            env,
+            verbatim_doc_comment,
+            value_name = "FILE",
+            value_parser = parse_query_file::<CustomYamlQuery>
+        )]
+        pub yaml_query_file: Vec<CustomYamlQuery>,
     }
 
     #[cfg(feature = "german")]
@@ -1343,6 +3290,30 @@ mod cli {
         pub(super) fn command() -> Command {
             <Self as CommandFactory>::command()
         }
+
+        /// Parses the arguments of a further pipeline stage, as passed to `--then`.
+        ///
+        /// Environment variables are *not* consulted, as they already apply to the
+        /// first pipeline.
+        pub(super) fn parse_stage(args: &str) -> Result<Self, clap::Error> {
+            let args = shlex::split(args).ok_or_else(|| {
+                clap::Error::raw(ErrorKind::InvalidValue, "invalid quoting in arguments\n")
+            })?;
+
+            let matches = Self::command()
+                .mut_args(|arg| arg.env(None))
+                .try_get_matches_from(iter::once(env!("CARGO_PKG_NAME").to_owned()).chain(args))?;
+            let stage = Self::from_arg_matches(&matches)?;
+
+            if !stage.options.then.is_empty() {
+                return Err(clap::Error::raw(
+                    ErrorKind::ArgumentConflict,
+                    "pipeline stages cannot be nested\n",
+                ));
+            }
+
+            Ok(stage)
+        }
     }
 }
 
@@ -1422,7 +3393,7 @@ mod tests {
             // Sanity check for sequential tests
             let i_am_not_sure_if_this_test_really_runs_sequentially = false;
             if i_am_not_sure_if_this_test_really_runs_sequentially {
-                std::thread::sleep(std::time::Duration::from_secs(2));
+                thread::sleep(std::time::Duration::from_secs(2));
             }
 
             let result = level_filter_from_env_and_verbosity(additional_verbosity);
@@ -0,0 +1,385 @@
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::string::FromUtf8Error;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::actions::{Action, ActionError};
+use crate::scoping::view::ScopedViewBuilder;
+use crate::scoping::Scoper;
+
+/// A combination of scopers and the actions to apply to whatever they scope.
+///
+/// Scopers are applied in order, each narrowing down the scope of all previous ones.
+/// Actions are then applied in order to whatever remains in scope. Any number of
+/// pipelines can be [chained](apply_all), each working on the output of the previous
+/// one, which avoids reading inputs anew for each.
+///
+/// ## Example
+///
+/// ```rust
+/// use srgn::actions::{Lower, Upper};
+/// use srgn::pipeline::{apply_all, Pipeline};
+/// use srgn::scoping::regex::Regex;
+/// use srgn::RegexPattern;
+///
+/// let mut upper = Pipeline::new();
+/// upper
+///     .scoper(Regex::new(RegexPattern::new(r"\w+").unwrap()))
+///     .action(Upper::default());
+///
+/// let mut lower = Pipeline::new();
+/// lower
+///     .scoper(Regex::new(RegexPattern::new(r"B\w+").unwrap()))
+///     .action(Lower::default());
+///
+/// assert_eq!(apply_all(&[upper, lower], "ab bc").unwrap(), "AB bc");
+/// ```
+#[derive(Default)]
+pub struct Pipeline {
+    scopers: Vec<Box<dyn Scoper>>,
+    actions: Vec<Box<dyn Action>>,
+}
+
+impl Pipeline {
+    /// Creates a new, empty pipeline, which has everything in scope and does
+    /// nothing.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a scoper, further narrowing down the scope.
+    pub fn scoper(&mut self, scoper: impl Scoper + 'static) -> &mut Self {
+        self.scopers.push(Box::new(scoper));
+        self
+    }
+
+    /// Adds an action, applied after all previously added ones.
+    pub fn action(&mut self, action: impl Action + 'static) -> &mut Self {
+        self.actions.push(Box::new(action));
+        self
+    }
+
+    /// Applies this pipeline to `input`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if any action does, see [`ActionError`].
+    pub fn apply(&self, input: &str) -> Result<String, ActionError> {
+        let mut builder = ScopedViewBuilder::new(input);
+        for scoper in &self.scopers {
+            builder.explode(scoper);
+        }
+
+        let mut view = builder.build();
+        for action in &self.actions {
+            view.map_with_context(action)?;
+        }
+
+        Ok(view.to_string())
+    }
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("scopers", &self.scopers.len())
+            .field("actions", &self.actions.len())
+            .finish()
+    }
+}
+
+/// Applies all `pipelines` to `input` in order, feeding the output of each into the
+/// next one.
+///
+/// # Errors
+///
+/// Errors as soon as any pipeline does, see [`Pipeline::apply`].
+pub fn apply_all(pipelines: &[Pipeline], input: &str) -> Result<String, ActionError> {
+    pipelines
+        .iter()
+        .try_fold(input.to_owned(), |current, pipeline| {
+            pipeline.apply(&current)
+        })
+}
+
+/// Applies all `pipelines` to each of `inputs` concurrently, as [`apply_all`] would.
+///
+/// Inputs are distributed across rayon's global thread pool. Results are in the order
+/// of `inputs`, regardless of which finished first. Scopers and actions are shared
+/// read-only between workers: language scopers parse each input with their own
+/// [`Parser`](tree_sitter::Parser) but reuse their compiled queries.
+///
+/// ## Example
+///
+/// ```rust
+/// use srgn::actions::Upper;
+/// use srgn::pipeline::{apply_all_par, Pipeline};
+///
+/// let mut upper = Pipeline::new();
+/// upper.action(Upper::default());
+///
+/// let outputs = apply_all_par(&[upper], &["a", "b", "c"]);
+/// let outputs: Result<Vec<_>, _> = outputs.into_iter().collect();
+/// assert_eq!(outputs.unwrap(), ["A", "B", "C"]);
+/// ```
+#[cfg(feature = "parallel")]
+pub fn apply_all_par<I>(pipelines: &[Pipeline], inputs: &[I]) -> Vec<Result<String, ActionError>>
+where
+    I: AsRef<str> + Sync,
+{
+    inputs
+        .par_iter()
+        .map(|input| apply_all(pipelines, input.as_ref()))
+        .collect()
+}
+
+/// Applies all `pipelines` to the contents of each file in `paths`, as [`apply_all`]
+/// would.
+///
+/// Each file is processed on its own: a failure for one of them is reported in its
+/// slot and does not abort the batch. Results are in the order of `paths`. Files are
+/// only read, never written; what to do with the outputs is up to the caller.
+///
+/// ## Example
+///
+/// ```rust
+/// use srgn::actions::Upper;
+/// use srgn::pipeline::{process_files, Pipeline, ProcessError};
+///
+/// let mut upper = Pipeline::new();
+/// upper.action(Upper::default());
+///
+/// let results = process_files(&[upper], &["Cargo.toml", "does/not/exist"]);
+/// assert!(results[0].as_ref().unwrap().contains("[PACKAGE]"));
+/// assert!(matches!(results[1], Err(ProcessError::Io { .. })));
+/// ```
+pub fn process_files<P>(pipelines: &[Pipeline], paths: &[P]) -> Vec<Result<String, ProcessError>>
+where
+    P: AsRef<Path>,
+{
+    paths
+        .iter()
+        .map(|path| process_file(pipelines, path.as_ref()))
+        .collect()
+}
+
+fn process_file(pipelines: &[Pipeline], path: &Path) -> Result<String, ProcessError> {
+    let bytes = std::fs::read(path).map_err(|error| ProcessError::Io {
+        path: path.to_owned(),
+        error,
+    })?;
+
+    let input = String::from_utf8(bytes).map_err(|error| ProcessError::Encoding {
+        path: path.to_owned(),
+        error,
+    })?;
+
+    apply_all(pipelines, &input).map_err(|error| ProcessError::Action {
+        path: path.to_owned(),
+        error,
+    })
+}
+
+/// An error in processing a single input of a batch, see [`process_files`].
+#[derive(Debug)]
+pub enum ProcessError {
+    /// The file could not be read.
+    Io {
+        /// The file in question.
+        path: PathBuf,
+        /// The underlying error.
+        error: std::io::Error,
+    },
+    /// The file is not valid UTF-8, for example because it is binary.
+    Encoding {
+        /// The file in question.
+        path: PathBuf,
+        /// The underlying error, pointing to the first invalid byte.
+        error: FromUtf8Error,
+    },
+    /// An action failed on the file's contents.
+    Action {
+        /// The file in question.
+        path: PathBuf,
+        /// The underlying error.
+        error: ActionError,
+    },
+}
+
+impl ProcessError {
+    /// The file the error occurred for.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Io { path, .. } | Self::Encoding { path, .. } | Self::Action { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for ProcessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => {
+                write!(f, "Cannot read '{}': {error}", path.display())
+            }
+            Self::Encoding { path, error } => {
+                write!(f, "Cannot decode '{}' as UTF-8: {error}", path.display())
+            }
+            Self::Action { path, error } => {
+                write!(f, "Cannot process '{}': {error}", path.display())
+            }
+        }
+    }
+}
+
+impl Error for ProcessError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => Some(error),
+            Self::Encoding { error, .. } => Some(error),
+            Self::Action { error, .. } => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::actions::{Deletion, ExpandEnv, Replacement, Upper};
+    use crate::scoping::regex::Regex;
+    use crate::RegexPattern;
+
+    fn pipeline(pattern: &str, action: impl Action + 'static) -> Pipeline {
+        let mut pipeline = Pipeline::new();
+        pipeline
+            .scoper(Regex::new(RegexPattern::new(pattern).unwrap()))
+            .action(action);
+
+        pipeline
+    }
+
+    #[test]
+    fn test_empty_pipeline_is_identity() {
+        assert_eq!(Pipeline::new().apply("abc").unwrap(), "abc");
+        assert_eq!(apply_all(&[], "abc").unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_scopers_intersect() {
+        let mut pipeline = Pipeline::new();
+        pipeline
+            .scoper(Regex::new(RegexPattern::new(r"\w+").unwrap()))
+            .scoper(Regex::new(RegexPattern::new(r"[a-c]").unwrap()))
+            .action(Upper::default());
+
+        assert_eq!(pipeline.apply("abcd-ef").unwrap(), "ABCd-ef");
+    }
+
+    #[rstest]
+    #[case::independent("foo bar", r"foo", r"bar", "FOO ")]
+    #[case::second_sees_output_of_first("foo bar", r"o+", r"O+", "f bar")]
+    #[case::second_scope_gone("foo bar", r"\w+", r"[a-z]+", "FOO BAR")]
+    fn test_chaining_equals_sequential_runs(
+        #[case] input: &str,
+        #[case] first: &str,
+        #[case] second: &str,
+        #[case] expected: &str,
+    ) {
+        let pipelines = [
+            pipeline(first, Upper::default()),
+            pipeline(second, Deletion::default()),
+        ];
+
+        let chained = apply_all(&pipelines, input).unwrap();
+        let sequential = pipelines[1]
+            .apply(&pipelines[0].apply(input).unwrap())
+            .unwrap();
+
+        assert_eq!(chained, sequential);
+        assert_eq!(chained, expected);
+    }
+
+    #[test]
+    fn test_capture_groups_refer_to_own_pipeline() {
+        let pipelines = [
+            pipeline(
+                r"(\w+)=(\w+)",
+                Replacement::try_from("$2=$1".to_owned()).unwrap(),
+            ),
+            pipeline(r"(\w)=", Replacement::try_from("[$1]=".to_owned()).unwrap()),
+        ];
+
+        assert_eq!(apply_all(&pipelines, "k=v").unwrap(), "[v]=k");
+    }
+
+    #[test]
+    fn test_process_files_continues_past_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let valid = dir.path().join("valid.txt");
+        let binary = dir.path().join("binary.bin");
+        let failing = dir.path().join("failing.txt");
+        let missing = dir.path().join("missing.txt");
+
+        std::fs::write(&valid, "Hello, ${NAME}!").unwrap();
+        std::fs::write(&binary, [0xff, 0xfe, 0x00]).unwrap();
+        std::fs::write(&failing, "Hello, ${UNDEFINED}!").unwrap();
+
+        let mut pipeline = Pipeline::new();
+        pipeline.action(ExpandEnv::with_variables(
+            HashMap::from([("NAME".to_owned(), "World".to_owned())]),
+            true,
+        ));
+        let pipelines = [pipeline];
+
+        let results = process_files(&pipelines, &[&valid, &binary, &failing, &missing, &valid]);
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].as_ref().unwrap(), "Hello, World!");
+        assert!(matches!(&results[1], Err(ProcessError::Encoding { path, .. }) if *path == binary));
+        assert!(matches!(&results[2], Err(ProcessError::Action { path, .. }) if *path == failing));
+        assert!(matches!(&results[3], Err(ProcessError::Io { path, .. }) if *path == missing));
+        assert_eq!(results[4].as_ref().unwrap(), "Hello, World!");
+
+        let error = results[3].as_ref().unwrap_err();
+        assert_eq!(error.path(), missing);
+        assert!(error.source().is_some());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_equals_sequential_in_order() {
+        use crate::scoping::langs::python::{PreparedPythonQuery, Python, PythonQuery};
+
+        let mut comments = Pipeline::new();
+        comments
+            .scoper(Python::new(PythonQuery::Prepared(
+                PreparedPythonQuery::Comments,
+            )))
+            .action(Upper::default());
+        let pipelines = [comments, pipeline(r"\d+", Deletion::default())];
+
+        let inputs = (0..200)
+            .map(|i| format!("x = {i}  # comment {i}\n"))
+            .collect::<Vec<_>>();
+
+        let parallel = apply_all_par(&pipelines, &inputs)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let sequential = inputs
+            .iter()
+            .map(|input| apply_all(&pipelines, input).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel[7], "x =   # COMMENT \n");
+    }
+}
@@ -8,6 +8,24 @@ use log::{debug, trace};
 /// A collection of [`Range`]s.
 ///
 /// This type implements a couple utility functions to work with collections of ranges.
+/// Ranges are always kept sorted by their start, no matter how the collection was
+/// built. They might still overlap or border each other, until [merged](Ranges::merge).
+///
+/// Scopers are expected to produce merged, non-overlapping ranges, which the rest of
+/// the pipeline relies on. Custom [`Scoper`](crate::scoping::Scoper) implementations
+/// can collect their raw ranges into this type, [merge](Ranges::merge) them, and
+/// subtract (`-`) any parts to leave out.
+///
+/// ## Example
+///
+/// ```rust
+/// use srgn::ranges::Ranges;
+///
+/// let mut ranges = Ranges::from(vec![5..8, 0..3, 2..4]);
+/// ranges.merge();
+///
+/// assert_eq!(ranges.iter().cloned().collect::<Vec<_>>(), [0..4, 5..8]);
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Ranges<Idx: Ord + Copy + Debug> {
     inner: Vec<Range<Idx>>,
@@ -28,7 +46,24 @@ impl<Idx: Ord + Copy + Debug> Ranges<Idx> {
 
     /// Merges, such that overlapping or bordering ranges are collapsed, and the number
     /// of individual elements is minimized.
-    pub(crate) fn merge(&mut self) -> &mut Self {
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use srgn::ranges::Ranges;
+    ///
+    /// let mut ranges = Ranges::from(vec![0..2, 1..3, 3..5, 7..9, 8..8]);
+    /// ranges.merge();
+    ///
+    /// // Overlapping (`0..2`, `1..3`), bordering (`3..5`) and contained (`8..8`)
+    /// // ranges are collapsed.
+    /// assert_eq!(ranges, Ranges::from(vec![0..5, 7..9]));
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// Panics if merging yielded more ranges than before, which would be a bug.
+    pub fn merge(&mut self) -> &mut Self {
         debug_assert!(self.is_sorted(), "Merging relies on sorted ranges");
 
         debug!("Merging ranges: {:?}", self);
@@ -80,6 +115,110 @@ impl<Idx: Ord + Copy + Debug> Ranges<Idx> {
         self
     }
 
+    /// Returns the parts of `within` not covered by any of the ranges.
+    ///
+    /// The result is merged, and empty if the ranges cover all of `within` (or
+    /// `within` is empty itself).
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use srgn::ranges::Ranges;
+    ///
+    /// let ranges = Ranges::from(vec![2..4, 6..7, 12..15]);
+    ///
+    /// assert_eq!(ranges.complement(0..10), Ranges::from(vec![0..2, 4..6, 7..10]));
+    /// assert_eq!(ranges.complement(2..4), Ranges::default());
+    /// ```
+    #[must_use]
+    pub fn complement(&self, within: Range<Idx>) -> Self {
+        if within.is_empty() {
+            return Self { inner: Vec::new() };
+        }
+
+        let mut covered = self.clone();
+        covered.merge();
+
+        let mut result = Self::from(vec![within]) - covered;
+        result.merge();
+
+        result
+    }
+
+    /// Returns the parts covered by both `self` and `other`.
+    ///
+    /// The result is merged and contains no empty ranges.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use srgn::ranges::Ranges;
+    ///
+    /// let left = Ranges::from(vec![0..5, 8..12]);
+    /// let right = Ranges::from(vec![3..9, 11..20]);
+    ///
+    /// assert_eq!(left.intersection(&right), Ranges::from(vec![3..5, 8..9, 11..12]));
+    /// ```
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut left, mut right) = (self.clone(), other.clone());
+        left.merge();
+        right.merge();
+
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(l), Some(r)) = (left.inner.get(i), right.inner.get(j)) {
+            let start = l.start.max(r.start);
+            let end = l.end.min(r.end);
+            if start < end {
+                result.push(start..end);
+            }
+
+            // Whichever ends first cannot overlap anything further right.
+            if l.end <= r.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { inner: result }
+    }
+
+    /// Returns the parts covered by exactly one of `self` and `other`.
+    ///
+    /// The result is merged and contains no empty ranges, hence parts bordering each
+    /// other are joined, no matter which side they stem from.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use srgn::ranges::Ranges;
+    ///
+    /// let left = Ranges::from(vec![0..5, 8..12]);
+    /// let right = Ranges::from(vec![3..9]);
+    ///
+    /// assert_eq!(
+    ///     left.symmetric_difference(&right),
+    ///     Ranges::from(vec![0..3, 5..8, 9..12])
+    /// );
+    /// ```
+    #[must_use]
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        let (mut left, mut right) = (self.clone(), other.clone());
+        left.merge();
+        right.merge();
+
+        let mut result: Self = (left.clone() - right.clone())
+            .into_iter()
+            .chain(right - left)
+            .filter(|range| range.start < range.end)
+            .collect();
+        result.merge();
+
+        result
+    }
+
     fn is_sorted(&self) -> bool {
         self.inner.windows(2).all(|w| w[0].start <= w[1].start)
     }
@@ -131,6 +270,15 @@ impl<Idx: Ord + Copy + Debug> FromIterator<Range<Idx>> for Ranges<Idx> {
     }
 }
 
+impl<Idx: Ord + Copy + Debug> From<Vec<Range<Idx>>> for Ranges<Idx> {
+    /// Collects the given ranges, sorting them by their start.
+    ///
+    /// Ranges are not [merged](Ranges::merge).
+    fn from(ranges: Vec<Range<Idx>>) -> Self {
+        ranges.into_iter().collect()
+    }
+}
+
 /// 'Shatter' the input [`Range`] into pieces of length 1. The returned instance will
 /// have a number of elements equal to the length of the input.
 ///
@@ -300,6 +448,8 @@ impl<Idx: Ord + Copy + Debug> Sub for Ranges<Idx> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use proptest::prelude::*;
     use rstest::rstest;
 
@@ -575,6 +725,32 @@ mod tests {
         Ranges::from_iter(vec![range]);
     }
 
+    #[rstest]
+    #[case::nothing_covered(vec![], 0..10, vec![0..10])]
+    #[case::everything_covered(vec![0..10], 0..10, vec![])]
+    #[case::gaps(vec![2..4, 6..7], 0..10, vec![0..2, 4..6, 7..10])]
+    #[case::overlapping(vec![2..5, 3..6], 0..10, vec![0..2, 6..10])]
+    #[case::bordering_gaps_merged(vec![2..4, 4..6], 0..10, vec![0..2, 6..10])]
+    #[case::partially_outside(vec![0..3, 8..20], 2..10, vec![3..8])]
+    #[case::entirely_outside(vec![20..30], 0..10, vec![0..10])]
+    #[case::empty_within(vec![2..4], 5..5, vec![])]
+    fn test_complement(
+        #[case] ranges: Vec<Range<usize>>,
+        #[case] within: Range<usize>,
+        #[case] expected: Vec<Range<usize>>,
+    ) {
+        let ranges = Ranges::from(ranges);
+
+        assert_eq!(ranges.complement(within), Ranges::from(expected));
+    }
+
+    #[test]
+    fn test_from_vec_sorts_without_merging() {
+        let ranges = Ranges::from(vec![4..6, 0..2, 1..3]);
+
+        assert_eq!(ranges.into_iter().collect_vec(), vec![0..2, 1..3, 4..6]);
+    }
+
     #[test]
     fn test_iteration() {
         let ranges: Ranges<usize> = Ranges::default();
@@ -592,6 +768,94 @@ mod tests {
         // Moved out
     }
 
+    #[rstest]
+    #[case::disjoint(vec![0..2], vec![4..6], vec![])]
+    #[case::bordering(vec![0..2], vec![2..4], vec![])]
+    #[case::single_element(vec![0..3], vec![2..4], vec![2..3])]
+    #[case::contained(vec![0..10], vec![2..4, 6..8], vec![2..4, 6..8])]
+    #[case::unmerged_input(vec![0..3, 2..5], vec![1..2, 4..9], vec![1..2, 4..5])]
+    #[case::empty_ranges(vec![3..3], vec![0..5], vec![])]
+    fn test_intersection(
+        #[case] left: Vec<Range<usize>>,
+        #[case] right: Vec<Range<usize>>,
+        #[case] expected: Vec<Range<usize>>,
+    ) {
+        let (left, right) = (Ranges::from(left), Ranges::from(right));
+
+        assert_eq!(left.intersection(&right), Ranges::from(expected.clone()));
+        assert_eq!(right.intersection(&left), Ranges::from(expected));
+    }
+
+    #[rstest]
+    #[case::disjoint(vec![0..2], vec![4..6], vec![0..2, 4..6])]
+    #[case::bordering(vec![0..2], vec![2..4], vec![0..4])]
+    #[case::identical(vec![1..5], vec![1..5], vec![])]
+    #[case::contained(vec![0..10], vec![2..4], vec![0..2, 4..10])]
+    #[case::shared_start(vec![0..4], vec![0..2], vec![2..4])]
+    #[case::empty_ranges(vec![3..3], vec![], vec![])]
+    fn test_symmetric_difference(
+        #[case] left: Vec<Range<usize>>,
+        #[case] right: Vec<Range<usize>>,
+        #[case] expected: Vec<Range<usize>>,
+    ) {
+        let (left, right) = (Ranges::from(left), Ranges::from(right));
+
+        assert_eq!(
+            left.symmetric_difference(&right),
+            Ranges::from(expected.clone())
+        );
+        assert_eq!(right.symmetric_difference(&left), Ranges::from(expected));
+    }
+
+    /// Small, arbitrary ranges, possibly empty, overlapping and bordering each other.
+    fn small_ranges() -> impl Strategy<Value = Vec<Range<usize>>> {
+        prop::collection::vec((0usize..24, 0usize..8).prop_map(|(s, l)| s..s + l), 0..6)
+    }
+
+    /// All elements covered by `ranges`, as a naive oracle.
+    fn elements<'a, I: IntoIterator<Item = &'a Range<usize>>>(ranges: I) -> HashSet<usize> {
+        ranges.into_iter().flat_map(Clone::clone).collect()
+    }
+
+    /// Whether `ranges` are sorted, non-empty, and neither overlap nor border each
+    /// other.
+    fn is_merged(ranges: &Ranges<usize>) -> bool {
+        ranges.iter().all(|r| r.start < r.end)
+            && ranges.inner.windows(2).all(|w| w[0].end < w[1].start)
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1024))]
+        #[test]
+        fn test_intersection_against_oracle(left in small_ranges(), right in small_ranges()) {
+            let result = Ranges::from(left.clone()).intersection(&Ranges::from(right.clone()));
+
+            prop_assert!(is_merged(&result), "not merged: {:?}", result);
+            prop_assert_eq!(
+                elements(&result),
+                elements(&left).intersection(&elements(&right)).copied().collect()
+            );
+        }
+
+        #[test]
+        fn test_symmetric_difference_against_oracle(
+            left in small_ranges(),
+            right in small_ranges(),
+        ) {
+            let result =
+                Ranges::from(left.clone()).symmetric_difference(&Ranges::from(right.clone()));
+
+            prop_assert!(is_merged(&result), "not merged: {:?}", result);
+            prop_assert_eq!(
+                elements(&result),
+                elements(&left)
+                    .symmetric_difference(&elements(&right))
+                    .copied()
+                    .collect()
+            );
+        }
+    }
+
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(512))]
         #[test]
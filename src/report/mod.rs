@@ -0,0 +1,302 @@
+use std::fmt;
+
+use fancy_regex::escape;
+
+use crate::scoping::scope::RWScope;
+use crate::scoping::scope::Scope::In;
+use crate::scoping::view::ScopedView;
+use crate::RegexPattern;
+
+/// A report, collecting findings from the [`In`] scope parts of a view instead of
+/// transforming them.
+pub trait Report: Send + Sync {
+    /// Collects all findings from the given view, each rendered for output on a line
+    /// of its own, starting with the line number it was found on.
+    fn findings(&self, view: &ScopedView<'_>) -> Vec<String>;
+}
+
+/// Markers collected by default.
+pub const DEFAULT_MARKERS: &[&str] = &["TODO", "FIXME", "HACK"];
+
+/// A single marker found in a view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marker {
+    /// The line number the marker was found on, starting at 1.
+    pub line: usize,
+    /// The marker itself, like `TODO`.
+    pub marker: String,
+    /// The text following the marker, on the same line.
+    pub text: String,
+}
+
+impl fmt::Display for Marker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.line, self.marker, self.text)
+    }
+}
+
+/// Collects markers such as `TODO` and `FIXME` from the [`In`] scope parts of a view.
+///
+/// Markers are matched as whole words, and case-sensitively. Combine with a scoper
+/// for comments to only find markers where they belong.
+///
+/// ## Example
+///
+/// ```rust
+/// use srgn::report::MarkerReport;
+/// use srgn::scoping::view::ScopedViewBuilder;
+///
+/// let input = "a = 1 # TODO: fix\nb = 2\n# FIXME broken\n";
+///
+/// let view = ScopedViewBuilder::new(input).build();
+/// let markers = MarkerReport::default().collect(&view);
+///
+/// assert_eq!(
+///     markers.iter().map(ToString::to_string).collect::<Vec<_>>(),
+///     ["1:TODO:fix", "3:FIXME:broken"]
+/// );
+/// ```
+#[derive(Debug)]
+pub struct MarkerReport {
+    pattern: RegexPattern,
+}
+
+impl MarkerReport {
+    /// Creates a new report collecting the given markers.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `markers` is empty.
+    #[must_use]
+    pub fn new<S: AsRef<str>>(markers: &[S]) -> Self {
+        assert!(!markers.is_empty(), "need at least one marker");
+
+        let alternatives = markers
+            .iter()
+            .map(|m| escape(m.as_ref()).into_owned())
+            .collect::<Vec<_>>()
+            .join("|");
+
+        Self {
+            // Not `\b`, which requires word characters on the inside.
+            pattern: RegexPattern::new(&format!(r"(?<!\w)(?:{alternatives})(?!\w)"))
+                .expect("escaped markers to form valid regex"),
+        }
+    }
+
+    /// Collects all markers found within [`In`] scope of the given view, line by
+    /// line.
+    #[must_use]
+    pub fn collect(&self, view: &ScopedView<'_>) -> Vec<Marker> {
+        let mut markers = Vec::new();
+
+        for (i, line) in view.lines().into_iter().enumerate() {
+            // Only consider in-scope parts; these might be split up if out-of-scope
+            // parts sit in between, hence search each part individually.
+            for scope in &line.scopes().0 {
+                let RWScope(In(s, _)) = scope else {
+                    continue;
+                };
+
+                for m in self.pattern.find_iter(s).flatten() {
+                    let text = s[m.end()..]
+                        .trim_start_matches(|c: char| c == ':' || c.is_whitespace())
+                        .trim_end();
+
+                    markers.push(Marker {
+                        line: i + 1,
+                        marker: m.as_str().to_owned(),
+                        text: text.to_owned(),
+                    });
+                }
+            }
+        }
+
+        markers
+    }
+}
+
+impl Default for MarkerReport {
+    fn default() -> Self {
+        Self::new(DEFAULT_MARKERS)
+    }
+}
+
+impl Report for MarkerReport {
+    fn findings(&self, view: &ScopedView<'_>) -> Vec<String> {
+        self.collect(view).iter().map(ToString::to_string).collect()
+    }
+}
+
+/// A single line found to mix tabs and spaces in its indentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MixedIndentation {
+    /// The line number, starting at 1.
+    pub line: usize,
+    /// The offending indentation.
+    pub indentation: String,
+}
+
+impl fmt::Display for MixedIndentation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:mixed indentation:{}",
+            self.line,
+            self.indentation.escape_debug()
+        )
+    }
+}
+
+/// Collects lines whose indentation mixes tabs and spaces, a common lint.
+///
+/// Only indentation in [`In`] scope is considered, so scope should cover the
+/// beginnings of lines. See [`FixMixedIndentation`](crate::actions::FixMixedIndentation)
+/// for fixing such lines.
+///
+/// ## Example
+///
+/// ```rust
+/// use srgn::report::MixedIndentationReport;
+/// use srgn::scoping::view::ScopedViewBuilder;
+///
+/// let input = "if x:\n\t  y = 1\n    z = 2\n";
+///
+/// let view = ScopedViewBuilder::new(input).build();
+/// let lines = MixedIndentationReport.collect(&view);
+///
+/// assert_eq!(
+///     lines.iter().map(ToString::to_string).collect::<Vec<_>>(),
+///     ["2:mixed indentation:\\t  "]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MixedIndentationReport;
+
+impl MixedIndentationReport {
+    /// Collects all lines of the given view whose indentation is in [`In`] scope and
+    /// mixes tabs and spaces.
+    #[must_use]
+    pub fn collect(&self, view: &ScopedView<'_>) -> Vec<MixedIndentation> {
+        let mut lines = Vec::new();
+
+        for (i, line) in view.lines().into_iter().enumerate() {
+            let Some(RWScope(In(s, _))) = line.scopes().0.first() else {
+                continue;
+            };
+
+            let indentation = leading_indentation(s);
+            if indentation.contains(' ') && indentation.contains('\t') {
+                lines.push(MixedIndentation {
+                    line: i + 1,
+                    indentation: indentation.to_owned(),
+                });
+            }
+        }
+
+        lines
+    }
+}
+
+impl Report for MixedIndentationReport {
+    fn findings(&self, view: &ScopedView<'_>) -> Vec<String> {
+        self.collect(view).iter().map(ToString::to_string).collect()
+    }
+}
+
+/// The leading tabs and spaces of `line`.
+fn leading_indentation(line: &str) -> &str {
+    let end = line.find(|c| c != ' ' && c != '\t').unwrap_or(line.len());
+
+    &line[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::scoping::regex::Regex;
+    use crate::scoping::view::ScopedViewBuilder;
+
+    #[rstest]
+    #[case("", DEFAULT_MARKERS, vec![])]
+    #[case("nothing here", DEFAULT_MARKERS, vec![])]
+    #[case("// TODO: a", DEFAULT_MARKERS, vec!["1:TODO:a"])]
+    #[case("\n\n// FIXME  b  \n", DEFAULT_MARKERS, vec!["3:FIXME:b"])]
+    #[case("HACK", DEFAULT_MARKERS, vec!["1:HACK:"])]
+    #[case("// TODO a, FIXME b", DEFAULT_MARKERS, vec!["1:TODO:a, FIXME b", "1:FIXME:b"])]
+    // Whole words, case-sensitive
+    #[case("TODOS todo MYTODO", DEFAULT_MARKERS, vec![])]
+    // Custom markers
+    #[case("// TODO a\n// XXX b\n// C++ c", &["XXX", "C++"], vec!["2:XXX:b", "3:C++:c"])]
+    fn test_marker_report(
+        #[case] input: &str,
+        #[case] markers: &[&str],
+        #[case] expected: Vec<&str>,
+    ) {
+        let view = ScopedViewBuilder::new(input).build();
+        let actual = MarkerReport::new(markers)
+            .collect(&view)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case::empty("", vec![])]
+    #[case::spaces_only("a\n    b\n", vec![])]
+    #[case::tabs_only("a\n\t\tb\n", vec![])]
+    #[case::tab_then_spaces("a\n\t  b\n", vec!["2:mixed indentation:\\t  "])]
+    #[case::spaces_then_tab("  \tb", vec!["1:mixed indentation:  \\t"])]
+    #[case::several("\t b\n c\n \tc\n", vec!["1:mixed indentation:\\t ", "3:mixed indentation: \\t"])]
+    #[case::whitespace_only_line(" \t\n", vec!["1:mixed indentation: \\t"])]
+    #[case::not_leading("a\t b", vec![])]
+    fn test_mixed_indentation_report(#[case] input: &str, #[case] expected: Vec<&str>) {
+        let view = ScopedViewBuilder::new(input).build();
+        let actual = MixedIndentationReport.findings(&view);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_mixed_indentation_report_only_in_scope() {
+        let input = "\t x = 1\n\t y = 2\n";
+
+        let mut builder = ScopedViewBuilder::new(input);
+        builder.explode(&Regex::new(RegexPattern::new(".*y.*").unwrap()));
+        let view = builder.build();
+
+        let actual = MixedIndentationReport.collect(&view);
+
+        assert_eq!(
+            actual,
+            vec![MixedIndentation {
+                line: 2,
+                indentation: "\t ".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_marker_report_only_in_scope() {
+        let input = "x = 'TODO: not a comment' # TODO: a comment\n";
+
+        let mut builder = ScopedViewBuilder::new(input);
+        builder.explode(&Regex::new(RegexPattern::new("#.*").unwrap()));
+        let view = builder.build();
+
+        let actual = MarkerReport::default().collect(&view);
+
+        assert_eq!(
+            actual,
+            vec![Marker {
+                line: 1,
+                marker: "TODO".into(),
+                text: "a comment".into(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use log::trace;
+
+use super::scope::RangesWithContext;
+use super::Scoper;
+use crate::ranges::Ranges;
+
+/// Scopes to balanced bracket blocks (`{}`, `()`, `[]`) at a given nesting depth.
+///
+/// This is a lightweight, grammar-independent alternative to the language scopers,
+/// useful for languages without a dedicated grammar. Depth `0` refers to the outermost
+/// blocks, depth `1` to blocks directly nested within those, and so on.
+///
+/// Unbalanced input is handled leniently: a closing bracket without any matching
+/// opening bracket is ignored, a mismatched closing bracket closes the nearest
+/// matching opening one (discarding any unclosed blocks in between), and blocks still
+/// open at the end of input are not in scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Brackets {
+    /// The nesting depth of blocks to scope to.
+    pub depth: usize,
+    /// Whether to only scope to the interior of blocks, excluding the brackets
+    /// themselves.
+    pub interior: bool,
+    /// Whether to ignore brackets inside of double-quoted strings (honoring `\`
+    /// escapes).
+    pub skip_strings: bool,
+}
+
+impl Brackets {
+    /// Returns the closing counterpart of `c`, if it is an opening bracket.
+    const fn closing(c: char) -> Option<char> {
+        match c {
+            '(' => Some(')'),
+            '[' => Some(']'),
+            '{' => Some('}'),
+            _ => None,
+        }
+    }
+
+    const fn is_closing(c: char) -> bool {
+        matches!(c, ')' | ']' | '}')
+    }
+}
+
+impl Scoper for Brackets {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        // Stack of expected closing brackets and the positions of their openers.
+        let mut stack: Vec<(char, usize)> = Vec::new();
+        let mut ranges = Vec::new();
+
+        let mut in_string = false;
+        let mut escaped = false;
+
+        for (i, c) in input.char_indices() {
+            if in_string {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+
+            if self.skip_strings && c == '"' {
+                in_string = true;
+                continue;
+            }
+
+            if let Some(closer) = Self::closing(c) {
+                stack.push((closer, i));
+            } else if Self::is_closing(c) {
+                let Some(pos) = stack.iter().rposition(|(closer, _)| *closer == c) else {
+                    trace!("Ignoring unmatched closing bracket {c:?} at {i}");
+                    continue;
+                };
+
+                let (_, start) = stack[pos];
+                stack.truncate(pos);
+
+                if pos == self.depth {
+                    // Brackets are all single-byte.
+                    #[allow(clippy::range_plus_one)] // Need `Range`, not `RangeInclusive`
+                    let range = if self.interior {
+                        start + 1..i
+                    } else {
+                        start..i + 1
+                    };
+
+                    if !range.is_empty() {
+                        ranges.push(range);
+                    }
+                }
+            }
+        }
+
+        let ranges: Ranges<usize> = ranges.into_iter().collect();
+        trace!("Ranges in scope for {:?}: {:?}", self, ranges);
+
+        ranges.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use rstest::rstest;
+
+    use super::*;
+
+    fn scoped<'a>(brackets: &Brackets, input: &'a str) -> Vec<&'a str> {
+        brackets
+            .scope_raw(input)
+            .into_iter()
+            .map(|(Range { start, end }, _)| &input[start..end])
+            .collect()
+    }
+
+    #[rstest]
+    #[case("", 0, vec![])]
+    #[case("no brackets", 0, vec![])]
+    #[case("a(b)c", 0, vec!["(b)"])]
+    #[case("a(b)c", 1, vec![])]
+    #[case("(a)[b]{c}", 0, vec!["(a)", "[b]", "{c}"])]
+    #[case("()", 0, vec!["()"])]
+    //
+    #[case("f(a, [b, {c}], (d))", 0, vec!["(a, [b, {c}], (d))"])]
+    #[case("f(a, [b, {c}], (d))", 1, vec!["[b, {c}]", "(d)"])]
+    #[case("f(a, [b, {c}], (d))", 2, vec!["{c}"])]
+    #[case("f(a, [b, {c}], (d))", 3, vec![])]
+    #[case("{ {x} } { {y} }", 1, vec!["{x}", "{y}"])]
+    fn test_brackets_full(#[case] input: &str, #[case] depth: usize, #[case] expected: Vec<&str>) {
+        let brackets = Brackets {
+            depth,
+            ..Default::default()
+        };
+
+        assert_eq!(scoped(&brackets, input), expected);
+    }
+
+    #[rstest]
+    #[case("a(b)c", 0, vec!["b"])]
+    #[case("()", 0, vec![])]
+    #[case("f(a, [b, {c}], (d))", 0, vec!["a, [b, {c}], (d)"])]
+    #[case("f(a, [b, {c}], (d))", 1, vec!["b, {c}", "d"])]
+    #[case("f(a, [b, {c}], (d))", 2, vec!["c"])]
+    #[case("fn f() {\n    x\n}", 0, vec!["\n    x\n"])]
+    fn test_brackets_interior(
+        #[case] input: &str,
+        #[case] depth: usize,
+        #[case] expected: Vec<&str>,
+    ) {
+        let brackets = Brackets {
+            depth,
+            interior: true,
+            ..Default::default()
+        };
+
+        assert_eq!(scoped(&brackets, input), expected);
+    }
+
+    #[rstest]
+    // Unmatched closer is ignored
+    #[case(")(a)", 0, vec!["(a)"])]
+    #[case("(a))", 0, vec!["(a)"])]
+    // Unclosed opener never comes into scope
+    #[case("(a", 0, vec![])]
+    #[case("((a)", 0, vec![])]
+    #[case("((a)", 1, vec!["(a)"])]
+    // Mismatched closer closes nearest matching opener, dropping those in between
+    #[case("(a[b)", 0, vec!["(a[b)"])]
+    #[case("(a[b)]", 0, vec!["(a[b)"])]
+    #[case("{(a]}", 0, vec!["{(a]}"])]
+    fn test_brackets_unbalanced(
+        #[case] input: &str,
+        #[case] depth: usize,
+        #[case] expected: Vec<&str>,
+    ) {
+        let brackets = Brackets {
+            depth,
+            ..Default::default()
+        };
+
+        assert_eq!(scoped(&brackets, input), expected);
+    }
+
+    #[rstest]
+    #[case(r#"f("(", x)"#, false, vec![])]
+    #[case(r#"f("(", x)"#, true, vec![r#"("(", x)"#])]
+    #[case(r#"f("\")", x)"#, true, vec![r#"("\")", x)"#])]
+    #[case(r#"f(")") g(y)"#, false, vec![r#"(")"#, "(y)"])]
+    #[case(r#"f(")") g(y)"#, true, vec![r#"(")")"#, "(y)"])]
+    fn test_brackets_strings(
+        #[case] input: &str,
+        #[case] skip_strings: bool,
+        #[case] expected: Vec<&str>,
+    ) {
+        let brackets = Brackets {
+            skip_strings,
+            ..Default::default()
+        };
+
+        assert_eq!(scoped(&brackets, input), expected);
+    }
+}
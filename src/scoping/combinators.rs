@@ -0,0 +1,457 @@
+use std::fmt;
+
+use log::trace;
+
+use super::scope::RangesWithContext;
+use super::Scoper;
+use crate::ranges::Ranges;
+
+/// Scopes to anything *any* of the wrapped scopers scope to (logical 'OR').
+///
+/// Allows combining scopers programmatically, for example two prepared queries of a
+/// language, without writing a custom query. Overlapping and bordering ranges are
+/// merged. Contexts of the wrapped scopers (like regex capture groups) are dropped.
+///
+/// Wrapping no scopers at all scopes nothing.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::scoping::combinators::UnionScoper;
+/// use srgn::scoping::literal::Literal;
+/// use srgn::scoping::Scoper;
+///
+/// let scoper = UnionScoper::new(vec![
+///     Box::new(Literal::try_from("foo".to_owned()).unwrap()),
+///     Box::new(Literal::try_from("bar".to_owned()).unwrap()),
+/// ]);
+///
+/// let ranges: Vec<_> = scoper.scope_raw("foo x bar").into_iter().map(|(r, _)| r).collect();
+/// assert_eq!(ranges, vec![0..3, 6..9]);
+/// ```
+pub struct UnionScoper {
+    scopers: Vec<Box<dyn Scoper>>,
+}
+
+impl UnionScoper {
+    /// Creates a new instance joining the scopes of all `scopers`.
+    #[must_use]
+    pub fn new(scopers: Vec<Box<dyn Scoper>>) -> Self {
+        Self { scopers }
+    }
+}
+
+impl fmt::Debug for UnionScoper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Scopers are opaque trait objects.
+        f.debug_struct("UnionScoper")
+            .field("scopers", &self.scopers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Scoper for UnionScoper {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        let mut ranges: Ranges<usize> = self
+            .scopers
+            .iter()
+            .flat_map(|s| s.scope_raw(input))
+            .map(|(range, _)| range)
+            .collect();
+        ranges.merge();
+        trace!("Union of scopes: {:?}", ranges);
+
+        ranges.into()
+    }
+}
+
+/// Scopes to only what *all* of the wrapped scopers scope to (logical 'AND').
+///
+/// Only those byte ranges covered by every wrapped scoper remain in scope, so ranges
+/// partially overlapping are cut down to their common part. Contexts of the wrapped
+/// scopers (like regex capture groups) are dropped.
+///
+/// Wrapping no scopers at all scopes the entire input, as there's nothing to
+/// intersect with.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::scoping::combinators::IntersectionScoper;
+/// use srgn::scoping::regex::Regex;
+/// use srgn::scoping::Scoper;
+/// use srgn::RegexPattern;
+///
+/// let scoper = IntersectionScoper::new(vec![
+///     Box::new(Regex::new(RegexPattern::new(r"\w+ \w+").unwrap())),
+///     Box::new(Regex::new(RegexPattern::new(r"\w+!").unwrap())),
+/// ]);
+///
+/// let ranges: Vec<_> = scoper.scope_raw("hello world!").into_iter().map(|(r, _)| r).collect();
+/// assert_eq!(ranges, vec![6..11]);
+/// ```
+pub struct IntersectionScoper {
+    scopers: Vec<Box<dyn Scoper>>,
+}
+
+impl IntersectionScoper {
+    /// Creates a new instance intersecting the scopes of all `scopers`.
+    #[must_use]
+    pub fn new(scopers: Vec<Box<dyn Scoper>>) -> Self {
+        Self { scopers }
+    }
+}
+
+impl fmt::Debug for IntersectionScoper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Scopers are opaque trait objects.
+        f.debug_struct("IntersectionScoper")
+            .field("scopers", &self.scopers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Scoper for IntersectionScoper {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        let mut ranges: Ranges<usize> = std::iter::once(0..input.len())
+            .filter(|r| !r.is_empty())
+            .collect();
+
+        for scoper in &self.scopers {
+            let mut other: Ranges<usize> = scoper
+                .scope_raw(input)
+                .into_iter()
+                .map(|(range, _)| range)
+                .collect();
+            other.merge();
+
+            // What's left after removing everything *not* in `other`.
+            let outside = ranges.clone() - other;
+            ranges = ranges - outside;
+        }
+
+        trace!("Intersection of scopes: {:?}", ranges);
+
+        ranges.into()
+    }
+}
+
+/// Scopes to everything the wrapped scoper does *not* scope to (logical 'NOT').
+///
+/// The complement is computed over the entire input, `0..input.len()`. For example,
+/// wrapping a scoper for string literals allows acting on everything *but* string
+/// literals. Contexts of the wrapped scoper (like regex capture groups) are dropped,
+/// as the inverted ranges cannot carry any.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::scoping::combinators::InvertScoper;
+/// use srgn::scoping::literal::Literal;
+/// use srgn::scoping::view::ScopedViewBuilder;
+///
+/// let scoper = InvertScoper::new(Box::new(Literal::try_from("b".to_owned()).unwrap()));
+///
+/// let mut builder = ScopedViewBuilder::new("abc");
+/// builder.explode(&scoper);
+/// let mut view = builder.build();
+/// view.upper();
+///
+/// assert_eq!(view.to_string(), "AbC");
+/// ```
+pub struct InvertScoper {
+    scoper: Box<dyn Scoper>,
+}
+
+impl InvertScoper {
+    /// Creates a new instance inverting the scope of `scoper`.
+    #[must_use]
+    pub fn new(scoper: Box<dyn Scoper>) -> Self {
+        Self { scoper }
+    }
+}
+
+impl fmt::Debug for InvertScoper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Scopers are opaque trait objects.
+        f.debug_struct("InvertScoper").finish_non_exhaustive()
+    }
+}
+
+impl Scoper for InvertScoper {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        let mut inner: Ranges<usize> = self
+            .scoper
+            .scope_raw(input)
+            .into_iter()
+            .map(|(range, _)| range)
+            .collect();
+        inner.merge();
+
+        let everything: Ranges<usize> = std::iter::once(0..input.len())
+            .filter(|r| !r.is_empty())
+            .collect();
+        let ranges = everything - inner;
+        trace!("Inverted scopes: {:?}", ranges);
+
+        ranges.into()
+    }
+}
+
+/// Applies the wrapped scopers in sequence, each scoping only within what the
+/// previous one scoped to.
+///
+/// This is what [`ScopedViewBuilder::explode`] does when called repeatedly, but as a
+/// single scoper assembled at runtime, from trait objects. For example, first scope
+/// to Python strings, then to a regex within them. Any scoper works as a stage,
+/// including [`LanguageScoper`]s via their blanket [`Scoper`] implementation. Only
+/// contexts of the last stage (like regex capture groups) are kept.
+///
+/// Wrapping no scopers at all scopes the entire input, like an empty sequence of
+/// [`ScopedViewBuilder::explode`] calls.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::scoping::combinators::PipelineScoper;
+/// use srgn::scoping::langs::python::{PreparedPythonQuery, Python, PythonQuery};
+/// use srgn::scoping::regex::Regex;
+/// use srgn::scoping::view::ScopedViewBuilder;
+/// use srgn::scoping::Scoper;
+/// use srgn::RegexPattern;
+///
+/// let stages: Vec<Box<dyn Scoper>> = vec![
+///     Box::new(Python::new(PythonQuery::Prepared(PreparedPythonQuery::Strings))),
+///     Box::new(Regex::new(RegexPattern::new(r"\bfoo\b").unwrap())),
+/// ];
+/// let scoper = PipelineScoper::new(stages);
+///
+/// let mut builder = ScopedViewBuilder::new("foo = 'foo bar'");
+/// builder.explode(&scoper);
+/// let mut view = builder.build();
+/// view.upper();
+///
+/// assert_eq!(view.to_string(), "foo = 'FOO bar'");
+/// ```
+///
+/// [`ScopedViewBuilder::explode`]: crate::scoping::view::ScopedViewBuilder::explode
+/// [`LanguageScoper`]: crate::scoping::langs::LanguageScoper
+pub struct PipelineScoper {
+    scopers: Vec<Box<dyn Scoper>>,
+}
+
+impl PipelineScoper {
+    /// Creates a new instance applying `scopers` in order, each narrowing the scope of
+    /// the previous one.
+    #[must_use]
+    pub fn new(scopers: Vec<Box<dyn Scoper>>) -> Self {
+        Self { scopers }
+    }
+
+    /// Appends `scoper` as the last stage.
+    #[must_use]
+    pub fn then(mut self, scoper: Box<dyn Scoper>) -> Self {
+        self.scopers.push(scoper);
+        self
+    }
+}
+
+impl fmt::Debug for PipelineScoper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Scopers are opaque trait objects.
+        f.debug_struct("PipelineScoper")
+            .field("scopers", &self.scopers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Scoper for PipelineScoper {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        let mut ranges: RangesWithContext<'viewee> = vec![(0..input.len(), None)];
+
+        for scoper in &self.scopers {
+            ranges = ranges
+                .into_iter()
+                .flat_map(|(outer, _)| {
+                    scoper.scope_raw(&input[outer.clone()]).into_iter().map(
+                        move |(inner, context)| {
+                            (outer.start + inner.start..outer.start + inner.end, context)
+                        },
+                    )
+                })
+                .collect();
+            trace!("Pipeline stage narrowed scopes to: {:?}", ranges);
+        }
+
+        ranges
+    }
+
+    fn syntax_errors(&self, input: &str) -> Option<usize> {
+        // Only the first stage sees the entire input.
+        self.scopers.first()?.syntax_errors(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::scoping::literal::Literal;
+    use crate::scoping::regex::Regex;
+    use crate::scoping::view::ScopedViewBuilder;
+    use crate::RegexPattern;
+
+    fn regex(pattern: &str) -> Box<dyn Scoper> {
+        Box::new(Regex::new(RegexPattern::new(pattern).unwrap()))
+    }
+
+    fn literal(literal: &str) -> Box<dyn Scoper> {
+        Box::new(Literal::try_from(literal.to_owned()).unwrap())
+    }
+
+    fn ranges(scoper: &impl Scoper, input: &str) -> Vec<Range<usize>> {
+        scoper
+            .scope_raw(input)
+            .into_iter()
+            .map(|(range, _)| range)
+            .collect()
+    }
+
+    #[rstest]
+    #[case::nothing(vec![], "abc", vec![])]
+    #[case::single(vec![literal("b")], "abc", vec![1..2])]
+    #[case::disjoint(vec![literal("a"), literal("c")], "abc", vec![0..1, 2..3])]
+    #[case::overlapping(vec![literal("ab"), literal("bc")], "abcd", vec![0..3])]
+    #[case::bordering(vec![literal("ab"), literal("cd")], "abcd", vec![0..4])]
+    #[case::enveloping(vec![regex(r"\w+"), literal("b")], "abc d", vec![0..3, 4..5])]
+    #[case::order_irrelevant(vec![literal("c"), literal("a")], "abc", vec![0..1, 2..3])]
+    fn test_union(
+        #[case] scopers: Vec<Box<dyn Scoper>>,
+        #[case] input: &str,
+        #[case] expected: Vec<Range<usize>>,
+    ) {
+        assert_eq!(ranges(&UnionScoper::new(scopers), input), expected);
+    }
+
+    #[rstest]
+    #[case::nothing(vec![], "abc", vec![0..3])]
+    #[case::single(vec![literal("b")], "abc", vec![1..2])]
+    #[case::disjoint(vec![literal("a"), literal("c")], "abc", vec![])]
+    #[case::overlapping(vec![literal("ab"), literal("bc")], "abcd", vec![1..2])]
+    #[case::bordering(vec![literal("ab"), literal("cd")], "abcd", vec![])]
+    #[case::enveloping(vec![regex(r"\w+"), literal("b")], "abc d b", vec![1..2, 6..7])]
+    #[case::multiple_pieces(
+        vec![regex(r"[a-z ]+"), regex(r"\w+")],
+        "ab cd1",
+        vec![0..2, 3..5]
+    )]
+    #[case::three_way(
+        vec![regex(r"\w+"), regex(r"[a-c]+"), regex(r"[b-d]+")],
+        "abcd",
+        vec![1..3]
+    )]
+    #[case::one_empty(vec![regex(r"\w+"), literal("x")], "abc", vec![])]
+    #[case::empty_input(vec![regex(r"\w*")], "", vec![])]
+    fn test_intersection(
+        #[case] scopers: Vec<Box<dyn Scoper>>,
+        #[case] input: &str,
+        #[case] expected: Vec<Range<usize>>,
+    ) {
+        assert_eq!(ranges(&IntersectionScoper::new(scopers), input), expected);
+    }
+
+    #[rstest]
+    #[case::nothing_scoped(literal("x"), "abc", vec![0..3])]
+    #[case::everything_scoped(regex(r".+"), "abc", vec![])]
+    #[case::empty_input(literal("x"), "", vec![])]
+    #[case::middle(literal("b"), "abc", vec![0..1, 2..3])]
+    #[case::edges(regex(r"^a|c$"), "abc", vec![1..2])]
+    #[case::multiple(literal("-"), "a-b--c", vec![0..1, 2..3, 5..6])]
+    #[case::overlapping_inner(
+        Box::new(UnionScoper::new(vec![literal("ab"), literal("bc")])),
+        "abcd",
+        vec![3..4]
+    )]
+    fn test_invert(
+        #[case] scoper: Box<dyn Scoper>,
+        #[case] input: &str,
+        #[case] expected: Vec<Range<usize>>,
+    ) {
+        assert_eq!(ranges(&InvertScoper::new(scoper), input), expected);
+    }
+
+    #[rstest]
+    #[case::nothing(vec![], "abc", vec![0..3])]
+    #[case::empty_input(vec![], "", vec![0..0])]
+    #[case::single(vec![literal("b")], "abc", vec![1..2])]
+    #[case::narrowing(vec![regex(r#""[^"]*""#), literal("a")], "a \"a b a\" a", vec![3..4, 7..8])]
+    #[case::stages_see_only_scope(vec![regex(r"\w+"), regex(r"^\w")], "ab cd", vec![0..1, 3..4])]
+    #[case::three_stages(
+        vec![regex(r"\(.*\)"), regex(r"\w+"), literal("b")],
+        "b (ab cb) b",
+        vec![4..5, 7..8]
+    )]
+    #[case::nothing_left(vec![literal("x"), regex(r".")], "abc", vec![])]
+    fn test_pipeline(
+        #[case] scopers: Vec<Box<dyn Scoper>>,
+        #[case] input: &str,
+        #[case] expected: Vec<Range<usize>>,
+    ) {
+        assert_eq!(ranges(&PipelineScoper::new(scopers), input), expected);
+    }
+
+    #[test]
+    fn test_pipeline_is_like_exploding_in_sequence() {
+        let input = "x = \"ab\" + \"cab\" + ab";
+        let stages = || vec![regex(r#""[^"]*""#), literal("ab")];
+
+        let mut builder = ScopedViewBuilder::new(input);
+        builder.explode(&PipelineScoper::new(stages()));
+        let mut pipelined = builder.build();
+
+        let mut builder = ScopedViewBuilder::new(input);
+        for stage in stages() {
+            builder.explode(&stage);
+        }
+        let mut exploded = builder.build();
+
+        assert_eq!(pipelined.upper().to_string(), exploded.upper().to_string());
+        assert_eq!(pipelined.to_string(), "x = \"AB\" + \"cAB\" + ab");
+    }
+
+    #[test]
+    fn test_pipeline_keeps_context_of_last_stage() {
+        let scoper = PipelineScoper::new(vec![literal("a1 b2")]).then(regex(r"(?<n>\d)"));
+
+        let contexts = scoper
+            .scope_raw("a1 b2")
+            .into_iter()
+            .map(|(range, context)| (range, context.is_some()))
+            .collect::<Vec<_>>();
+        assert_eq!(contexts, vec![(1..2, true), (4..5, true)]);
+    }
+
+    #[test]
+    fn test_invert_twice_is_identity() {
+        let input = "a \"b\" c \"d\"";
+        let scoper = InvertScoper::new(Box::new(InvertScoper::new(regex(r#""[^"]*""#))));
+
+        assert_eq!(ranges(&scoper, input), vec![2..5, 8..11]);
+    }
+
+    #[test]
+    fn test_invert_through_view() {
+        let input = "x = \"keep\" + y";
+        let scoper = InvertScoper::new(regex(r#""[^"]*""#));
+
+        let mut builder = ScopedViewBuilder::new(input);
+        builder.explode(&scoper);
+        let mut view = builder.build();
+        view.upper();
+
+        assert_eq!(view.to_string(), "X = \"keep\" + Y");
+    }
+}
@@ -0,0 +1,463 @@
+use std::ops::Range;
+
+use log::trace;
+
+use super::scope::RangesWithContext;
+use super::Scoper;
+use crate::ranges::Ranges;
+
+/// Which part of shell command invocations to scope, see [`ShellCommand`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellCommandPart {
+    /// The entire invocation: the command name and all its arguments, including
+    /// redirections. Leading variable assignments (`LANG=C sort`) are not part of it.
+    Invocation,
+    /// Only the command name, such as `grep` in `grep -r x .`.
+    Name,
+}
+
+/// Scopes command invocations in shell scripts, for example to rename a deprecated
+/// tool across scripts.
+///
+/// This is a heuristic, not a full shell parser. It recognizes simple commands,
+/// pipelines and lists (`|`, `&&`, `;`, ...), subshells, command substitutions (`$(...)`
+/// and backticks, also inside double quotes), process substitutions, as well as
+/// commands following keywords like `if`, `then` or `do`. Compound commands
+/// themselves (`for`, `case`, `[[ ... ]]`, function definitions) are not commands and
+/// never scoped, but commands in their bodies are. Comments and quoted strings are
+/// skipped.
+///
+/// Commands nested in the arguments of others are scoped as well. For
+/// [`ShellCommandPart::Invocation`], they are part of the outer invocation.
+///
+/// ## Example
+///
+/// ```rust
+/// use srgn::scoping::command::{ShellCommand, ShellCommandPart};
+/// use srgn::scoping::regex::Regex;
+/// use srgn::scoping::view::ScopedViewBuilder;
+///
+/// let input = "egrep -r x . | sort\necho \"$(egrep y z)\" # egrep\n";
+///
+/// let mut builder = ScopedViewBuilder::new(input);
+/// builder.explode(&ShellCommand::new(ShellCommandPart::Name));
+/// builder.explode(&Regex::anchored("egrep").unwrap());
+/// let mut view = builder.build();
+/// view.replace("grep -E".to_owned()).unwrap();
+///
+/// assert_eq!(
+///     view.to_string(),
+///     "grep -E -r x . | sort\necho \"$(grep -E y z)\" # egrep\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShellCommand {
+    part: ShellCommandPart,
+}
+
+impl ShellCommand {
+    /// Creates a new instance scoping the given `part` of command invocations.
+    #[must_use]
+    pub const fn new(part: ShellCommandPart) -> Self {
+        Self { part }
+    }
+}
+
+impl Scoper for ShellCommand {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        let mut scanner = Scanner {
+            input: input.as_bytes(),
+            pos: 0,
+            commands: Vec::new(),
+        };
+        scanner.list(None);
+
+        let mut ranges: Ranges<usize> = scanner
+            .commands
+            .into_iter()
+            .map(|command| match self.part {
+                ShellCommandPart::Invocation => command.invocation,
+                ShellCommandPart::Name => command.name,
+            })
+            .collect();
+        ranges.merge();
+        trace!("Ranges in scope for {:?}: {:?}", self, ranges);
+
+        ranges.into()
+    }
+}
+
+/// Reserved words after which a command may follow.
+const PREFIX_KEYWORDS: &[&[u8]] = &[
+    b"if", b"then", b"else", b"elif", b"do", b"while", b"until", b"!", b"time",
+];
+
+/// Words starting things which aren't commands.
+const NON_COMMANDS: &[&[u8]] = &[
+    b"for",
+    b"select",
+    b"case",
+    b"function",
+    b"fi",
+    b"done",
+    b"esac",
+    b"{",
+    b"}",
+    b"[[",
+    b"]]",
+];
+
+/// A command found while scanning.
+#[derive(Debug)]
+struct Command {
+    invocation: Range<usize>,
+    name: Range<usize>,
+}
+
+/// Scans shell input for commands, recursing into substitutions and subshells.
+struct Scanner<'a> {
+    input: &'a [u8],
+    pos: usize,
+    commands: Vec<Command>,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.input.get(self.pos + offset).copied()
+    }
+
+    /// Skips spaces, tabs and line continuations.
+    fn skip_blanks(&mut self) {
+        loop {
+            match (self.peek(), self.peek_at(1)) {
+                (Some(b' ' | b'\t'), _) => self.pos += 1,
+                (Some(b'\\'), Some(b'\n')) => self.pos += 2,
+                _ => break,
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        while self.peek().is_some_and(|c| c != b'\n') {
+            self.pos += 1;
+        }
+    }
+
+    /// Scans a list of commands, up to and including `until` (end of input if
+    /// [`None`]).
+    fn list(&mut self, until: Option<u8>) {
+        // After `case ... in` and `;;`, patterns like `a|b)` come first.
+        let mut in_case = 0_usize;
+        let mut expect_pattern = false;
+
+        loop {
+            self.skip_blanks();
+
+            let Some(c) = self.peek() else {
+                return;
+            };
+
+            if Some(c) == until {
+                self.pos += 1;
+                return;
+            }
+
+            match c {
+                b';' if self.peek_at(1) == Some(b';') && in_case > 0 => {
+                    self.pos += 2;
+                    expect_pattern = true;
+                }
+                b'\n' | b';' | b'|' | b'&' | b')' => self.pos += 1,
+                b'#' => self.skip_comment(),
+                b'(' if self.peek_at(1) == Some(b'(') => self.skip_balanced(b'(', b')'),
+                b'(' => {
+                    self.pos += 1;
+                    self.list(Some(b')'));
+                }
+                _ if expect_pattern => {
+                    let word = self.word(until);
+                    if &self.input[word] == b"esac" {
+                        in_case -= 1;
+                    } else {
+                        self.skip_pattern();
+                    }
+                    expect_pattern = false;
+                }
+                _ => {
+                    if self.command(until) {
+                        in_case += 1;
+                        expect_pattern = true;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Skips the rest of a `case` pattern, up to and including the closing `)`.
+    fn skip_pattern(&mut self) {
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            if c == b')' {
+                return;
+            }
+        }
+    }
+
+    /// Skips a balanced construct like `((...))`, starting at its first `open`.
+    fn skip_balanced(&mut self, open: u8, close: u8) {
+        let mut depth = 0_usize;
+
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+
+            if c == open {
+                depth += 1;
+            } else if c == close {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Scans a single simple command, recording it if it is one. Returns whether it
+    /// starts a `case` statement.
+    fn command(&mut self, until: Option<u8>) -> bool {
+        let mut words = Vec::new();
+
+        loop {
+            self.skip_blanks();
+
+            match self.peek() {
+                None | Some(b'\n' | b';' | b'|' | b'&' | b'(' | b')' | b'#') => break,
+                Some(c) if Some(c) == until => break,
+                Some(_) => words.push(self.word(until)),
+            }
+        }
+
+        if words.len() == 1
+            && self.peek() == Some(b'(')
+            && self.input[self.pos + 1..].trim_ascii_start().first() == Some(&b')')
+        {
+            trace!("Skipping function definition at {:?}", words[0]);
+            self.skip_balanced(b'(', b')');
+            return false;
+        }
+
+        let mut words = words.into_iter();
+        let mut redirection_target = false;
+        let name = words.find(|word| {
+            let text = &self.input[word.clone()];
+
+            if std::mem::take(&mut redirection_target) {
+                return false;
+            }
+
+            if is_redirection(text) {
+                // `> out` has its target as the next word, `>out` doesn't.
+                redirection_target = text.last().is_some_and(|c| matches!(c, b'<' | b'>' | b'&'));
+                return false;
+            }
+
+            !(is_assignment(text) || PREFIX_KEYWORDS.contains(&text))
+        });
+
+        let Some(name) = name else {
+            return false;
+        };
+
+        let text = &self.input[name.clone()];
+        if NON_COMMANDS.contains(&text) {
+            // Does not include its body, hence no need to skip anything.
+            return text == b"case";
+        }
+
+        let end = words.last().map_or(name.end, |word| word.end);
+        trace!("Found command {:?}", String::from_utf8_lossy(text));
+        self.commands.push(Command {
+            invocation: name.start..end,
+            name,
+        });
+
+        false
+    }
+
+    /// Scans a single word, recursing into any substitutions inside it.
+    fn word(&mut self, until: Option<u8>) -> Range<usize> {
+        let start = self.pos;
+
+        while let Some(c) = self.peek() {
+            if Some(c) == until {
+                break;
+            }
+
+            match (c, self.peek_at(1)) {
+                (b' ' | b'\t' | b'\n' | b';' | b'|' | b'(' | b')', _) => break,
+                (b'&', _)
+                    if !matches!(self.input.get(self.pos.wrapping_sub(1)), Some(b'<' | b'>')) =>
+                {
+                    break
+                }
+                (b'\\', _) => self.pos += 2,
+                (b'\'', _) => {
+                    self.pos += 1;
+                    while self.peek().is_some_and(|c| c != b'\'') {
+                        self.pos += 1;
+                    }
+                    self.pos += 1;
+                }
+                (b'"', _) => self.double_quoted(),
+                (b'$', Some(b'(')) if self.peek_at(2) == Some(b'(') => {
+                    self.pos += 1;
+                    self.skip_balanced(b'(', b')');
+                }
+                (b'$' | b'<' | b'>', Some(b'(')) => {
+                    self.pos += 2;
+                    self.list(Some(b')'));
+                }
+                (b'$', Some(b'{')) => {
+                    self.pos += 1;
+                    self.skip_balanced(b'{', b'}');
+                }
+                (b'`', _) => {
+                    self.pos += 1;
+                    self.list(Some(b'`'));
+                }
+                _ => self.pos += 1,
+            }
+        }
+
+        start..self.pos.min(self.input.len())
+    }
+
+    /// Scans a double-quoted string, starting at its opening quote, recursing into any
+    /// substitutions inside it.
+    fn double_quoted(&mut self) {
+        self.pos += 1;
+
+        while let Some(c) = self.peek() {
+            match (c, self.peek_at(1)) {
+                (b'"', _) => {
+                    self.pos += 1;
+                    return;
+                }
+                (b'\\', _) => self.pos += 2,
+                (b'$', Some(b'(')) if self.peek_at(2) == Some(b'(') => {
+                    self.pos += 1;
+                    self.skip_balanced(b'(', b')');
+                }
+                (b'$', Some(b'(')) => {
+                    self.pos += 2;
+                    self.list(Some(b')'));
+                }
+                (b'`', _) => {
+                    self.pos += 1;
+                    self.list(Some(b'`'));
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+}
+
+/// Whether `word` is a variable assignment like `A=b` or `A+=b`.
+fn is_assignment(word: &[u8]) -> bool {
+    let Some(eq) = word.iter().position(|&c| c == b'=') else {
+        return false;
+    };
+    let name = word[..eq].strip_suffix(b"+").unwrap_or(&word[..eq]);
+
+    name.first()
+        .is_some_and(|c| c.is_ascii_alphabetic() || *c == b'_')
+        && name.iter().all(|c| c.is_ascii_alphanumeric() || *c == b'_')
+}
+
+/// Whether `word` is a redirection like `>out`, `2>&1` or `<`.
+fn is_redirection(word: &[u8]) -> bool {
+    let rest = word.iter().skip_while(|c| c.is_ascii_digit()).copied();
+
+    matches!(rest.clone().next(), Some(b'<' | b'>'))
+        && !word.starts_with(b"<(")
+        && !word.starts_with(b">(")
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", vec![])]
+    #[case("# ls -l\n", vec![])]
+    // Simple commands
+    #[case("ls", vec!["ls"])]
+    #[case("ls -l /tmp\n", vec!["ls"])]
+    #[case("  ls -l\nrm -rf x\n", vec!["ls", "rm"])]
+    #[case("LANG=C sort -u file", vec!["sort"])]
+    #[case("X=1 Y+=2\n", vec![])]
+    #[case(">out 2>&1 echo hi", vec!["echo"])]
+    #[case("> out echo hi", vec!["echo"])]
+    #[case("ls \\\n  -l; rm x", vec!["ls", "rm"])]
+    #[case("echo 'a; b' \"c | d\" # e; f\n", vec!["echo"])]
+    // Pipelines and lists
+    #[case("cat f | grep x | wc -l", vec!["cat", "grep", "wc"])]
+    #[case("make && make install || echo failed", vec!["make", "make", "echo"])]
+    #[case("sleep 1 & wait", vec!["sleep", "wait"])]
+    #[case("cmd 2>&1 | tee log", vec!["cmd", "tee"])]
+    #[case("! grep -q x f", vec!["grep"])]
+    // Substitutions
+    #[case("echo $(date +%s)", vec!["echo", "date"])]
+    #[case("x=$(git rev-parse HEAD)", vec!["git"])]
+    #[case("echo \"now: $(date | tr a b)\"", vec!["echo", "date", "tr"])]
+    #[case("echo `hostname`", vec!["echo", "hostname"])]
+    #[case("echo $(basename $(pwd))", vec!["echo", "basename", "pwd"])]
+    #[case("diff <(sort a) <(sort b)", vec!["diff", "sort", "sort"])]
+    #[case("echo $((1 + 2)) ${x:-$y}", vec!["echo"])]
+    // Compound commands
+    #[case("(cd dir && make)", vec!["cd", "make"])]
+    #[case("if grep -q x f; then echo yes; else echo no; fi", vec!["grep", "echo", "echo"])]
+    #[case("for f in *.txt; do\n  cp \"$f\" out/\ndone", vec!["cp"])]
+    #[case("while read -r line; do echo \"$line\"; done < file", vec!["read", "echo"])]
+    #[case("[[ -f x ]] && rm x", vec!["rm"])]
+    #[case("(( i++ ))", vec![])]
+    #[case("f() {\n  ls\n}\nf", vec!["ls", "f"])]
+    #[case("function f {\n  ls\n}", vec!["ls"])]
+    #[case(
+        "case $x in\n  a|b)\n    echo ab\n    ;;\n  *) rm -f x;;\nesac\necho done",
+        vec!["echo", "rm", "echo"]
+    )]
+    // Unicode
+    #[case("echo größe | grün", vec!["echo", "grün"])]
+    fn test_shell_command_names(#[case] input: &str, #[case] expected: Vec<&str>) {
+        let actual = ShellCommand::new(ShellCommandPart::Name)
+            .scope_raw(input)
+            .into_iter()
+            .map(|(range, _)| &input[range])
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case("ls -l /tmp\n", vec!["ls -l /tmp"])]
+    #[case("LANG=C sort -u file > out", vec!["sort -u file > out"])]
+    #[case("cat f | grep x", vec!["cat f", "grep x"])]
+    #[case("a && b; c", vec!["a", "b", "c"])]
+    #[case("if true; then echo \"$(date)\"; fi", vec!["true", "echo \"$(date)\""])]
+    #[case("x=$(git log --oneline)", vec!["git log --oneline"])]
+    fn test_shell_command_invocations(#[case] input: &str, #[case] expected: Vec<&str>) {
+        let actual = ShellCommand::new(ShellCommandPart::Invocation)
+            .scope_raw(input)
+            .into_iter()
+            .map(|(range, _)| &input[range])
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,63 @@
+use super::scope::RangesWithContext;
+use super::Scoper;
+
+/// Scopes the entire input as a single region.
+///
+/// Useful for pipelines consisting of actions only, where any scoper is merely
+/// required to get going. A regex like `.*` does not serve this purpose well, as it
+/// does not cross lines by default.
+///
+/// ## Example
+///
+/// ```rust
+/// use srgn::scoping::everything::Everything;
+/// use srgn::scoping::view::ScopedViewBuilder;
+///
+/// let input = "Hello\nWorld\n";
+///
+/// let mut builder = ScopedViewBuilder::new(input);
+/// builder.explode(&Everything);
+/// let mut view = builder.build();
+/// view.upper();
+///
+/// assert_eq!(view.to_string(), "HELLO\nWORLD\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Everything;
+
+impl Scoper for Everything {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        if input.is_empty() {
+            return Vec::new();
+        }
+
+        vec![(0..input.len(), None)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow::Borrowed;
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::scoping::scope::Scope::In;
+    use crate::scoping::scope::{RWScope, RWScopes};
+    use crate::scoping::view::{ScopedView, ScopedViewBuilder};
+
+    #[rstest]
+    #[case("", ScopedView::new(RWScopes(vec![])))]
+    #[case("a", ScopedView::new(RWScopes(vec![RWScope(In(Borrowed("a"), None))])))]
+    #[case(
+        "a\nb\r\n\n",
+        ScopedView::new(RWScopes(vec![RWScope(In(Borrowed("a\nb\r\n\n"), None))]))
+    )]
+    fn test_everything(#[case] input: &str, #[case] expected: ScopedView<'_>) {
+        let mut builder = ScopedViewBuilder::new(input);
+        builder.explode(&Everything);
+        let actual = builder.build();
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use log::trace;
+
+use super::scope::RangesWithContext;
+use super::Scoper;
+use crate::ranges::Ranges;
+use crate::RegexPattern;
+
+/// Here-document redirection operators, like `<<EOF`, `<<-EOF`, `<< 'EOF'` or
+/// `<<"EOF"`. Here-strings (`<<<`) are not matched.
+static OPERATOR: LazyLock<RegexPattern> = LazyLock::new(|| {
+    RegexPattern::new(
+        r#"(?<!<)<<(?<strip>-?)[ \t]*(?:'(?<single>[^'\n]+)'|"(?<double>[^"\n]+)"|\\?(?<bare>[A-Za-z_][A-Za-z0-9_]*))"#,
+    )
+    .expect("regex to be valid")
+});
+
+/// Scopes the bodies of shell here-documents.
+///
+/// Supported are plain (`<<EOF`), tab-stripping (`<<-EOF`) and quoted (`<<'EOF'`,
+/// `<<"EOF"`) here-documents, as well as multiple here-documents started on the same
+/// line. For the tab-stripping variant, leading tabs of body lines are stripped by the
+/// shell, hence not in scope either.
+///
+/// Bodies are scoped up to, but excluding, their delimiter line. Unterminated bodies
+/// extend to the end of input, as shells do.
+///
+/// ## Example
+///
+/// ```rust
+/// use srgn::scoping::heredoc::HereDoc;
+/// use srgn::scoping::view::ScopedViewBuilder;
+///
+/// let input = "cat <<EOF\nhello\nEOF\necho hello\n";
+///
+/// let mut builder = ScopedViewBuilder::new(input);
+/// builder.explode(&HereDoc);
+/// let mut view = builder.build();
+/// view.upper();
+///
+/// assert_eq!(view.to_string(), "cat <<EOF\nHELLO\nEOF\necho hello\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HereDoc;
+
+/// A here-document whose body is still to come.
+#[derive(Debug)]
+struct Pending<'a> {
+    delimiter: &'a str,
+    strip_tabs: bool,
+}
+
+impl Scoper for HereDoc {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        let mut pending: VecDeque<Pending<'_>> = VecDeque::new();
+        let mut body_start: Option<usize> = None;
+
+        let mut offset = 0;
+        for line in input.split_inclusive('\n') {
+            let start = offset;
+            offset += line.len();
+
+            let content = line
+                .strip_suffix('\n')
+                .map_or(line, |l| l.strip_suffix('\r').unwrap_or(l));
+
+            if let Some(current) = pending.front() {
+                let stripped = if current.strip_tabs {
+                    content.trim_start_matches('\t')
+                } else {
+                    content
+                };
+
+                if stripped == current.delimiter {
+                    trace!("Here-document '{}' ends at {start}", current.delimiter);
+                    if let Some(body_start) = body_start.take() {
+                        ranges.push(body_start..start);
+                    }
+                    pending.pop_front();
+                } else if current.strip_tabs {
+                    // Each line is its own scope, without leading tabs
+                    let tabs = content.len() - stripped.len();
+                    ranges.push(start + tabs..offset);
+                } else {
+                    body_start.get_or_insert(start);
+                }
+
+                continue;
+            }
+
+            for caps in OPERATOR.captures_iter(content).flatten() {
+                let delimiter = ["single", "double", "bare"]
+                    .into_iter()
+                    .find_map(|name| caps.name(name))
+                    .expect("one alternative to have matched")
+                    .as_str();
+                let strip_tabs = caps.name("strip").is_some_and(|m| !m.as_str().is_empty());
+
+                trace!("Here-document '{delimiter}' starts after {offset}");
+                pending.push_back(Pending {
+                    delimiter,
+                    strip_tabs,
+                });
+            }
+        }
+
+        if let Some(body_start) = body_start {
+            // Unterminated
+            ranges.push(body_start..input.len());
+        }
+
+        let ranges: Ranges<usize> = ranges.into_iter().filter(|r| !r.is_empty()).collect();
+        trace!("Ranges in scope for {:?}: {:?}", self, ranges);
+
+        ranges.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", vec![])]
+    #[case("echo hi\n", vec![])]
+    // Plain
+    #[case("cat <<EOF\nhello\nworld\nEOF\necho\n", vec!["hello\nworld\n"])]
+    #[case("cat << EOF > out\n  a $x\nEOF\n", vec!["  a $x\n"])]
+    #[case("cat <<EOF\nEOF\n", vec![])]
+    #[case("cat <<EOF\n  EOF\nEOF\n", vec!["  EOF\n"])] // Indented delimiter doesn't end
+    #[case("cat <<EOF\r\na\r\nEOF\r\n", vec!["a\r\n"])]
+    // Indented, tab-stripping
+    #[case("\tcat <<-EOF\n\t\thello\n\tworld\n\tEOF\n", vec!["hello\n", "world\n"])]
+    #[case("cat <<- END\n\ta\n  b\nEND\n", vec!["a\n", "  b\n"])]
+    #[case("cat <<EOF\n\ta\n\tEOF\nEOF\n", vec!["\ta\n\tEOF\n"])] // No stripping w/o `-`
+    // Quoted delimiters
+    #[case("cat <<'EOF'\n$not_expanded\nEOF\n", vec!["$not_expanded\n"])]
+    #[case("cat <<\"EOF\"\n$x\nEOF\n", vec!["$x\n"])]
+    #[case("cat <<\\EOF\n$x\nEOF\n", vec!["$x\n"])]
+    #[case("cat <<'END OF'\nx\nEND OF\n", vec!["x\n"])]
+    #[case("cat <<-'EOF'\n\tx\n\tEOF\n", vec!["x\n"])]
+    // Multiple
+    #[case("cat <<A <<B\na\nA\nb\nB\nrest\n", vec!["a\n", "b\n"])]
+    #[case("cat <<A\na\nA\ncat <<B\nb\nB\n", vec!["a\n", "b\n"])]
+    // Not here-documents
+    #[case("cat <<< \"$x\"\nx\n", vec![])]
+    #[case("echo $((1 << 2))\n", vec![])]
+    // Unterminated
+    #[case("cat <<EOF\na\nb", vec!["a\nb"])]
+    fn test_heredoc(#[case] input: &str, #[case] expected: Vec<&str>) {
+        let actual = HereDoc
+            .scope_raw(input)
+            .into_iter()
+            .map(|(range, _)| &input[range])
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,293 @@
+use std::ops::Range;
+
+use fancy_regex::Regex;
+use log::trace;
+
+use super::scope::RangesWithContext;
+use super::Scoper;
+use crate::ranges::Ranges;
+
+/// Scopes blocks of lines indented at least a given number of columns.
+///
+/// This approximates block selection for indentation-based languages (Python, YAML,
+/// ...) without requiring a grammar: a block is a run of consecutive lines each
+/// indented at least [`Indentation::level`] columns. Blank (whitespace-only) lines
+/// neither start nor end a block, but are part of it if enclosed by deeper indented
+/// lines. Blocks span entire lines, excluding the final line ending.
+///
+/// Tabs advance indentation to the next multiple of [`Indentation::tab_width`], so
+/// tab- and space-indented lines compare consistently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Indentation {
+    /// The minimum indentation, in columns, of lines in scope.
+    pub level: usize,
+    /// The number of columns between tab stops.
+    pub tab_width: usize,
+}
+
+impl Default for Indentation {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            tab_width: 4,
+        }
+    }
+}
+
+impl Scoper for Indentation {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        let lines = lines(input, self.tab_width);
+
+        let mut ranges = Vec::new();
+        let mut i = 0;
+
+        while i < lines.len() {
+            match lines[i].width {
+                Some(width) if width >= self.level => {
+                    let (range, next) = block(&lines, i, self.level);
+                    ranges.extend(range);
+                    i = next;
+                }
+                Some(_) | None => i += 1,
+            }
+        }
+
+        let ranges: Ranges<usize> = ranges.into_iter().collect();
+        trace!("Ranges in scope for {:?}: {:?}", self, ranges);
+
+        ranges.into()
+    }
+}
+
+/// Scopes the blocks of lines indented below header lines matching a pattern.
+///
+/// For every line matching [`IndentedBlock::header`], the following lines indented
+/// *deeper* than that header line are in scope (its 'children'). The block stops at
+/// the first non-blank line indented as much as the header or less (a dedent). The
+/// header line itself is not in scope. Otherwise, this works like [`Indentation`].
+#[derive(Debug, Clone)]
+pub struct IndentedBlock {
+    /// The pattern lines heading blocks must match (anywhere in the line).
+    pub header: Regex,
+    /// The number of columns between tab stops.
+    pub tab_width: usize,
+}
+
+impl IndentedBlock {
+    /// Creates a new instance scoping blocks below lines matching `header`, with the
+    /// default tab width of [`Indentation`].
+    #[must_use]
+    pub fn new(header: Regex) -> Self {
+        Self {
+            header,
+            tab_width: Indentation::default().tab_width,
+        }
+    }
+}
+
+impl Scoper for IndentedBlock {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        let lines = lines(input, self.tab_width);
+
+        let mut ranges: Ranges<usize> = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                let width = line.width?;
+
+                match self.header.is_match(line.content) {
+                    Ok(true) => block(&lines, i + 1, width + 1).0,
+                    Ok(false) => None,
+                    Err(e) => {
+                        trace!("Failed matching header against {:?}: {e}", line.content);
+                        None
+                    }
+                }
+            })
+            .collect();
+        // Blocks below nested headers lie within their parent's.
+        ranges.merge();
+        trace!("Ranges in scope for {:?}: {:?}", self, ranges);
+
+        ranges.into()
+    }
+}
+
+/// A line of input.
+#[derive(Debug)]
+struct Line<'a> {
+    /// Byte offset of the line's start in the input.
+    start: usize,
+    /// The line, excluding its line ending.
+    content: &'a str,
+    /// The line's indentation in columns, or [`None`] if the line is blank.
+    width: Option<usize>,
+}
+
+fn lines(input: &str, tab_width: usize) -> Vec<Line<'_>> {
+    let mut start = 0;
+
+    input
+        .split_inclusive('\n')
+        .map(|line| {
+            let content = line
+                .strip_suffix('\n')
+                .map_or(line, |l| l.strip_suffix('\r').unwrap_or(l));
+
+            let res = Line {
+                start,
+                content,
+                width: indentation(content, tab_width),
+            };
+            start += line.len();
+            res
+        })
+        .collect()
+}
+
+/// The indentation of `line` in columns, or [`None`] if it is blank.
+fn indentation(line: &str, tab_width: usize) -> Option<usize> {
+    let mut width = 0;
+
+    for c in line.chars() {
+        match c {
+            ' ' => width += 1,
+            '\t' if tab_width == 0 => {}
+            '\t' => width += tab_width - width % tab_width,
+            c if c.is_whitespace() => width += 1,
+            _ => return Some(width),
+        }
+    }
+
+    None
+}
+
+/// Finds the block of lines starting at index `from` which are indented at least
+/// `min_width` columns.
+///
+/// Returns the block's range, if any, and the index of the first line after it.
+fn block(lines: &[Line<'_>], from: usize, min_width: usize) -> (Option<Range<usize>>, usize) {
+    let mut first = None;
+    let mut last = None;
+    let mut i = from;
+
+    while let Some(line) = lines.get(i) {
+        match line.width {
+            Some(width) if width >= min_width => {
+                first.get_or_insert(line);
+                last = Some(line);
+            }
+            Some(_) => break,
+            None => {}
+        }
+
+        i += 1;
+    }
+
+    let range = first
+        .zip(last)
+        .map(|(first, last)| first.start..last.start + last.content.len());
+
+    (range, i)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn scoped<'a>(scoper: &impl Scoper, input: &'a str) -> Vec<&'a str> {
+        scoper
+            .scope_raw(input)
+            .into_iter()
+            .map(|(range, _)| &input[range])
+            .collect()
+    }
+
+    #[rstest]
+    #[case::empty("", 1, vec![])]
+    #[case::flat("a\nb\n", 1, vec![])]
+    #[case::level_zero_is_everything("a\n  b\n", 0, vec!["a\n  b"])]
+    #[case::single_block("def f():\n    x = 1\n    y = 2\nz = 3\n", 4, vec!["    x = 1\n    y = 2"])]
+    #[case::stops_at_dedent(
+        "if a:\n    b\n        c\n    d\ne\n    f\n",
+        4,
+        vec!["    b\n        c\n    d", "    f"]
+    )]
+    #[case::deeper_only(
+        "if a:\n    b\n        c\n    d\ne\n",
+        8,
+        vec!["        c"]
+    )]
+    #[case::interior_blank_lines_kept(
+        "a:\n  b\n\n  c\n\nd\n",
+        2,
+        vec!["  b\n\n  c"]
+    )]
+    #[case::whitespace_only_lines_are_blank("a:\n  b\n \n  c\n", 4, vec![])]
+    #[case::crlf("a:\r\n  b\r\n  c\r\nd\r\n", 2, vec!["  b\r\n  c"])]
+    #[case::tabs_and_spaces_consistent(
+        "def f():\n\tx = 1\n    y = 2\n  \tz = 3\nw\n",
+        4,
+        vec!["\tx = 1\n    y = 2\n  \tz = 3"]
+    )]
+    fn test_indentation(#[case] input: &str, #[case] level: usize, #[case] expected: Vec<&str>) {
+        let scoper = Indentation {
+            level,
+            ..Default::default()
+        };
+
+        assert_eq!(scoped(&scoper, input), expected);
+    }
+
+    #[rstest]
+    #[case(4, "\tx", Some(4))]
+    #[case(8, "\tx", Some(8))]
+    #[case(4, "  \tx", Some(4))]
+    #[case(4, "    \tx", Some(8))]
+    #[case(4, "\t  x", Some(6))]
+    #[case(0, "\t  x", Some(2))]
+    #[case(4, " \t ", None)]
+    fn test_indentation_width(
+        #[case] tab_width: usize,
+        #[case] line: &str,
+        #[case] expected: Option<usize>,
+    ) {
+        assert_eq!(indentation(line, tab_width), expected);
+    }
+
+    #[rstest]
+    #[case::block_under_header(
+        "class A:\n    def f(self):\n        pass\n\nclass B:\n    pass\n",
+        r"^class A\b",
+        vec!["    def f(self):\n        pass"]
+    )]
+    #[case::stops_at_dedent_to_header_level(
+        "spec:\n  replicas: 3\n  template:\n    name: x\nstatus:\n  ready: true\n",
+        r"^\s*spec:",
+        vec!["  replicas: 3\n  template:\n    name: x"]
+    )]
+    #[case::nested_header(
+        "spec:\n  replicas: 3\n  template:\n    name: x\n  other: 1\nstatus: {}\n",
+        r"template:",
+        vec!["    name: x"]
+    )]
+    #[case::nested_headers_merge(
+        "a:\n  b:\n    c\n  d\n",
+        r":$",
+        vec!["  b:\n    c\n  d"]
+    )]
+    #[case::header_without_children("a:\nb\n", r"^a:", vec![])]
+    #[case::trailing_blank_lines_excluded("a:\n  b\n\n\n", r"^a:", vec!["  b"])]
+    #[case::multiple_headers(
+        "if x:\n    one\nif y:\n\ttwo\nz\n",
+        r"^if",
+        vec!["    one", "\ttwo"]
+    )]
+    fn test_indented_block(#[case] input: &str, #[case] header: &str, #[case] expected: Vec<&str>) {
+        let scoper = IndentedBlock::new(Regex::new(header).unwrap());
+
+        assert_eq!(scoped(&scoper, input), expected);
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use tree_sitter::QueryError;
+
+use super::{tree_sitter_c, CodeQuery, Language, LanguageScoper, TSLanguage, TSQuery};
+use crate::find::Find;
+
+/// The C language.
+pub type C = Language<CQuery>;
+/// A query for C.
+pub type CQuery = CodeQuery<CustomCQuery, PreparedCQuery>;
+
+/// Prepared tree-sitter queries for C.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreparedCQuery {
+    /// Comments (single- and multi-line).
+    Comments,
+    /// Strings (incl. quotes and prefixes like `L`; excl. character literals).
+    Strings,
+    /// Paths of `#include` directives (excl. quotes and angle brackets).
+    Includes,
+}
+
+impl AsRef<str> for PreparedCQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "(comment) @comment",
+            Self::Strings => "(string_literal) @string",
+            Self::Includes => {
+                r"
+                (preproc_include path: (string_literal (string_content) @include))
+
+                (preproc_include
+                    path: (system_lib_string) @include
+                    (#offset! @include 0 1 0 -1)
+                )
+                "
+            }
+        }
+    }
+}
+
+impl From<PreparedCQuery> for TSQuery {
+    fn from(value: PreparedCQuery) -> Self {
+        Self::new(&C::lang(), value.as_ref()).expect("Prepared queries to be valid")
+    }
+}
+
+/// A custom tree-sitter query for C.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomCQuery(String);
+
+impl FromStr for CustomCQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TSQuery::new(&C::lang(), s) {
+            Ok(_) => Ok(Self(s.to_string())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsRef<str> for CustomCQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CustomCQuery> for TSQuery {
+    fn from(value: CustomCQuery) -> Self {
+        Self::new(&C::lang(), &value.0)
+            .expect("Valid query, as object cannot be constructed otherwise")
+    }
+}
+
+impl LanguageScoper for C {
+    fn lang() -> TSLanguage {
+        tree_sitter_c::language()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        &self.positive_query
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Find for C {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["c", "h"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["c"]
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use tree_sitter::QueryError;
+
+use super::{tree_sitter_cpp, CodeQuery, Language, LanguageScoper, TSLanguage, TSQuery};
+use crate::find::Find;
+
+/// The C++ language.
+pub type Cpp = Language<CppQuery>;
+/// A query for C++.
+pub type CppQuery = CodeQuery<CustomCppQuery, PreparedCppQuery>;
+
+/// Prepared tree-sitter queries for C++.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreparedCppQuery {
+    /// Comments (single- and multi-line).
+    Comments,
+    /// Strings (regular and raw; incl. quotes and prefixes like `L`; excl. character
+    /// literals).
+    Strings,
+    /// Paths of `#include` directives (excl. quotes and angle brackets).
+    Includes,
+    /// `class` definitions (in their entirety).
+    Classes,
+    /// `namespace` definitions (in their entirety).
+    Namespaces,
+}
+
+impl AsRef<str> for PreparedCppQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "(comment) @comment",
+            Self::Strings => "[(string_literal) (raw_string_literal)] @string",
+            Self::Includes => {
+                r"
+                (preproc_include path: (string_literal (string_content) @include))
+
+                (preproc_include
+                    path: (system_lib_string) @include
+                    (#offset! @include 0 1 0 -1)
+                )
+                "
+            }
+            Self::Classes => "(class_specifier) @class",
+            Self::Namespaces => "(namespace_definition) @namespace",
+        }
+    }
+}
+
+impl From<PreparedCppQuery> for TSQuery {
+    fn from(value: PreparedCppQuery) -> Self {
+        Self::new(&Cpp::lang(), value.as_ref()).expect("Prepared queries to be valid")
+    }
+}
+
+/// A custom tree-sitter query for C++.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomCppQuery(String);
+
+impl FromStr for CustomCppQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TSQuery::new(&Cpp::lang(), s) {
+            Ok(_) => Ok(Self(s.to_string())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsRef<str> for CustomCppQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CustomCppQuery> for TSQuery {
+    fn from(value: CustomCppQuery) -> Self {
+        Self::new(&Cpp::lang(), &value.0)
+            .expect("Valid query, as object cannot be constructed otherwise")
+    }
+}
+
+impl LanguageScoper for Cpp {
+    fn lang() -> TSLanguage {
+        tree_sitter_cpp::language()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        &self.positive_query
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Find for Cpp {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["cpp", "cc", "cxx", "hpp", "hh"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["cpp", "c++"]
+    }
+}
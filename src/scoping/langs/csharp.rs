@@ -36,10 +36,14 @@ pub enum PreparedCSharpQuery {
     Class,
     /// Method definitions (in their entirety).
     Method,
+    /// Names of method definitions, like `Run` in `void Run() {}`.
+    MethodName,
     /// Variable declarations (in their entirety).
     VariableDeclaration,
     /// Property definitions (in their entirety).
     Property,
+    /// Names of property definitions, like `Name` in `string Name { get; set; }`.
+    PropertyName,
     /// Constructor definitions (in their entirety).
     Constructor,
     /// Destructor definitions (in their entirety).
@@ -48,48 +52,80 @@ pub enum PreparedCSharpQuery {
     Field,
     /// Attribute names.
     Attribute,
+    /// String arguments of attributes, like `[Obsolete("...")]` (excl. quotes).
+    AttributeStrings,
     /// Identifier names.
     Identifier,
 }
 
-impl From<PreparedCSharpQuery> for TSQuery {
-    fn from(value: PreparedCSharpQuery) -> Self {
-        Self::new(
-            &CSharp::lang(),
-            match value {
-                PreparedCSharpQuery::Comments => "(comment) @comment",
-                PreparedCSharpQuery::Usings => {
-                    r"(using_directive [(identifier) (qualified_name)] @import)"
-                }
-                PreparedCSharpQuery::Strings => {
-                    formatcp!(
-                        r"
-                            [
-                                (interpolated_string_expression (interpolation) @{0})
-                                (string_literal)
-                                (raw_string_literal)
-                                (verbatim_string_literal)
+impl AsRef<str> for PreparedCSharpQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "(comment) @comment",
+            Self::Usings => r"(using_directive [(identifier) (qualified_name)] @import)",
+            Self::Strings => {
+                formatcp!(
+                    r"
+                        [
+                            (interpolated_string_expression (interpolation) @{0})
+                            (string_literal)
+                            (raw_string_literal)
+                            (verbatim_string_literal)
+                        ]
+                        @string
+                ",
+                    IGNORE
+                )
+            }
+            Self::Struct => "(struct_declaration) @struct",
+            Self::Enum => "(enum_declaration) @enum",
+            Self::Interface => "(interface_declaration) @interface",
+            Self::Class => "(class_declaration) @class",
+            Self::Method => "(method_declaration) @method",
+            Self::MethodName => "(method_declaration name: (identifier) @name)",
+            Self::VariableDeclaration => "(variable_declaration) @variable",
+            Self::Property => "(property_declaration) @property",
+            Self::PropertyName => "(property_declaration name: (identifier) @name)",
+            Self::Constructor => "(constructor_declaration) @constructor",
+            Self::Destructor => "(destructor_declaration) @destructor",
+            Self::Field => "(field_declaration) @field",
+            Self::Attribute => "(attribute) @attribute",
+            Self::AttributeStrings => {
+                r"
+                [
+                    (attribute_argument
+                        [
+                            (string_literal (string_literal_content) @string)
+                            (raw_string_literal (raw_string_content) @string)
+                        ]
+                    )
+                    (attribute_argument
+                        (assignment_expression
+                            right: [
+                                (string_literal (string_literal_content) @string)
+                                (raw_string_literal (raw_string_content) @string)
                             ]
-                            @string
-                    ",
-                        IGNORE
+                        )
                     )
-                }
-                PreparedCSharpQuery::Struct => "(struct_declaration) @struct",
-                PreparedCSharpQuery::Enum => "(enum_declaration) @enum",
-                PreparedCSharpQuery::Interface => "(interface_declaration) @interface",
-                PreparedCSharpQuery::Class => "(class_declaration) @class",
-                PreparedCSharpQuery::Method => "(method_declaration) @method",
-                PreparedCSharpQuery::VariableDeclaration => "(variable_declaration) @variable",
-                PreparedCSharpQuery::Property => "(property_declaration) @property",
-                PreparedCSharpQuery::Constructor => "(constructor_declaration) @constructor",
-                PreparedCSharpQuery::Destructor => "(destructor_declaration) @destructor",
-                PreparedCSharpQuery::Field => "(field_declaration) @field",
-                PreparedCSharpQuery::Attribute => "(attribute) @attribute",
-                PreparedCSharpQuery::Identifier => "(identifier) @identifier",
-            },
-        )
-        .expect("Prepared queries to be valid")
+                ]
+
+                (attribute_argument
+                    [
+                        (verbatim_string_literal) @string
+                        (assignment_expression right: (verbatim_string_literal) @string)
+                    ]
+                    (#offset! @string 0 2 0 -1)
+                )
+                "
+            }
+            Self::Identifier => "(identifier) @identifier",
+        }
+    }
+}
+
+impl From<PreparedCSharpQuery> for TSQuery {
+    fn from(value: PreparedCSharpQuery) -> Self {
+        Self::new(&CSharp::lang(), value.as_ref()).expect("Prepared queries to be valid")
     }
 }
 
@@ -108,6 +144,12 @@ impl FromStr for CustomCSharpQuery {
     }
 }
 
+impl AsRef<str> for CustomCSharpQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
 impl From<CustomCSharpQuery> for TSQuery {
     fn from(value: CustomCSharpQuery) -> Self {
         Self::new(&CSharp::lang(), &value.0)
@@ -125,7 +167,11 @@ impl LanguageScoper for CSharp {
     }
 
     fn neg_query(&self) -> Option<&TSQuery> {
-        self.negative_query.as_ref()
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
     }
 }
 
@@ -133,4 +179,8 @@ impl Find for CSharp {
     fn extensions(&self) -> &'static [&'static str] {
         &["cs"]
     }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["cs", "csharp"]
+    }
 }
@@ -0,0 +1,122 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use tree_sitter::QueryError;
+
+use super::{tree_sitter_css, CodeQuery, Language, LanguageScoper, TSLanguage, TSQuery};
+use crate::find::Find;
+
+/// The CSS language.
+///
+/// SCSS files are parsed as CSS as well, as there is no dedicated grammar. Nested
+/// rules and `//` comments are understood. SCSS-only syntax like `$variables`,
+/// `@mixin` and `@include` is not: it ends up in error nodes or generic at-rules, and
+/// prepared queries may not match inside of it.
+pub type Css = Language<CssQuery>;
+/// A query for CSS.
+pub type CssQuery = CodeQuery<CustomCssQuery, PreparedCssQuery>;
+
+/// Prepared tree-sitter queries for CSS.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreparedCssQuery {
+    /// Comments (block and, as in SCSS, line).
+    Comments,
+    /// Selectors of rule sets, like `.a > b:hover, #c`.
+    Selectors,
+    /// Property names of declarations, incl. custom properties (`--foo`).
+    PropertyNames,
+    /// Values of declarations, each separately (excl. `!important`).
+    ///
+    /// Function calls like `var(--foo)` or `rgb(0, 0, 0)` are a single value.
+    PropertyValues,
+}
+
+impl AsRef<str> for PreparedCssQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "[(comment) (js_comment)] @comment",
+            Self::Selectors => "(rule_set (selectors) @selectors)",
+            Self::PropertyNames => "(declaration (property_name) @name)",
+            Self::PropertyValues => {
+                r"
+                (declaration
+                    [
+                        (plain_value)
+                        (color_value)
+                        (integer_value)
+                        (float_value)
+                        (string_value)
+                        (grid_value)
+                        (binary_expression)
+                        (parenthesized_value)
+                        (call_expression)
+                    ] @value
+                )
+                "
+            }
+        }
+    }
+}
+
+impl From<PreparedCssQuery> for TSQuery {
+    fn from(value: PreparedCssQuery) -> Self {
+        Self::new(&Css::lang(), value.as_ref()).expect("Prepared queries to be valid")
+    }
+}
+
+/// A custom tree-sitter query for CSS.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomCssQuery(String);
+
+impl FromStr for CustomCssQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TSQuery::new(&Css::lang(), s) {
+            Ok(_) => Ok(Self(s.to_string())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsRef<str> for CustomCssQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CustomCssQuery> for TSQuery {
+    fn from(value: CustomCssQuery) -> Self {
+        Self::new(&Css::lang(), &value.0)
+            .expect("Valid query, as object cannot be constructed otherwise")
+    }
+}
+
+impl LanguageScoper for Css {
+    fn lang() -> TSLanguage {
+        tree_sitter_css::language()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        &self.positive_query
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Find for Css {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["css", "scss"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["css", "scss"]
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use tree_sitter::QueryError;
+
+use super::{tree_sitter_dockerfile, CodeQuery, Language, LanguageScoper, TSLanguage, TSQuery};
+use crate::find::Find;
+
+/// The Dockerfile language.
+pub type Dockerfile = Language<DockerfileQuery>;
+/// A query for Dockerfile.
+pub type DockerfileQuery = CodeQuery<CustomDockerfileQuery, PreparedDockerfileQuery>;
+
+/// Prepared tree-sitter queries for Dockerfile.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreparedDockerfileQuery {
+    /// Comments.
+    Comments,
+    /// Instructions, like `RUN make` (in their entirety, incl. continuation lines and
+    /// heredocs).
+    Instructions,
+    /// Image references of `FROM` instructions, like `rust:1.80-slim` (incl. tag and
+    /// digest, excl. the `AS` stage name).
+    ImageRefs,
+    /// Names and values of `ENV` variables, each separately (values incl. quotes).
+    ///
+    /// Of the legacy, space-separated form (`ENV KEY value`), only single-word values
+    /// parse.
+    EnvVars,
+}
+
+impl AsRef<str> for PreparedDockerfileQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "(comment) @comment",
+            Self::Instructions => {
+                r"
+                (source_file
+                    [
+                        (from_instruction)
+                        (run_instruction)
+                        (cmd_instruction)
+                        (label_instruction)
+                        (expose_instruction)
+                        (env_instruction)
+                        (add_instruction)
+                        (copy_instruction)
+                        (entrypoint_instruction)
+                        (volume_instruction)
+                        (user_instruction)
+                        (workdir_instruction)
+                        (arg_instruction)
+                        (onbuild_instruction)
+                        (stopsignal_instruction)
+                        (healthcheck_instruction)
+                        (shell_instruction)
+                        (maintainer_instruction)
+                        (cross_build_instruction)
+                    ] @instruction
+                )
+                "
+            }
+            Self::ImageRefs => "(from_instruction (image_spec) @image)",
+            Self::EnvVars => {
+                r"
+                [
+                    (env_pair name: (_) @name)
+                    (env_pair value: (_) @value)
+                ]
+                "
+            }
+        }
+    }
+}
+
+impl From<PreparedDockerfileQuery> for TSQuery {
+    fn from(value: PreparedDockerfileQuery) -> Self {
+        Self::new(&Dockerfile::lang(), value.as_ref()).expect("Prepared queries to be valid")
+    }
+}
+
+/// A custom tree-sitter query for Dockerfile.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomDockerfileQuery(String);
+
+impl FromStr for CustomDockerfileQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TSQuery::new(&Dockerfile::lang(), s) {
+            Ok(_) => Ok(Self(s.to_string())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsRef<str> for CustomDockerfileQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CustomDockerfileQuery> for TSQuery {
+    fn from(value: CustomDockerfileQuery) -> Self {
+        Self::new(&Dockerfile::lang(), &value.0)
+            .expect("Valid query, as object cannot be constructed otherwise")
+    }
+}
+
+impl LanguageScoper for Dockerfile {
+    fn lang() -> TSLanguage {
+        tree_sitter_dockerfile::language()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        &self.positive_query
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Find for Dockerfile {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["dockerfile", "Dockerfile"]
+    }
+
+    fn filenames(&self) -> &'static [&'static str] {
+        &["Dockerfile", "Containerfile"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["dockerfile"]
+    }
+}
@@ -25,12 +25,14 @@ pub enum PreparedGoQuery {
     /// Imports.
     Imports,
     /// Type definitions.
+    #[value(alias = "type-defs")]
     TypeDef,
     /// Type alias assignments.
     TypeAlias,
     /// `struct` type definitions.
     Struct,
     /// `interface` type definitions.
+    #[value(alias = "interfaces")]
     Interface,
     /// `const` specifications.
     Const,
@@ -58,68 +60,64 @@ pub enum PreparedGoQuery {
     Labeled,
     /// `goto` statements.
     Goto,
-    /// Struct tags.
+    /// Struct tags (raw and interpreted strings; tag literals only).
     StructTags,
 }
 
-impl From<PreparedGoQuery> for TSQuery {
-    fn from(value: PreparedGoQuery) -> Self {
-        Self::new(
-            &Go::lang(),
-            match value {
-                PreparedGoQuery::Comments => "(comment) @comment",
-                PreparedGoQuery::Strings => {
-                    formatcp!(
-                        r"
-                        [
-                            (raw_string_literal)
-                            (interpreted_string_literal)
-                            (import_spec (interpreted_string_literal)) @{0}
-                            (field_declaration tag: (raw_string_literal)) @{0}
-                        ]
-                        @string",
-                        IGNORE
-                    )
-                }
-                PreparedGoQuery::Imports => {
-                    r"(import_spec path: (interpreted_string_literal) @path)"
-                }
-                PreparedGoQuery::TypeDef => r"(type_declaration) @type_decl",
-                PreparedGoQuery::TypeAlias => r"(type_alias) @type_alias",
-                PreparedGoQuery::Struct => {
-                    r"(type_declaration (type_spec type: (struct_type))) @struct"
-                }
-                PreparedGoQuery::Interface => {
-                    r"(type_declaration (type_spec type: (interface_type))) @interface"
-                }
-                PreparedGoQuery::Const => "(const_spec) @const",
-                PreparedGoQuery::Var => "(var_spec) @var",
-                PreparedGoQuery::Func => {
+impl AsRef<str> for PreparedGoQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "(comment) @comment",
+            Self::Strings => {
+                formatcp!(
                     r"
                     [
-                        (method_declaration)
-                        (function_declaration)
-                        (func_literal)
-                    ] @func"
-                }
-                PreparedGoQuery::Method => "(method_declaration) @method",
-                PreparedGoQuery::FreeFunc => "(function_declaration) @free_func",
-                PreparedGoQuery::InitFunc => {
-                    r#"(function_declaration
-                        name: (identifier) @id (#eq? @id "init")
-                    ) @init_func"#
-                }
-                PreparedGoQuery::TypeParams => "(type_parameter_declaration) @type_params",
-                PreparedGoQuery::Defer => "(defer_statement) @defer",
-                PreparedGoQuery::Select => "(select_statement) @select",
-                PreparedGoQuery::Go => "(go_statement) @go",
-                PreparedGoQuery::Switch => "(expression_switch_statement) @switch",
-                PreparedGoQuery::Labeled => "(labeled_statement) @labeled",
-                PreparedGoQuery::Goto => "(goto_statement) @goto",
-                PreparedGoQuery::StructTags => "(field_declaration tag: (raw_string_literal) @tag)",
-            },
-        )
-        .expect("Prepared queries to be valid")
+                        (raw_string_literal)
+                        (interpreted_string_literal)
+                        (import_spec (interpreted_string_literal)) @{0}
+                        (field_declaration tag: (_)) @{0}
+                    ]
+                    @string",
+                    IGNORE
+                )
+            }
+            Self::Imports => r"(import_spec path: (interpreted_string_literal) @path)",
+            Self::TypeDef => r"(type_declaration) @type_decl",
+            Self::TypeAlias => r"(type_alias) @type_alias",
+            Self::Struct => r"(type_declaration (type_spec type: (struct_type))) @struct",
+            Self::Interface => r"(type_declaration (type_spec type: (interface_type))) @interface",
+            Self::Const => "(const_spec) @const",
+            Self::Var => "(var_spec) @var",
+            Self::Func => {
+                r"
+                [
+                    (method_declaration)
+                    (function_declaration)
+                    (func_literal)
+                ] @func"
+            }
+            Self::Method => "(method_declaration) @method",
+            Self::FreeFunc => "(function_declaration) @free_func",
+            Self::InitFunc => {
+                r#"(function_declaration
+                    name: (identifier) @id (#eq? @id "init")
+                ) @init_func"#
+            }
+            Self::TypeParams => "(type_parameter_declaration) @type_params",
+            Self::Defer => "(defer_statement) @defer",
+            Self::Select => "(select_statement) @select",
+            Self::Go => "(go_statement) @go",
+            Self::Switch => "(expression_switch_statement) @switch",
+            Self::Labeled => "(labeled_statement) @labeled",
+            Self::Goto => "(goto_statement) @goto",
+            Self::StructTags => "(field_declaration tag: (_) @tag)",
+        }
+    }
+}
+
+impl From<PreparedGoQuery> for TSQuery {
+    fn from(value: PreparedGoQuery) -> Self {
+        Self::new(&Go::lang(), value.as_ref()).expect("Prepared queries to be valid")
     }
 }
 
@@ -138,6 +136,12 @@ impl FromStr for CustomGoQuery {
     }
 }
 
+impl AsRef<str> for CustomGoQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
 impl From<CustomGoQuery> for TSQuery {
     fn from(value: CustomGoQuery) -> Self {
         Self::new(&Go::lang(), &value.0)
@@ -155,7 +159,11 @@ impl LanguageScoper for Go {
     }
 
     fn neg_query(&self) -> Option<&TSQuery> {
-        self.negative_query.as_ref()
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
     }
 }
 
@@ -164,6 +172,10 @@ impl Find for Go {
         &["go"]
     }
 
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["go"]
+    }
+
     fn is_path_invalid(&self, path: &Path) -> bool {
         for component in path.components() {
             if let Component::Normal(item) = component {
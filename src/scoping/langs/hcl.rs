@@ -51,6 +51,15 @@ pub enum PreparedHclQuery {
     ///
     /// In `data "a" "b"`, only "a" is matched.
     DataSources,
+    /// Labels of blocks, without quotes.
+    ///
+    /// In `resource "a" "b"`, both "a" and "b" are matched. Useful for renaming
+    /// across all block types at once, such as a provider prefix of resource and data
+    /// source types.
+    BlockLabels,
+    /// Attributes (`key = value`, in their entirety), including those in nested
+    /// blocks.
+    Attributes,
     /// Comments.
     Comments,
     /// Literal strings.
@@ -59,260 +68,272 @@ pub enum PreparedHclQuery {
     Strings,
 }
 
-impl From<PreparedHclQuery> for TSQuery {
+impl AsRef<str> for PreparedHclQuery {
     #[allow(clippy::too_many_lines)] // No good way to avoid
-    fn from(value: PreparedHclQuery) -> Self {
-        Self::new(
-            &Hcl::lang(),
-            // Seems to not play nice with the macro. Put up here, else interpolation is
-            // affected.
-            #[allow(clippy::needless_raw_string_hashes)]
-            match value {
-                PreparedHclQuery::Variable => {
-                    r#"
-                        (block
-                            (identifier) @name
-                            (#eq? @name "variable")
-                        ) @block
-                    "#
-                }
-                PreparedHclQuery::Resource => {
-                    r#"
-                        (block
-                            (identifier) @name
-                            (#eq? @name "resource")
-                        ) @block
-                    "#
-                }
-                PreparedHclQuery::Data => {
-                    r#"
-                        (block
-                            (identifier) @name
-                            (#eq? @name "data")
-                        ) @block
-                    "#
-                }
-                PreparedHclQuery::Output => {
-                    r#"
-                        (block
-                            (identifier) @name
-                            (#eq? @name "output")
-                        ) @block
-                    "#
-                }
-                PreparedHclQuery::Provider => {
-                    r#"
-                        (block
-                            (identifier) @name
-                            (#eq? @name "provider")
-                        ) @block
-                    "#
-                }
-                PreparedHclQuery::Terraform => {
+    fn as_ref(&self) -> &str {
+        // Seems to not play nice with the macro. Put up here, else interpolation is
+        // affected.
+        #[allow(clippy::needless_raw_string_hashes)]
+        match self {
+            Self::Variable => {
+                r#"
+                    (block
+                        (identifier) @name
+                        (#eq? @name "variable")
+                    ) @block
+                "#
+            }
+            Self::Resource => {
+                r#"
+                    (block
+                        (identifier) @name
+                        (#eq? @name "resource")
+                    ) @block
+                "#
+            }
+            Self::Data => {
+                r#"
+                    (block
+                        (identifier) @name
+                        (#eq? @name "data")
+                    ) @block
+                "#
+            }
+            Self::Output => {
+                r#"
+                    (block
+                        (identifier) @name
+                        (#eq? @name "output")
+                    ) @block
+                "#
+            }
+            Self::Provider => {
+                r#"
+                    (block
+                        (identifier) @name
+                        (#eq? @name "provider")
+                    ) @block
+                "#
+            }
+            Self::Terraform => {
+                r#"
+                    (block
+                        (identifier) @name
+                        (#eq? @name "terraform")
+                    ) @block
+                "#
+            }
+            Self::Locals => {
+                r#"
+                    (block
+                        (identifier) @name
+                        (#eq? @name "locals")
+                    ) @block
+                "#
+            }
+            Self::Module => {
+                r#"
+                    (block
+                        (identifier) @name
+                        (#eq? @name "module")
+                    ) @block
+                "#
+            }
+            Self::Variables => {
+                // Capturing nodes with names, such as `@id`, requires names to be
+                // unique across the *entire* query, else things break. Hence, us
+                // `@a.b` syntax (which seems undocumented).
+                formatcp!(
                     r#"
-                        (block
-                            (identifier) @name
-                            (#eq? @name "terraform")
-                        ) @block
-                    "#
-                }
-                PreparedHclQuery::Locals => {
-                    r#"
-                        (block
-                            (identifier) @name
-                            (#eq? @name "locals")
-                        ) @block
-                    "#
-                }
-                PreparedHclQuery::Module => {
-                    r#"
-                        (block
-                            (identifier) @name
-                            (#eq? @name "module")
-                        ) @block
-                    "#
-                }
-                PreparedHclQuery::Variables => {
-                    // Capturing nodes with names, such as `@id`, requires names to be
-                    // unique across the *entire* query, else things break. Hence, us
-                    // `@a.b` syntax (which seems undocumented).
-                    formatcp!(
-                        r#"
-                            [
-                                (block
-                                    (identifier) @{0}.declaration
-                                    (string_lit (template_literal) @name.declaration)
-                                    (#match? @{0}.declaration "variable")
+                        [
+                            (block
+                                (identifier) @{0}.declaration
+                                (string_lit (template_literal) @name.declaration)
+                                (#match? @{0}.declaration "variable")
+                            )
+                            (
+                                (variable_expr
+                                    (identifier) @{0}.usage
+                                    (#match? @{0}.usage "var")
                                 )
-                                (
-                                    (variable_expr
-                                        (identifier) @{0}.usage
-                                        (#match? @{0}.usage "var")
-                                    )
-                                    .
-                                    (get_attr
-                                        (identifier) @name.usage
+                                .
+                                (get_attr
+                                    (identifier) @name.usage
+                                )
+                            )
+                        ]
+                    "#,
+                    IGNORE
+                )
+            }
+            Self::ResourceNames => {
+                // Capturing nodes with names, such as `@id`, requires names to be
+                // unique across the *entire* query, else things break. Hence, us
+                // `@a.b` syntax (which seems undocumented).
+                formatcp!(
+                    r#"
+                        [
+                            (block
+                                (identifier) @{0}.declaration
+                                (string_lit)
+                                (string_lit (template_literal) @name.declaration)
+                                (#match? @{0}.declaration "resource")
+                            )
+                            (
+                                (variable_expr
+                                    (identifier) @{0}.usage
+                                    (#not-any-of? @{0}.usage
+                                        "var"
+                                        "data"
+                                        "count"
+                                        "module"
+                                        "local"
                                     )
                                 )
-                            ]
-                        "#,
-                        IGNORE
-                    )
-                }
-                PreparedHclQuery::ResourceNames => {
-                    // Capturing nodes with names, such as `@id`, requires names to be
-                    // unique across the *entire* query, else things break. Hence, us
-                    // `@a.b` syntax (which seems undocumented).
-                    formatcp!(
-                        r#"
-                            [
-                                (block
-                                    (identifier) @{0}.declaration
-                                    (string_lit)
-                                    (string_lit (template_literal) @name.declaration)
-                                    (#match? @{0}.declaration "resource")
+                                .
+                                (get_attr
+                                    (identifier) @name.usage
                                 )
-                                (
-                                    (variable_expr
-                                        (identifier) @{0}.usage
-                                        (#not-any-of? @{0}.usage
-                                            "var"
-                                            "data"
-                                            "count"
-                                            "module"
-                                            "local"
-                                        )
-                                    )
+                            )
+                        ]
+                    "#,
+                    IGNORE
+                )
+            }
+            Self::ResourceTypes => {
+                // Capturing nodes with names, such as `@id`, requires names to be
+                // unique across the *entire* query, else things break. Hence, us
+                // `@a.b` syntax (which seems undocumented).
+                formatcp!(
+                    r#"
+                        [
+                            (block
+                                (identifier) @{0}.declaration
+                                (string_lit (template_literal) @name.type)
+                                (string_lit)
+                                (#match? @{0}.declaration "resource")
+                            )
+                            (
+                                (variable_expr
                                     .
-                                    (get_attr
-                                        (identifier) @name.usage
+                                    (identifier) @name.usage
+                                    (#not-any-of? @name.usage
+                                        "var"
+                                        "data"
+                                        "count"
+                                        "module"
+                                        "local"
                                     )
                                 )
-                            ]
-                        "#,
-                        IGNORE
-                    )
-                }
-                PreparedHclQuery::ResourceTypes => {
-                    // Capturing nodes with names, such as `@id`, requires names to be
-                    // unique across the *entire* query, else things break. Hence, us
-                    // `@a.b` syntax (which seems undocumented).
-                    formatcp!(
-                        r#"
-                            [
-                                (block
-                                    (identifier) @{0}.declaration
-                                    (string_lit (template_literal) @name.type)
-                                    (string_lit)
-                                    (#match? @{0}.declaration "resource")
+                                .
+                                (get_attr
+                                    (identifier)
                                 )
-                                (
-                                    (variable_expr
-                                        .
-                                        (identifier) @name.usage
-                                        (#not-any-of? @name.usage
-                                            "var"
-                                            "data"
-                                            "count"
-                                            "module"
-                                            "local"
-                                        )
-                                    )
-                                    .
-                                    (get_attr
-                                        (identifier)
-                                    )
+                            )
+                        ]
+                    "#,
+                    IGNORE
+                )
+            }
+            Self::DataNames => {
+                // Capturing nodes with names, such as `@id`, requires names to be
+                // unique across the *entire* query, else things break. Hence, us
+                // `@a.b` syntax (which seems undocumented).
+                formatcp!(
+                    r#"
+                        [
+                            (block
+                                (identifier) @{0}.declaration
+                                (string_lit)
+                                (string_lit (template_literal) @name.declaration)
+                                (#match? @{0}.declaration "data")
+                            )
+                            (
+                                (variable_expr
+                                    (identifier) @{0}.usage
+                                    (#match? @{0}.usage "data")
                                 )
-                            ]
-                        "#,
-                        IGNORE
-                    )
-                }
-                PreparedHclQuery::DataNames => {
-                    // Capturing nodes with names, such as `@id`, requires names to be
-                    // unique across the *entire* query, else things break. Hence, us
-                    // `@a.b` syntax (which seems undocumented).
-                    formatcp!(
-                        r#"
-                            [
-                                (block
-                                    (identifier) @{0}.declaration
-                                    (string_lit)
-                                    (string_lit (template_literal) @name.declaration)
-                                    (#match? @{0}.declaration "data")
+                                .
+                                (get_attr
+                                    (identifier)
                                 )
-                                (
-                                    (variable_expr
-                                        (identifier) @{0}.usage
-                                        (#match? @{0}.usage "data")
-                                    )
-                                    .
-                                    (get_attr
-                                        (identifier)
-                                    )
-                                    .
-                                    (get_attr
-                                        (identifier) @name.usage
-                                    )
+                                .
+                                (get_attr
+                                    (identifier) @name.usage
                                 )
-                            ]
-                        "#,
-                        IGNORE
-                    )
-                }
-                PreparedHclQuery::DataSources => {
-                    // Capturing nodes with names, such as `@id`, requires names to be
-                    // unique across the *entire* query, else things break. Hence, us
-                    // `@a.b` syntax (which seems undocumented).
-                    formatcp!(
-                        r#"
-                            [
-                                (block
-                                    (identifier) @{0}.declaration
-                                    (string_lit (template_literal) @name.provider)
-                                    (string_lit)
-                                    (#match? @{0}.declaration "data")
+                            )
+                        ]
+                    "#,
+                    IGNORE
+                )
+            }
+            Self::DataSources => {
+                // Capturing nodes with names, such as `@id`, requires names to be
+                // unique across the *entire* query, else things break. Hence, us
+                // `@a.b` syntax (which seems undocumented).
+                formatcp!(
+                    r#"
+                        [
+                            (block
+                                (identifier) @{0}.declaration
+                                (string_lit (template_literal) @name.provider)
+                                (string_lit)
+                                (#match? @{0}.declaration "data")
+                            )
+                            (
+                                (variable_expr
+                                    (identifier) @{0}.usage
+                                    (#match? @{0}.usage "data")
                                 )
-                                (
-                                    (variable_expr
-                                        (identifier) @{0}.usage
-                                        (#match? @{0}.usage "data")
-                                    )
-                                    .
-                                    (get_attr
-                                        (identifier) @name.provider
-                                    )
-                                    .
-                                    (get_attr
-                                        (identifier)
-                                    )
+                                .
+                                (get_attr
+                                    (identifier) @name.provider
+                                )
+                                .
+                                (get_attr
+                                    (identifier)
                                 )
-                            ]
-                        "#,
-                        IGNORE
-                    )
-                }
-                PreparedHclQuery::Comments => "(comment) @comment",
-                PreparedHclQuery::Strings => {
-                    r"
-                    [
-                        (literal_value
-                            (string_lit
-                                (template_literal) @string.literal
                             )
+                        ]
+                    "#,
+                    IGNORE
+                )
+            }
+            Self::BlockLabels => {
+                r"
+                    (block
+                        (string_lit
+                            (template_literal) @label
                         )
-                        (quoted_template
-                            (template_literal) @string.template_literal
-                        )
-                        (heredoc_template
-                            (template_literal) @string.heredoc_literal
+                    )
+                "
+            }
+            Self::Attributes => "(attribute) @attribute",
+            Self::Comments => "(comment) @comment",
+            Self::Strings => {
+                r"
+                [
+                    (literal_value
+                        (string_lit
+                            (template_literal) @string.literal
                         )
-                    ]
-                    "
-                }
-            },
-        )
-        .expect("Prepared queries to be valid")
+                    )
+                    (quoted_template
+                        (template_literal) @string.template_literal
+                    )
+                    (heredoc_template
+                        (template_literal) @string.heredoc_literal
+                    )
+                ]
+                "
+            }
+        }
+    }
+}
+
+impl From<PreparedHclQuery> for TSQuery {
+    fn from(value: PreparedHclQuery) -> Self {
+        Self::new(&Hcl::lang(), value.as_ref()).expect("Prepared queries to be valid")
     }
 }
 
@@ -331,6 +352,12 @@ impl FromStr for CustomHclQuery {
     }
 }
 
+impl AsRef<str> for CustomHclQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
 impl From<CustomHclQuery> for TSQuery {
     fn from(value: CustomHclQuery) -> Self {
         Self::new(&Hcl::lang(), &value.0)
@@ -348,7 +375,11 @@ impl LanguageScoper for Hcl {
     }
 
     fn neg_query(&self) -> Option<&TSQuery> {
-        self.negative_query.as_ref()
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
     }
 }
 
@@ -356,4 +387,8 @@ impl Find for Hcl {
     fn extensions(&self) -> &'static [&'static str] {
         &["hcl", "tf"]
     }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["hcl", "terraform"]
+    }
 }
@@ -0,0 +1,103 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use tree_sitter::QueryError;
+
+use super::{tree_sitter_html, CodeQuery, Language, LanguageScoper, TSLanguage, TSQuery};
+use crate::find::Find;
+
+/// The HTML language.
+pub type Html = Language<HtmlQuery>;
+/// A query for HTML.
+pub type HtmlQuery = CodeQuery<CustomHtmlQuery, PreparedHtmlQuery>;
+
+/// Prepared tree-sitter queries for HTML.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreparedHtmlQuery {
+    /// Comments.
+    Comments,
+    /// Text content, as rendered to readers: not tags, attributes or comments.
+    ///
+    /// Character references (`&amp;`) are left out, splitting text around them. The
+    /// contents of `<script>` and `<style>` elements are included, as whole, without
+    /// being parsed any further.
+    Text,
+    /// Attribute values (excl. quotes).
+    Attributes,
+    /// Tag names, of start, end and self-closing tags.
+    Tags,
+}
+
+impl AsRef<str> for PreparedHtmlQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "(comment) @comment",
+            Self::Text => "[(text) (raw_text)] @text",
+            Self::Attributes => "(attribute_value) @value",
+            Self::Tags => "(tag_name) @tag",
+        }
+    }
+}
+
+impl From<PreparedHtmlQuery> for TSQuery {
+    fn from(value: PreparedHtmlQuery) -> Self {
+        Self::new(&Html::lang(), value.as_ref()).expect("Prepared queries to be valid")
+    }
+}
+
+/// A custom tree-sitter query for HTML.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomHtmlQuery(String);
+
+impl FromStr for CustomHtmlQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TSQuery::new(&Html::lang(), s) {
+            Ok(_) => Ok(Self(s.to_string())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsRef<str> for CustomHtmlQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CustomHtmlQuery> for TSQuery {
+    fn from(value: CustomHtmlQuery) -> Self {
+        Self::new(&Html::lang(), &value.0)
+            .expect("Valid query, as object cannot be constructed otherwise")
+    }
+}
+
+impl LanguageScoper for Html {
+    fn lang() -> TSLanguage {
+        tree_sitter_html::language()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        &self.positive_query
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Find for Html {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["html", "htm"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["html"]
+    }
+}
@@ -0,0 +1,247 @@
+use std::ops::Range;
+
+use clap::ValueEnum;
+use log::trace;
+
+use crate::find::Find;
+use crate::ranges::Ranges;
+use crate::scoping::scope::RangesWithContext;
+use crate::scoping::Scoper;
+
+/// Items of INI-style configuration files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum IniItem {
+    /// Section headers like `[section]` (incl. the brackets).
+    Sections,
+    /// Keys of `key = value` and `key: value` pairs (excl. surrounding whitespace).
+    Keys,
+    /// Values of `key = value` and `key: value` pairs (excl. surrounding whitespace,
+    /// quotes of quoted values and trailing comments).
+    Values,
+    /// Comments, starting with `;` or `#`, on their own lines or trailing a value.
+    Comments,
+}
+
+/// The INI configuration file format.
+///
+/// There is no formal specification and no tree-sitter grammar of INI, so it is
+/// scoped line by line, as most parsers do. Lines are either blank, comments
+/// (starting with `;` or `#`), section headers (`[section]`), or key-value pairs
+/// separated by the first `=` or `:`. Trailing comments are recognized after
+/// whitespace, or after the closing quote of quoted values. Lines without separator
+/// are taken to be keys without value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ini {
+    /// The item to scope.
+    pub item: IniItem,
+}
+
+impl Ini {
+    /// Creates a new instance scoping the given `item`.
+    #[must_use]
+    pub const fn new(item: IniItem) -> Self {
+        Self { item }
+    }
+}
+
+impl Scoper for Ini {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        let mut ranges = Vec::new();
+        let mut start = 0;
+
+        for line in input.split_inclusive('\n') {
+            let content = line
+                .strip_suffix('\n')
+                .map_or(line, |l| l.strip_suffix('\r').unwrap_or(l));
+
+            let parsed = parse_line(content);
+            let range = match (self.item, parsed) {
+                (IniItem::Sections, Line::Section(range))
+                | (IniItem::Keys, Line::Pair { key: range, .. })
+                | (
+                    IniItem::Values,
+                    Line::Pair {
+                        value: Some(range), ..
+                    },
+                )
+                | (
+                    IniItem::Comments,
+                    Line::Comment(range)
+                    | Line::Pair {
+                        comment: Some(range),
+                        ..
+                    },
+                ) => Some(range),
+                _ => None,
+            };
+
+            ranges.extend(range.map(|r| start + r.start..start + r.end));
+            start += line.len();
+        }
+
+        let ranges: Ranges<usize> = ranges.into_iter().filter(|r| !r.is_empty()).collect();
+        trace!("Ranges in scope for {:?}: {:?}", self, ranges);
+
+        ranges.into()
+    }
+}
+
+impl Find for Ini {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ini", "cfg"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["dosini", "ini"]
+    }
+}
+
+/// A single, classified line of INI, with ranges relative to the line.
+#[derive(Debug, PartialEq, Eq)]
+enum Line {
+    Blank,
+    Comment(Range<usize>),
+    Section(Range<usize>),
+    Pair {
+        key: Range<usize>,
+        value: Option<Range<usize>>,
+        comment: Option<Range<usize>>,
+    },
+}
+
+const COMMENT_CHARS: [char; 2] = [';', '#'];
+
+fn parse_line(line: &str) -> Line {
+    let indent = line.len() - line.trim_start().len();
+    let end = line.trim_end().len();
+
+    if indent == line.len() {
+        return Line::Blank;
+    }
+
+    let trimmed = &line[indent..end];
+    if trimmed.starts_with(COMMENT_CHARS) {
+        return Line::Comment(indent..end);
+    }
+
+    if trimmed.starts_with('[') {
+        if let Some(close) = trimmed.find(']') {
+            return Line::Section(indent..indent + close + ']'.len_utf8());
+        }
+    }
+
+    let Some(sep) = trimmed.find(['=', ':']).map(|i| indent + i) else {
+        return Line::Pair {
+            key: indent..end,
+            value: None,
+            comment: None,
+        };
+    };
+
+    let key = indent..indent + line[indent..sep].trim_end().len();
+    let value_start = sep + 1 + (line[sep + 1..].len() - line[sep + 1..].trim_start().len());
+    let (value, comment) = split_value(line, value_start..end);
+
+    Line::Pair {
+        key,
+        value,
+        comment,
+    }
+}
+
+/// Splits the `value` part of `line` into the value itself and any trailing comment.
+fn split_value(line: &str, value: Range<usize>) -> (Option<Range<usize>>, Option<Range<usize>>) {
+    let s = &line[value.clone()];
+
+    let quote = s.chars().next().filter(|c| ['"', '\''].contains(c));
+    if let Some(quote) = quote {
+        if let Some(close) = s[1..].find(quote).map(|i| i + 1) {
+            let rest = &s[close + 1..];
+            let comment = rest
+                .find(COMMENT_CHARS)
+                .map(|i| value.start + close + 1 + i..value.end);
+
+            return (Some(value.start + 1..value.start + close), comment);
+        }
+    }
+
+    // Trailing comments need preceding whitespace, else `a=b#c` is a value.
+    let comment_start = s
+        .char_indices()
+        .zip(s.chars().skip(1))
+        .find(|((_, c), next)| c.is_whitespace() && COMMENT_CHARS.contains(next))
+        .map(|((i, _), _)| i);
+
+    match comment_start {
+        Some(i) => {
+            let comment = value.start + s[i..].find(COMMENT_CHARS).map_or(i, |j| i + j);
+            let v = value.start..value.start + s[..i].trim_end().len();
+
+            (Some(v), Some(comment..value.end))
+        }
+        None => (Some(value), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    const INPUT: &str = r#"; Global settings
+# Also a comment
+name = demo
+
+[database]
+host=localhost
+port: 5432 ; default port
+password = "hunter 2"  # quoted
+  user = 'admin'
+path = /var/lib#notacomment
+
+[empty values]
+flag
+key =
+"#;
+
+    fn scoped(item: IniItem, input: &str) -> Vec<&str> {
+        Ini::new(item)
+            .scope_raw(input)
+            .into_iter()
+            .map(|(range, _)| &input[range])
+            .collect()
+    }
+
+    #[rstest]
+    #[case::sections(IniItem::Sections, vec!["[database]", "[empty values]"])]
+    #[case::keys(
+        IniItem::Keys,
+        vec!["name", "host", "port", "password", "user", "path", "flag", "key"]
+    )]
+    #[case::values(
+        IniItem::Values,
+        vec!["demo", "localhost", "5432", "hunter 2", "admin", "/var/lib#notacomment"]
+    )]
+    #[case::comments(
+        IniItem::Comments,
+        vec!["; Global settings", "# Also a comment", "; default port", "# quoted"]
+    )]
+    fn test_ini(#[case] item: IniItem, #[case] expected: Vec<&str>) {
+        assert_eq!(scoped(item, INPUT), expected);
+    }
+
+    #[rstest]
+    #[case::crlf("[s]\r\na = b\r\n", IniItem::Values, vec!["b"])]
+    #[case::commented_out_pair(";a = b\n", IniItem::Keys, vec![])]
+    #[case::commented_out_section("# [s]\n", IniItem::Sections, vec![])]
+    #[case::separator_in_value("url = http://x\n", IniItem::Values, vec!["http://x"])]
+    #[case::unclosed_quote("a = \"b ; c\n", IniItem::Values, vec!["\"b"])]
+    fn test_ini_edge_cases(
+        #[case] input: &str,
+        #[case] item: IniItem,
+        #[case] expected: Vec<&str>,
+    ) {
+        assert_eq!(scoped(item, input), expected);
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use const_format::formatcp;
+use tree_sitter::QueryError;
+
+use super::{tree_sitter_java, CodeQuery, Language, LanguageScoper, TSLanguage, TSQuery};
+use crate::find::Find;
+use crate::scoping::langs::IGNORE;
+
+/// The Java language.
+pub type Java = Language<JavaQuery>;
+/// A query for Java.
+pub type JavaQuery = CodeQuery<CustomJavaQuery, PreparedJavaQuery>;
+
+/// Prepared tree-sitter queries for Java.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreparedJavaQuery {
+    /// Comments (line, block and Javadoc comments).
+    Comments,
+    /// Javadoc comments (`/** ... */`).
+    Javadoc,
+    /// Strings (regular and text blocks; incl. quotes, excl. template interpolation).
+    Strings,
+    /// Names in `import` declarations (incl. periods; excl. `import`/`static`/`.*`).
+    Imports,
+    /// Method definitions (in their entirety).
+    Methods,
+    /// Constructor definitions (in their entirety).
+    Constructors,
+    /// `class` definitions (in their entirety).
+    Classes,
+    /// `interface` definitions (in their entirety).
+    Interfaces,
+    /// `enum` definitions (in their entirety).
+    Enums,
+    /// Annotations like `@Override` (incl. arguments).
+    Annotations,
+}
+
+impl AsRef<str> for PreparedJavaQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "[(line_comment) (block_comment)] @comment",
+            Self::Javadoc => {
+                // `/**/` is an empty regular comment, not Javadoc.
+                r#"(
+                    (block_comment) @javadoc
+                    (#match? @javadoc "^/\\*\\*[^/]")
+                )"#
+            }
+            Self::Strings => {
+                formatcp!(
+                    r"
+                        [
+                            (string_literal (string_interpolation) @{0})
+                            (string_literal)
+                        ]
+                        @string
+                ",
+                    IGNORE
+                )
+            }
+            Self::Imports => r"(import_declaration [(identifier) (scoped_identifier)] @import)",
+            Self::Methods => "(method_declaration) @method",
+            Self::Constructors => "(constructor_declaration) @constructor",
+            Self::Classes => "(class_declaration) @class",
+            Self::Interfaces => "(interface_declaration) @interface",
+            Self::Enums => "(enum_declaration) @enum",
+            Self::Annotations => "[(annotation) (marker_annotation)] @annotation",
+        }
+    }
+}
+
+impl From<PreparedJavaQuery> for TSQuery {
+    fn from(value: PreparedJavaQuery) -> Self {
+        Self::new(&Java::lang(), value.as_ref()).expect("Prepared queries to be valid")
+    }
+}
+
+/// A custom tree-sitter query for Java.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomJavaQuery(String);
+
+impl FromStr for CustomJavaQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TSQuery::new(&Java::lang(), s) {
+            Ok(_) => Ok(Self(s.to_string())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsRef<str> for CustomJavaQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CustomJavaQuery> for TSQuery {
+    fn from(value: CustomJavaQuery) -> Self {
+        Self::new(&Java::lang(), &value.0)
+            .expect("Valid query, as object cannot be constructed otherwise")
+    }
+}
+
+impl LanguageScoper for Java {
+    fn lang() -> TSLanguage {
+        tree_sitter_java::language()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        &self.positive_query
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Find for Java {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["java"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["java"]
+    }
+}
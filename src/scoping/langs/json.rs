@@ -0,0 +1,110 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use tree_sitter::QueryError;
+
+use super::{tree_sitter_json, CodeQuery, Language, LanguageScoper, TSLanguage, TSQuery};
+use crate::find::Find;
+
+/// The JSON language.
+pub type Json = Language<JsonQuery>;
+/// A query for JSON.
+pub type JsonQuery = CodeQuery<CustomJsonQuery, PreparedJsonQuery>;
+
+/// Prepared tree-sitter queries for JSON.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreparedJsonQuery {
+    /// Object keys (incl. quotes).
+    Keys,
+    /// String values, i.e. strings which are not object keys (incl. quotes).
+    Strings,
+    /// Numbers.
+    Numbers,
+    /// Comments (line and block).
+    ///
+    /// Not part of the JSON specification, but commonly found in the wild (JSONC), so
+    /// the grammar supports them.
+    Comments,
+}
+
+impl AsRef<str> for PreparedJsonQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Keys => "(pair key: (string) @key)",
+            Self::Strings => {
+                r"
+                [
+                    (pair value: (string) @string)
+                    (array (string) @string)
+                    (document (string) @string)
+                ]
+                "
+            }
+            Self::Numbers => "(number) @number",
+            Self::Comments => "(comment) @comment",
+        }
+    }
+}
+
+impl From<PreparedJsonQuery> for TSQuery {
+    fn from(value: PreparedJsonQuery) -> Self {
+        Self::new(&Json::lang(), value.as_ref()).expect("Prepared queries to be valid")
+    }
+}
+
+/// A custom tree-sitter query for JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomJsonQuery(String);
+
+impl FromStr for CustomJsonQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TSQuery::new(&Json::lang(), s) {
+            Ok(_) => Ok(Self(s.to_string())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsRef<str> for CustomJsonQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CustomJsonQuery> for TSQuery {
+    fn from(value: CustomJsonQuery) -> Self {
+        Self::new(&Json::lang(), &value.0)
+            .expect("Valid query, as object cannot be constructed otherwise")
+    }
+}
+
+impl LanguageScoper for Json {
+    fn lang() -> TSLanguage {
+        tree_sitter_json::language()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        &self.positive_query
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Find for Json {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::str::FromStr;
+use std::sync::{LazyLock, Mutex};
+
+use clap::ValueEnum;
+use const_format::formatcp;
+use tree_sitter::QueryError;
+
+use super::{tree_sitter_markdown, CodeQuery, Language, LanguageScoper, TSLanguage, TSQuery};
+use crate::find::Find;
+use crate::scoping::langs::IGNORE;
+
+/// The Markdown language.
+///
+/// Markdown is parsed using two grammars: one for the *block* structure of documents
+/// (headings, code blocks, paragraphs, ...), and one for the *inline* structure of
+/// text within blocks (code spans, links, emphasis, ...). Each query is run against
+/// the tree of exactly one of them: queries valid for the block grammar run against
+/// the block tree. All others run against the inline tree, built by parsing the text
+/// of all paragraphs, headings and table cells at once.
+///
+/// As that text is parsed as one, inline constructs left unclosed at the end of a
+/// block (like a lone backtick) may continue into the next block, unlike in
+/// `CommonMark`.
+pub type Markdown = Language<MarkdownQuery>;
+/// A query for Markdown.
+pub type MarkdownQuery = CodeQuery<CustomMarkdownQuery, PreparedMarkdownQuery>;
+
+/// Prepared tree-sitter queries for Markdown.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreparedMarkdownQuery {
+    /// Contents of fenced code blocks (excl. fences and language info string).
+    CodeBlocks,
+    /// Contents of inline code spans (excl. backticks).
+    InlineCode,
+    /// Destinations (URLs) of inline links and images.
+    ///
+    /// Link reference definitions (`[label]: url`) are not included.
+    Links,
+    /// Text of headings (excl. `#` markers and underlines).
+    Headings,
+}
+
+impl PreparedMarkdownQuery {
+    /// The grammar this query is for.
+    fn lang(self) -> TSLanguage {
+        match self {
+            Self::CodeBlocks | Self::Headings => tree_sitter_markdown::language(),
+            Self::InlineCode | Self::Links => tree_sitter_markdown::inline_language(),
+        }
+    }
+}
+
+impl AsRef<str> for PreparedMarkdownQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::CodeBlocks => "(fenced_code_block (code_fence_content) @code)",
+            Self::InlineCode => {
+                formatcp!("(code_span (code_span_delimiter) @{0}) @code", IGNORE)
+            }
+            Self::Links => {
+                r"
+                [
+                    (inline_link (link_destination) @url)
+                    (image (link_destination) @url)
+                ]
+                "
+            }
+            Self::Headings => {
+                r"
+                [
+                    (atx_heading heading_content: (_) @heading)
+                    (setext_heading heading_content: (_) @heading)
+                ]
+                "
+            }
+        }
+    }
+}
+
+impl From<PreparedMarkdownQuery> for TSQuery {
+    fn from(value: PreparedMarkdownQuery) -> Self {
+        Self::new(&value.lang(), value.as_ref()).expect("Prepared queries to be valid")
+    }
+}
+
+/// A custom tree-sitter query for Markdown, for either its block or inline grammar.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomMarkdownQuery(String);
+
+impl FromStr for CustomMarkdownQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TSQuery::new(&Markdown::lang(), s) {
+            Ok(_) => Ok(Self(s.to_string())),
+            // Report errors for the block grammar, the more commonly targeted one.
+            Err(e) => match TSQuery::new(&tree_sitter_markdown::inline_language(), s) {
+                Ok(_) => Ok(Self(s.to_string())),
+                Err(_) => Err(e),
+            },
+        }
+    }
+}
+
+impl AsRef<str> for CustomMarkdownQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CustomMarkdownQuery> for TSQuery {
+    fn from(value: CustomMarkdownQuery) -> Self {
+        let lang = if is_inline(&value.0) {
+            tree_sitter_markdown::inline_language()
+        } else {
+            Markdown::lang()
+        };
+
+        Self::new(&lang, &value.0).expect("Valid query, as object cannot be constructed otherwise")
+    }
+}
+
+/// Whether `query` is for the inline grammar, as opposed to the block grammar.
+///
+/// Queries valid for both are taken to be for the block grammar. Cached, as compiling
+/// queries is expensive compared to running them on small inputs.
+fn is_inline(query: &str) -> bool {
+    static CACHE: LazyLock<Mutex<HashMap<String, bool>>> = LazyLock::new(Mutex::default);
+
+    *CACHE
+        .lock()
+        .expect("no other thread to have panicked holding the lock")
+        .entry(query.to_owned())
+        .or_insert_with(|| TSQuery::new(&Markdown::lang(), query).is_err())
+}
+
+impl LanguageScoper for Markdown {
+    /// The block grammar.
+    fn lang() -> TSLanguage {
+        tree_sitter_markdown::language()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        &self.positive_query
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.negative_query.as_deref()
+    }
+
+    /// The inline grammar, covering the text of blocks, for queries written against
+    /// it.
+    fn injection(&self) -> Option<(TSLanguage, &'static [&'static str])> {
+        is_inline(&self.source).then(|| {
+            (
+                tree_sitter_markdown::inline_language(),
+                &["inline", "pipe_table_cell"][..],
+            )
+        })
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Find for Markdown {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["md", "markdown"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["markdown"]
+    }
+}
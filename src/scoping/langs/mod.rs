@@ -1,12 +1,25 @@
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::error::Error;
 use std::marker::PhantomData;
+use std::num::NonZero;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::{fmt, io};
 
 use log::{debug, info, trace};
 pub use tree_sitter::{
     Language as TSLanguage, Parser as TSParser, Query as TSQuery, QueryCursor as TSQueryCursor,
+    QueryError as TSQueryError,
 };
+use tree_sitter::{Node, Point, QueryCapture, QueryPredicateArg, Range as TSRange, Tree};
 
-use super::scope::RangesWithContext;
+use super::regex::CaptureGroup;
+use super::scope::{RangesWithContext, ScopeContext};
 use super::Scoper;
 use crate::find::Find;
 use crate::ranges::Ranges;
@@ -16,29 +29,75 @@ use crate::scoping::{
     view::ScopedViewBuilder,
 };
 
+/// C.
+pub mod c;
+/// C++.
+pub mod cpp;
 /// C#.
 pub mod csharp;
+/// CSS.
+pub mod css;
+/// Dockerfile.
+pub mod dockerfile;
 /// Go.
 pub mod go;
 /// Hashicorp Configuration Language
 pub mod hcl;
+/// HTML.
+pub mod html;
+/// INI.
+pub mod ini;
+/// Java.
+pub mod java;
+/// JSON.
+pub mod json;
+/// Markdown.
+pub mod markdown;
+/// Perl.
+pub mod perl;
 /// Python.
 pub mod python;
+/// Ruby.
+pub mod ruby;
 /// Rust.
 pub mod rust;
+/// SQL.
+pub mod sql;
+/// TOML.
+pub mod toml;
+mod tree_sitter_c;
+mod tree_sitter_cpp;
+mod tree_sitter_css;
+mod tree_sitter_dockerfile;
 mod tree_sitter_hcl;
+mod tree_sitter_html;
+mod tree_sitter_java;
+mod tree_sitter_json;
+mod tree_sitter_markdown;
+mod tree_sitter_perl;
+mod tree_sitter_ruby;
+mod tree_sitter_sql;
+mod tree_sitter_toml;
 /// TypeScript.
 pub mod typescript;
+/// YAML.
+pub mod yaml;
 
 /// Represents a (programming) language.
 #[derive(Debug)]
 pub struct Language<Q> {
     /// The *positive* query: it will be run against input and its results used for
     /// scoping.
-    positive_query: TSQuery,
+    positive_query: Arc<TSQuery>,
     /// The *negative* query: if present (if [`IGNORE`] is present) will be run and
     /// *subtracted* from the positive query.
-    negative_query: Option<TSQuery>,
+    negative_query: Option<Arc<TSQuery>>,
+    /// The source text the queries were constructed from.
+    source: String,
+    /// The queries compiled for the language's dialect, if any (see
+    /// [`LanguageScoper::dialect`]). Compiled on first use, as most inputs won't need
+    /// them.
+    dialect_queries: OnceLock<Option<CompiledQueries>>,
     /// We are generic over this to allow languages to be their own type.
     ///
     /// We only store constructed [`TSQuery`]s as those are actually actionable and
@@ -49,46 +108,110 @@ pub struct Language<Q> {
     _marker: PhantomData<Q>,
 }
 
-impl<Q> Language<Q>
+/// Compiled queries, as `(positive, negative)`.
+type CompiledQueries = (Arc<TSQuery>, Option<Arc<TSQuery>>);
+
+/// Compiled prepared queries, keyed by their type and source.
+///
+/// Compiling queries is expensive compared to running them on small inputs, and
+/// prepared queries are fixed. So compile each only once per process, no matter how
+/// many times a scoper for it is created. Custom queries are not cached.
+static PREPARED_QUERIES: OnceLock<Mutex<HashMap<(TypeId, String), CompiledQueries>>> =
+    OnceLock::new();
+
+impl<C, P> Language<CodeQuery<C, P>>
 where
-    Q: Into<TSQuery> + Clone,
+    C: FromStr + Into<TSQuery> + Clone + AsRef<str>,
+    P: Into<TSQuery> + Clone + AsRef<str> + 'static,
 {
     /// Create a new language with the given associated query over it.
+    ///
+    /// Prepared queries are only compiled the first time they are used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another thread panicked while compiling a prepared query.
     #[allow(clippy::needless_pass_by_value)] // TODO: refactor later
-    pub fn new(query: Q) -> Self {
-        let positive_query = query.clone().into();
+    pub fn new(query: CodeQuery<C, P>) -> Self {
+        let source = query.as_ref().to_owned();
 
-        let is_ignored = |name: &str| name.starts_with(IGNORE);
-        let has_ignored_captures = positive_query
-            .capture_names()
-            .iter()
-            .any(|name| is_ignored(name));
-
-        let negative_query = has_ignored_captures.then(|| {
-            let mut query = query.clone().into();
-            let acknowledged_captures = query
-                .capture_names()
-                .iter()
-                .filter(|name| !is_ignored(name))
-                .map(|s| String::from(*s))
-                .collect::<Vec<_>>();
-
-            for name in acknowledged_captures {
-                trace!("Disabling capture for: {:?}", name);
-                query.disable_capture(&name);
-            }
-
-            query
-        });
+        let (positive_query, negative_query) = match query {
+            CodeQuery::Prepared(_) => PREPARED_QUERIES
+                .get_or_init(Mutex::default)
+                .lock()
+                .expect("no other thread to have panicked holding the lock")
+                .entry((TypeId::of::<P>(), source.clone()))
+                .or_insert_with(|| {
+                    debug!("Compiling prepared query (first use): {source}");
+                    compile(|| query.clone().into())
+                })
+                .clone(),
+            CodeQuery::Custom(_) => compile(|| query.clone().into()),
+        };
 
         Self {
             positive_query,
             negative_query,
+            source,
+            dialect_queries: OnceLock::new(),
             _marker: PhantomData,
         }
     }
 }
 
+impl<Q> Language<Q> {
+    /// The queries compiled for `dialect`, a grammar alternative to the language's
+    /// main one, as `(positive, negative)`.
+    ///
+    /// [`None`] if the query source is invalid for `dialect`.
+    fn dialect_queries(&self, dialect: &TSLanguage) -> Option<(&TSQuery, Option<&TSQuery>)> {
+        self.dialect_queries
+            .get_or_init(|| {
+                debug!("Compiling query for dialect (first use): {}", self.source);
+                TSQuery::new(dialect, &self.source).ok()?;
+
+                Some(compile(|| {
+                    TSQuery::new(dialect, &self.source).expect("query compiled before")
+                }))
+            })
+            .as_ref()
+            .map(|(positive, negative)| (positive.as_ref(), negative.as_deref()))
+    }
+}
+
+/// Compiles a query, as produced by `make`, into its positive and (if it contains
+/// [`IGNORE`]d captures) negative form.
+///
+/// [`TSQuery`]s cannot be cloned, hence the need to produce fresh ones.
+fn compile(make: impl Fn() -> TSQuery) -> CompiledQueries {
+    let positive_query = make();
+
+    let is_ignored = |name: &str| name.starts_with(IGNORE);
+    let has_ignored_captures = positive_query
+        .capture_names()
+        .iter()
+        .any(|name| is_ignored(name));
+
+    let negative_query = has_ignored_captures.then(|| {
+        let mut query = make();
+        let acknowledged_captures = query
+            .capture_names()
+            .iter()
+            .filter(|name| !is_ignored(name))
+            .map(|s| String::from(*s))
+            .collect::<Vec<_>>();
+
+        for name in acknowledged_captures {
+            trace!("Disabling capture for: {:?}", name);
+            query.disable_capture(&name);
+        }
+
+        Arc::new(query)
+    });
+
+    (Arc::new(positive_query), negative_query)
+}
+
 /// A query over a language, for scoping.
 ///
 /// Parts hit by the query are [`In`] scope, parts not hit are [`Out`] of scope.
@@ -107,6 +230,19 @@ where
     Prepared(P),
 }
 
+impl<C, P> AsRef<str> for CodeQuery<C, P>
+where
+    C: FromStr + Into<TSQuery> + AsRef<str>,
+    P: Into<TSQuery> + AsRef<str>,
+{
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Custom(query) => query.as_ref(),
+            Self::Prepared(query) => query.as_ref(),
+        }
+    }
+}
+
 impl<C, P> From<CodeQuery<C, P>> for TSQuery
 where
     C: FromStr + Into<Self>,
@@ -120,6 +256,68 @@ where
     }
 }
 
+/// Reads a custom query from a tree-sitter query file (conventionally `*.scm`) at
+/// `path`, validating it against the grammar of the query type `Q`.
+///
+/// Allows sharing and reusing queries too long to comfortably inline on the command
+/// line.
+///
+/// # Errors
+///
+/// If the file cannot be read, or doesn't contain a valid query. The latter reports
+/// the position of the offending part in the file.
+pub fn read_query_file<Q>(path: impl AsRef<Path>) -> Result<Q, QueryFileError>
+where
+    Q: FromStr<Err = TSQueryError>,
+{
+    let path = path.as_ref();
+
+    let contents = std::fs::read_to_string(path).map_err(|e| QueryFileError::Io {
+        path: path.to_owned(),
+        error: e,
+    })?;
+
+    Q::from_str(&contents).map_err(|e| QueryFileError::Query {
+        path: path.to_owned(),
+        error: e,
+    })
+}
+
+/// An error in reading a query from a file, see [`read_query_file`].
+#[derive(Debug)]
+pub enum QueryFileError {
+    /// The file could not be read.
+    Io {
+        /// The file in question.
+        path: PathBuf,
+        /// The underlying error.
+        error: io::Error,
+    },
+    /// The file does not contain a valid query.
+    Query {
+        /// The file in question.
+        path: PathBuf,
+        /// The underlying error, pointing to the position in the file.
+        error: TSQueryError,
+    },
+}
+
+impl fmt::Display for QueryFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => {
+                write!(f, "Cannot read query file '{}': {error}", path.display())
+            }
+            Self::Query { path, error } => {
+                // Tree-sitter's message already points to line and column.
+                write!(f, "Invalid query in '{}': {error}", path.display())
+            }
+        }
+    }
+}
+
+impl Error for QueryFileError {}
+
 /// In a query, use this name to mark a capture to be ignored.
 ///
 /// Useful for queries where tree-sitter doesn't natively support a fitting node type,
@@ -149,6 +347,79 @@ pub trait LanguageScoper: Scoper + Find + Send + Sync {
     where
         Self: Sized; // Exclude from trait object
 
+    /// An alternative grammar of the language, along with the queries compiled for it,
+    /// as `(grammar, positive, negative)`.
+    ///
+    /// Input from files with one of the [`LanguageScoper::dialect_extensions`] is
+    /// always parsed using the dialect (see [`in_file`]). So is any input if the query
+    /// is only valid for the dialect. Other input which doesn't parse cleanly using
+    /// [`LanguageScoper::lang`] is parsed again using the dialect, which is used
+    /// instead if it yields fewer errors. This allows for example TSX to be handled by
+    /// the TypeScript scoper.
+    fn dialect(&self) -> Option<(TSLanguage, &TSQuery, Option<&TSQuery>)>
+    where
+        Self: Sized, // Exclude from trait object
+    {
+        None
+    }
+
+    /// File extensions of files always parsed using the
+    /// [dialect](LanguageScoper::dialect), like `tsx` for TSX.
+    fn dialect_extensions(&self) -> &'static [&'static str]
+    where
+        Self: Sized, // Exclude from trait object
+    {
+        &[]
+    }
+
+    /// A grammar covering only parts of the input, along with the kinds of nodes (of
+    /// [`LanguageScoper::lang`]) whose contents it covers, as `(grammar, kinds)`.
+    ///
+    /// For languages split into several grammars, like the block and inline structure
+    /// of Markdown. If present, input is first parsed using [`LanguageScoper::lang`].
+    /// The contents of all nodes of the given kinds, minus their named children, are
+    /// then parsed using `grammar`, and queries run against that tree instead.
+    fn injection(&self) -> Option<(TSLanguage, &'static [&'static str])>
+    where
+        Self: Sized, // Exclude from trait object
+    {
+        None
+    }
+
+    /// The source text both [`LanguageScoper::pos_query`] and
+    /// [`LanguageScoper::neg_query`] were constructed from.
+    fn source(&self) -> &str
+    where
+        Self: Sized; // Exclude from trait object
+
+    /// The source of the queries effectively run, as `(positive, negative)`.
+    ///
+    /// tree-sitter doesn't retain the source of a compiled query, so this is
+    /// reconstructed from [`LanguageScoper::source`]. The negative query is the same
+    /// source with all captures but the ignored ones disabled; those are listed in a
+    /// leading comment. Useful for debugging why something is (not) in scope.
+    fn effective_queries(&self) -> (String, Option<String>)
+    where
+        Self: Sized, // Exclude from trait object
+    {
+        let source = self.source().to_owned();
+
+        let negative = self.neg_query().map(|_| {
+            let disabled = self
+                .pos_query()
+                .capture_names()
+                .iter()
+                .filter(|name| !name.starts_with(IGNORE))
+                .map(|name| format!("@{name}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!("; Disabled captures: {disabled}\n{source}")
+        });
+
+        (source, negative)
+    }
+
     /// The language's tree-sitter parser.
     #[must_use]
     fn parser() -> TSParser
@@ -170,48 +441,405 @@ pub trait LanguageScoper: Scoper + Find + Send + Sync {
     where
         Self: Sized, // Exclude from trait object
     {
-        // tree-sitter is about incremental parsing, which we don't use here
-        let old_tree = None;
-
-        trace!("Parsing into AST: {:?}", input);
+        let (tree, pos_query, neg_query) = parse(self, input);
+        let root = tree.root_node();
 
-        let tree = Self::parser()
-            .parse(input, old_tree)
-            .expect("No language set in parser, or other unrecoverable error");
+        let ranges = run_query(pos_query, root, input);
+        match neg_query {
+            Some(nq) => ranges - run_query(nq, root, input),
+            None => ranges,
+        }
+    }
 
+    /// Scope the given input using the language's query, keeping captures apart.
+    ///
+    /// As opposed to [`LanguageScoper::scope_via_query`], captures directly bordering
+    /// each other are not merged into one, so actions process each separately. Each
+    /// capture carries its name (and the text it captured) as a
+    /// [`CaptureGroup::Named`](super::regex::CaptureGroup::Named) in its context, like
+    /// regex capture groups, so for example `$name` in a
+    /// [`Replacement`](crate::actions::Replacement) refers to it. A node captured
+    /// under multiple names carries all of them.
+    ///
+    /// tree-sitter returns captures in no particular order. They are returned ordered
+    /// by start offset. Scopes cannot overlap, so for captures overlapping each other,
+    /// only the one starting first is kept (the outer one if they start at the same
+    /// offset). Parts covered by the negative query are cut out, leaving the remaining
+    /// pieces of a capture with the capture's context.
+    fn scope_via_query_unmerged<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee>
+    where
+        Self: Sized, // Exclude from trait object
+    {
+        let (tree, pos_query, neg_query) = parse(self, input);
         let root = tree.root_node();
-        debug!(
-            "S expression of parsed source code is: {:?}",
-            root.to_sexp()
-        );
+        let names = pos_query.capture_names();
 
-        let run = |query: &TSQuery| {
-            trace!("Running query: {:?}", query);
+        let mut qc = TSQueryCursor::new();
+        let mut captures: Vec<(Range<usize>, &str)> = qc
+            .matches(pos_query, root, input.as_bytes())
+            .flat_map(|query_match| {
+                let pattern = query_match.pattern_index;
+                query_match.captures.iter().map(move |capture| {
+                    (
+                        capture_range(pos_query, pattern, capture, input),
+                        names[capture.index as usize],
+                    )
+                })
+            })
+            .filter(|(range, _)| !range.is_empty())
+            .collect();
+        captures.sort_by_key(|(range, _)| (range.start, Reverse(range.end)));
 
-            let mut qc = TSQueryCursor::new();
-            let matches = qc.matches(query, root, input.as_bytes());
+        let mut kept: Vec<(Range<usize>, HashMap<CaptureGroup, &'viewee str>)> = Vec::new();
+        for (range, name) in captures {
+            let group = CaptureGroup::Named(name.to_owned());
 
-            let mut ranges: Ranges<usize> = matches
-                .flat_map(|query_match| query_match.captures)
-                .map(|capture| capture.node.byte_range())
-                .collect();
+            match kept.last_mut() {
+                Some((last, context)) if *last == range => {
+                    context.insert(group, &input[range]);
+                }
+                Some((last, _)) if range.start < last.end => {
+                    trace!("Dropping capture {range:?} overlapping {last:?}");
+                }
+                _ => kept.push((range.clone(), HashMap::from([(group, &input[range])]))),
+            }
+        }
 
-            // ⚠️ tree-sitter queries with multiple captures will return them in some
-            // mixed order (not ordered, and not merged), but we later rely on cleanly
-            // ordered, non-overlapping ranges (a bit unfortunate we have to know about
-            // that remote part over here).
-            ranges.merge();
-            trace!("Querying yielded ranges: {:?}", ranges);
+        let mut pieces = kept
+            .iter()
+            .map(|(range, _)| range.clone())
+            .collect::<Ranges<_>>();
+        if let Some(nq) = neg_query {
+            pieces = pieces - run_query(nq, root, input);
+        }
 
-            ranges
-        };
+        // Pieces are in order, and each lies within exactly one kept capture.
+        let mut kept = kept.into_iter().peekable();
+        let ranges = pieces
+            .into_iter()
+            .filter_map(|piece| {
+                while kept.peek()?.0.end <= piece.start {
+                    kept.next();
+                }
+                let (_, context) = kept.peek()?;
 
-        let ranges = run(self.pos_query());
-        match &self.neg_query() {
-            Some(nq) => ranges - run(nq),
-            None => ranges,
+                Some((piece, Some(ScopeContext::CaptureGroups(context.clone()))))
+            })
+            .collect();
+        trace!("Querying yielded unmerged ranges: {:?}", ranges);
+
+        ranges
+    }
+
+    /// The ranges of syntax nodes enclosing the given byte `offset` into `input`, the
+    /// innermost one first, followed by all of its ancestors up to the root.
+    ///
+    /// Powers features like "expand selection" in editors. Only named nodes count,
+    /// not anonymous ones such as punctuation. Ancestors spanning the very same range
+    /// as their child are left out, so each range is strictly larger than the one
+    /// before. An `offset` past the end of `input` is treated as its end.
+    fn enclosing_nodes(&self, input: &str, offset: usize) -> Vec<Range<usize>>
+    where
+        Self: Sized, // Exclude from trait object
+    {
+        let (tree, _) = parse_tree(self, input);
+        let offset = offset.min(input.len());
+
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        let mut node = tree
+            .root_node()
+            .named_descendant_for_byte_range(offset, offset);
+        while let Some(current) = node {
+            let range = current.byte_range();
+            if current.is_named() && ranges.last() != Some(&range) {
+                ranges.push(range);
+            }
+
+            node = current.parent();
+        }
+        trace!("Nodes enclosing offset {offset}: {:?}", ranges);
+
+        ranges
+    }
+}
+
+/// Parses the given input into a syntax tree using the language's grammar, or its
+/// [dialect](LanguageScoper::dialect) if that fits better.
+///
+/// Returns the tree along with the queries to run against it, as `(tree, positive,
+/// negative)`.
+fn parse<'a, L: LanguageScoper>(
+    scoper: &'a L,
+    input: &str,
+) -> (Tree, &'a TSQuery, Option<&'a TSQuery>) {
+    let (tree, is_dialect) = parse_tree(scoper, input);
+    let (pos_query, neg_query) = queries(scoper, is_dialect);
+
+    (tree, pos_query, neg_query)
+}
+
+thread_local! {
+    /// The file input is currently read from on this thread, if any. See [`in_file`].
+    static FILE: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, with input scoped on this thread inside of it read from the file at
+/// `path`.
+///
+/// Scopers only ever see input text, not where it came from. Some languages however
+/// need to know the kind of file to pick the grammar to parse it with, like TSX for
+/// `.tsx` files (see [`LanguageScoper::dialect_extensions`]). Without this, the
+/// grammar is picked by content alone.
+pub fn in_file<R>(path: &Path, f: impl FnOnce() -> R) -> R {
+    let previous = FILE.replace(Some(path.to_owned()));
+    let result = f();
+    FILE.set(previous);
+
+    result
+}
+
+/// Whether the file input is currently read from calls for the
+/// [dialect](LanguageScoper::dialect) of `scoper`.
+fn in_dialect_file<L: LanguageScoper>(scoper: &L) -> bool {
+    FILE.with_borrow(|path| {
+        path.as_deref()
+            .and_then(Path::extension)
+            .is_some_and(|extension| scoper.dialect_extensions().iter().any(|e| extension == *e))
+    })
+}
+
+/// Parses the given input like [`parse`], returning whether the
+/// [dialect](LanguageScoper::dialect) was used.
+fn parse_tree<L: LanguageScoper>(scoper: &L, input: &str) -> (Tree, bool) {
+    if let Some((grammar, kinds)) = scoper.injection() {
+        let tree = parse_as(&L::lang(), input);
+        return (parse_injected(&grammar, &tree, kinds, input), false);
+    }
+
+    let Some((dialect, ..)) = scoper.dialect() else {
+        return (parse_as(&L::lang(), input), false);
+    };
+
+    if in_dialect_file(scoper) {
+        debug!("File calls for dialect, using that.");
+        return (parse_as(&dialect, input), true);
+    }
+
+    // Queries only valid for the dialect match nothing using the main grammar.
+    if scoper.pos_query().pattern_count() == 0 {
+        debug!("Query only valid for dialect, using that.");
+        return (parse_as(&dialect, input), true);
+    }
+
+    let tree = parse_as(&L::lang(), input);
+    if tree.root_node().has_error() {
+        let dialect_tree = parse_as(&dialect, input);
+
+        if count_errors(&dialect_tree) < count_errors(&tree) {
+            debug!("Input parses with fewer errors using dialect, using that.");
+            return (dialect_tree, true);
+        }
+    }
+
+    (tree, false)
+}
+
+/// The queries of `scoper` to run against a tree parsed using its grammar, or its
+/// [dialect](LanguageScoper::dialect), as `(positive, negative)`.
+fn queries<L: LanguageScoper>(scoper: &L, is_dialect: bool) -> (&TSQuery, Option<&TSQuery>) {
+    match scoper.dialect() {
+        Some((_, pos_query, neg_query)) if is_dialect => (pos_query, neg_query),
+        _ => (scoper.pos_query(), scoper.neg_query()),
+    }
+}
+
+/// Counts the `ERROR` and missing nodes in `tree`.
+fn count_errors(tree: &Tree) -> usize {
+    if !tree.root_node().has_error() {
+        return 0;
+    }
+
+    let mut count = 0;
+    let mut cursor = tree.walk();
+    'walk: loop {
+        let node = cursor.node();
+        if node.is_error() || node.is_missing() {
+            count += 1;
+        }
+
+        // Errors can only be found below nodes reporting to contain some.
+        if node.has_error() && cursor.goto_first_child() {
+            continue;
+        }
+
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                break 'walk;
+            }
+        }
+    }
+
+    count
+}
+
+/// Parses the given input into a syntax tree using the given grammar.
+fn parse_as(lang: &TSLanguage, input: &str) -> Tree {
+    // tree-sitter is about incremental parsing, which we don't use here
+    let old_tree = None;
+
+    trace!("Parsing into AST: {:?}", input);
+
+    let mut parser = TSParser::new();
+    parser
+        .set_language(lang)
+        .expect("Should be able to load language grammar and parser");
+
+    let tree = parser
+        .parse(input, old_tree)
+        .expect("No language set in parser, or other unrecoverable error");
+
+    debug!(
+        "S expression of parsed source code is: {:?}",
+        tree.root_node().to_sexp()
+    );
+
+    tree
+}
+
+/// Parses the contents of all nodes of the given `kinds` in `tree`, minus their named
+/// children, using `grammar`, see [`LanguageScoper::injection`].
+///
+/// All contents are parsed as one, as if they were a single contiguous text, with
+/// offsets still referring to `input`.
+fn parse_injected(grammar: &TSLanguage, tree: &Tree, kinds: &[&str], input: &str) -> Tree {
+    let mut ranges = Vec::new();
+    let mut push = |start: (usize, Point), end: (usize, Point)| {
+        if start.0 < end.0 {
+            ranges.push(TSRange {
+                start_byte: start.0,
+                end_byte: end.0,
+                start_point: start.1,
+                end_point: end.1,
+            });
+        }
+    };
+
+    let mut cursor = tree.walk();
+    'walk: loop {
+        let node = cursor.node();
+        if kinds.contains(&node.kind()) {
+            let mut start = (node.start_byte(), node.start_position());
+            for child in node.named_children(&mut node.walk()) {
+                push(start, (child.start_byte(), child.start_position()));
+                start = (child.end_byte(), child.end_position());
+            }
+            push(start, (node.end_byte(), node.end_position()));
+        } else if cursor.goto_first_child() {
+            continue;
+        }
+
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                break 'walk;
+            }
         }
     }
+    trace!("Parsing injected ranges: {:?}", ranges);
+
+    if ranges.is_empty() {
+        // No ranges means *everything* to tree-sitter; there's nothing to parse though.
+        return parse_as(grammar, "");
+    }
+
+    let mut parser = TSParser::new();
+    parser
+        .set_language(grammar)
+        .expect("Should be able to load language grammar and parser");
+    parser
+        .set_included_ranges(&ranges)
+        .expect("Ranges to be ordered and not overlapping, as taken from a tree");
+
+    parser
+        .parse(input, None)
+        .expect("No language set in parser, or other unrecoverable error")
+}
+
+/// The byte range of `capture`, as found by the query's pattern at index `pattern`.
+///
+/// This is the range of the captured node, narrowed by an `#offset!` directive of the
+/// pattern, if any. As in Neovim, `(#offset! @capture start_row start_col end_row
+/// end_col)` moves the start and end of the capture, for example to leave out the
+/// delimiters of a node consisting of a single token. Only column offsets are
+/// supported (both row offsets need to be `0`), counted in bytes. Ranges cannot grow
+/// beyond the captured node. Directives not fulfilling these requirements are
+/// ignored.
+fn capture_range(
+    query: &TSQuery,
+    pattern: usize,
+    capture: &QueryCapture<'_>,
+    input: &str,
+) -> Range<usize> {
+    let range = capture.node.byte_range();
+
+    query
+        .general_predicates(pattern)
+        .iter()
+        .filter(|predicate| predicate.operator.as_ref() == "offset!")
+        .find_map(|predicate| {
+            let [QueryPredicateArg::Capture(index), offsets @ ..] = predicate.args.as_ref() else {
+                return None;
+            };
+            if *index != capture.index {
+                return None;
+            }
+
+            let offsets = offsets
+                .iter()
+                .map(|arg| match arg {
+                    QueryPredicateArg::String(s) => s.parse::<isize>().ok(),
+                    QueryPredicateArg::Capture(_) => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            let [0, start, 0, end] = offsets[..] else {
+                return None;
+            };
+
+            let start = range.start.checked_add_signed(start)?;
+            let end = range.end.checked_add_signed(end)?;
+            let narrowed = range.start <= start && start <= end && end <= range.end;
+
+            (narrowed && input.is_char_boundary(start) && input.is_char_boundary(end))
+                .then_some(start..end)
+        })
+        .unwrap_or(range)
+}
+
+/// Runs the query against the tree at `root`, collecting the ranges of all captures
+/// of all matches.
+fn run_query(query: &TSQuery, root: Node<'_>, input: &str) -> Ranges<usize> {
+    trace!("Running query: {:?}", query);
+
+    let mut qc = TSQueryCursor::new();
+    let matches = qc.matches(query, root, input.as_bytes());
+
+    let mut ranges: Ranges<usize> = matches
+        .flat_map(|query_match| {
+            let pattern = query_match.pattern_index;
+            query_match
+                .captures
+                .iter()
+                .map(move |capture| capture_range(query, pattern, capture, input))
+        })
+        .collect();
+
+    // ⚠️ tree-sitter queries with multiple captures will return them in some
+    // mixed order (not ordered, and not merged), but we later rely on cleanly
+    // ordered, non-overlapping ranges (a bit unfortunate we have to know about
+    // that remote part over here).
+    ranges.merge();
+    trace!("Querying yielded ranges: {:?}", ranges);
+
+    ranges
 }
 
 impl<T> Scoper for T
@@ -221,12 +849,22 @@ where
     fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
         self.scope_via_query(input).into()
     }
+
+    fn syntax_errors(&self, input: &str) -> Option<usize> {
+        let (tree, _) = parse_tree(self, input);
+
+        Some(count_errors(&tree))
+    }
 }
 
 impl Scoper for Box<dyn LanguageScoper> {
     fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
         self.as_ref().scope_raw(input)
     }
+
+    fn syntax_errors(&self, input: &str) -> Option<usize> {
+        self.as_ref().syntax_errors(input)
+    }
 }
 
 impl Scoper for &[Box<dyn LanguageScoper>] {
@@ -236,31 +874,889 @@ impl Scoper for &[Box<dyn LanguageScoper>] {
     /// position/range is considered in scope. In some sense, this is the opposite of
     /// [`ScopedViewBuilder::explode`], which is subtractive.
     fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
-        trace!("Scoping many scopes: {:?}", input);
+        scope_many(self, input)
+    }
+}
+
+/// A scoper for a kind of file, as found via [`Find`].
+///
+/// Any [`LanguageScoper`] is one, but so are scopers for languages without a
+/// tree-sitter grammar (like [`ini::Ini`]). Use this to handle either uniformly.
+pub trait FileScoper: Scoper + Find {}
 
-        if self.is_empty() {
-            trace!("Short-circuiting: self is empty, nothing to scope.");
-            return vec![(0..input.len(), None)].into_iter().collect();
+impl<T> FileScoper for T where T: Scoper + Find {}
+
+impl Scoper for Box<dyn FileScoper> {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        self.as_ref().scope_raw(input)
+    }
+
+    fn syntax_errors(&self, input: &str) -> Option<usize> {
+        self.as_ref().syntax_errors(input)
+    }
+}
+
+impl Scoper for &[Box<dyn FileScoper>] {
+    /// Same as the implementation for multiple [`LanguageScoper`]s.
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        scope_many(self, input)
+    }
+}
+
+/// Applies *multiple* scopers all at once, OR'ing their results.
+fn scope_many<'viewee, S: Scoper>(
+    scopers: &[S],
+    input: &'viewee str,
+) -> RangesWithContext<'viewee> {
+    trace!("Scoping many scopes: {:?}", input);
+
+    if scopers.is_empty() {
+        trace!("Short-circuiting: self is empty, nothing to scope.");
+        return vec![(0..input.len(), None)].into_iter().collect();
+    }
+
+    // This is slightly leaky in that it drops down to a more 'primitive' layer and
+    // uses `Ranges`.
+    let mut ranges: Ranges<usize> = scopers
+        .iter()
+        .flat_map(|s| s.scope_raw(input))
+        .map(|(range, ctx)| {
+            assert!(
+                ctx.is_none(),
+                "When language scoping runs, no contexts exist yet."
+            );
+            range
+        })
+        .collect();
+    ranges.merge();
+    info!("New ranges after scoping many: {ranges:?}");
+
+    let ranges: RangesWithContext<'_> = ranges.into_iter().map(|r| (r, None)).collect();
+
+    ranges
+}
+
+/// A language scoper only keeping the `n`th occurrence of a capture per query match.
+///
+/// Regular language scoping flattens all captures of all matches. For queries
+/// capturing repeated sub-nodes, this loses which captures belong together. For
+/// example, with a quantified group as in the Python query
+///
+/// ```scheme
+/// (call arguments: (argument_list ((_) @arg ","?)+))
+/// ```
+///
+/// each call is a single match, holding all of its arguments as `@arg` captures.
+/// Selecting the second `@arg` then scopes the second argument of each call. Matches
+/// with fewer occurrences are skipped. Only the selected capture is scoped: other
+/// captures of the query are dropped, while [`IGNORE`]d parts are still excluded.
+///
+/// Note that quantifiers only repeat over *adjacent* siblings, so separators such as
+/// the commas above need to be part of the repeated group. Without any quantifier,
+/// tree-sitter produces one match *per argument*, which is rarely what is wanted here.
+#[derive(Debug)]
+pub struct NthCapture<L> {
+    language: L,
+    capture: String,
+    n: NonZero<usize>,
+}
+
+impl<L: LanguageScoper> NthCapture<L> {
+    /// Creates a new scoper selecting the `n`th (one-based) occurrence of the capture
+    /// named `capture` in each match of `language`'s query.
+    ///
+    /// # Errors
+    ///
+    /// If the query contains no capture of the given name.
+    pub fn new(language: L, capture: String, n: NonZero<usize>) -> Result<Self, NthCaptureError> {
+        if language
+            .pos_query()
+            .capture_index_for_name(&capture)
+            .is_none()
+        {
+            return Err(NthCaptureError::UnknownCapture(capture));
+        }
+
+        Ok(Self {
+            language,
+            capture,
+            n,
+        })
+    }
+}
+
+/// An error in constructing an [`NthCapture`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NthCaptureError {
+    /// The query does not contain a capture of this name.
+    UnknownCapture(String),
+}
+
+impl fmt::Display for NthCaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownCapture(name) => write!(f, "Query has no capture named '{name}'"),
+        }
+    }
+}
+
+impl Error for NthCaptureError {}
+
+impl<L: LanguageScoper> LanguageScoper for NthCapture<L> {
+    fn lang() -> TSLanguage {
+        L::lang()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        self.language.pos_query()
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.language.neg_query()
+    }
+
+    fn source(&self) -> &str {
+        self.language.source()
+    }
+
+    fn dialect(&self) -> Option<(TSLanguage, &TSQuery, Option<&TSQuery>)> {
+        self.language.dialect()
+    }
+
+    fn dialect_extensions(&self) -> &'static [&'static str] {
+        self.language.dialect_extensions()
+    }
+
+    fn scope_via_query(&self, input: &str) -> Ranges<usize> {
+        let (tree, query, neg_query) = parse(self, input);
+        let root = tree.root_node();
+
+        let index = query
+            .capture_index_for_name(&self.capture)
+            .expect("Capture to exist, as object cannot be constructed otherwise");
+
+        let mut qc = TSQueryCursor::new();
+        let mut ranges: Ranges<usize> = qc
+            .matches(query, root, input.as_bytes())
+            .filter_map(|query_match| {
+                query_match
+                    .captures
+                    .iter()
+                    .filter(|capture| capture.index == index)
+                    .nth(self.n.get() - 1)
+                    .map(|capture| capture_range(query, query_match.pattern_index, capture, input))
+            })
+            .collect();
+        ranges.merge();
+        trace!("Selecting capture occurrences yielded ranges: {:?}", ranges);
+
+        match neg_query {
+            Some(nq) => ranges - run_query(nq, root, input),
+            None => ranges,
+        }
+    }
+}
+
+impl<L: Find> Find for NthCapture<L> {
+    fn extensions(&self) -> &'static [&'static str] {
+        self.language.extensions()
+    }
+
+    fn filenames(&self) -> &'static [&'static str] {
+        self.language.filenames()
+    }
+
+    fn interpreters(&self) -> Option<&'static [&'static str]> {
+        self.language.interpreters()
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        self.language.modeline_names()
+    }
+
+    fn is_path_invalid(&self, path: &Path) -> bool {
+        self.language.is_path_invalid(path)
+    }
+}
+
+/// Scopes using a language's query like the language itself, but without merging
+/// bordering captures, see [`LanguageScoper::scope_via_query_unmerged`].
+///
+/// ## Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use srgn::scoping::langs::python::{CustomPythonQuery, Python, PythonQuery};
+/// use srgn::scoping::langs::Unmerged;
+/// use srgn::scoping::view::ScopedViewBuilder;
+///
+/// let input = "x = f'{a}{b}'";
+/// let query = CustomPythonQuery::from_str("(interpolation) @i").unwrap();
+/// let scoper = Unmerged::new(Python::new(PythonQuery::Custom(query)));
+///
+/// let mut builder = ScopedViewBuilder::new(input);
+/// builder.explode(&scoper);
+/// let mut view = builder.build();
+/// view.replace("<$i>".to_owned()).unwrap();
+///
+/// // Merged, this would have been a single `<{a}{b}>`.
+/// assert_eq!(view.to_string(), "x = f'<{a}><{b}>'");
+/// ```
+#[derive(Debug)]
+pub struct Unmerged<L> {
+    language: L,
+}
+
+impl<L: LanguageScoper> Unmerged<L> {
+    /// Creates a new scoper keeping the captures of `language`'s query apart.
+    #[must_use]
+    pub const fn new(language: L) -> Self {
+        Self { language }
+    }
+}
+
+impl<L: LanguageScoper> Scoper for Unmerged<L> {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        self.language.scope_via_query_unmerged(input)
+    }
+}
+
+/// Runs a query only within the subtrees of nodes matched by another query, of the
+/// same language.
+///
+/// The first query selects *containers*: all its captures. The second query then runs
+/// against the subtree of each container only, so its matches can neither lie outside
+/// a container nor extend beyond one. As opposed to intersecting the scopes of both
+/// queries, matches only partially inside a container are dropped entirely, instead of
+/// being cut down to the part inside. Negative queries ([`IGNORE`]d captures) of the
+/// second query apply as usual.
+///
+/// ## Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use srgn::scoping::langs::rust::{CustomRustQuery, PreparedRustQuery, Rust, RustQuery};
+/// use srgn::scoping::langs::SubtreeQuery;
+/// use srgn::scoping::Scoper;
+///
+/// let input = "use a::b;\nmod inner {\n    use c::d;\n}\n";
+/// let containers = CustomRustQuery::from_str("(mod_item) @mod").unwrap();
+/// let scoper = SubtreeQuery::new(
+///     Rust::new(RustQuery::Custom(containers)),
+///     Rust::new(RustQuery::Prepared(PreparedRustQuery::Uses)),
+/// );
+///
+/// let scoped: Vec<_> = scoper
+///     .scope_raw(input)
+///     .into_iter()
+///     .map(|(r, _)| &input[r])
+///     .collect();
+/// assert_eq!(scoped, vec!["c::d"]);
+/// ```
+#[derive(Debug)]
+pub struct SubtreeQuery<L> {
+    containers: L,
+    query: L,
+}
+
+impl<L: LanguageScoper> SubtreeQuery<L> {
+    /// Creates a new scoper running `query` only within the nodes captured by
+    /// `containers`.
+    #[must_use]
+    pub const fn new(containers: L, query: L) -> Self {
+        Self { containers, query }
+    }
+}
+
+impl<L: LanguageScoper> Scoper for SubtreeQuery<L> {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        let (tree, is_dialect) = parse_tree(&self.containers, input);
+        let root = tree.root_node();
+        let (containers_query, _) = queries(&self.containers, is_dialect);
+        let (pos_query, neg_query) = queries(&self.query, is_dialect);
+
+        let mut qc = TSQueryCursor::new();
+        let containers: Vec<Node<'_>> = qc
+            .matches(containers_query, root, input.as_bytes())
+            .flat_map(|query_match| query_match.captures)
+            .map(|capture| capture.node)
+            .collect();
+        trace!("Running query within {} containers", containers.len());
+
+        let mut ranges: Ranges<usize> = containers
+            .into_iter()
+            .flat_map(|container| {
+                let mut qc = TSQueryCursor::new();
+                qc.set_byte_range(container.byte_range());
+
+                qc.matches(pos_query, container, input.as_bytes())
+                    .flat_map(|query_match| {
+                        let pattern = query_match.pattern_index;
+                        query_match
+                            .captures
+                            .iter()
+                            .map(move |capture| capture_range(pos_query, pattern, capture, input))
+                    })
+                    .filter(|range| {
+                        // Matches need only *intersect* the byte range.
+                        container.start_byte() <= range.start && range.end <= container.end_byte()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        ranges.merge();
+
+        let ranges = match neg_query {
+            Some(nq) => ranges - run_query(nq, root, input),
+            None => ranges,
+        };
+        trace!("Querying within containers yielded ranges: {:?}", ranges);
+
+        ranges.into()
+    }
+}
+
+/// Expands each hit of a wrapped scoper to the innermost *statement* enclosing it,
+/// according to the grammar of language `L`.
+///
+/// Useful to act on entire statements containing something, like deleting all
+/// statements calling some deprecated function.
+///
+/// Statements are syntax nodes of a kind ending in `_statement`, `_declaration`,
+/// `_definition` or `_item`, covering for example `expression_statement` and
+/// `let_declaration` in Rust, or `return_statement` and `function_definition` in
+/// Python. Hits not inside any statement are dropped. Statements don't include
+/// trailing line breaks. Contexts of the wrapped scoper (like regex capture groups)
+/// are dropped.
+///
+/// ## Examples
+///
+/// ```rust
+/// use srgn::scoping::langs::rust::Rust;
+/// use srgn::scoping::langs::EnclosingStatement;
+/// use srgn::scoping::literal::Literal;
+/// use srgn::scoping::Scoper;
+///
+/// let input = "fn f() {\n    let x = old(1);\n    g(x);\n}";
+/// let scoper =
+///     EnclosingStatement::<Rust>::new(Box::new(Literal::try_from("old".to_owned()).unwrap()));
+///
+/// let scoped: Vec<_> = scoper
+///     .scope_raw(input)
+///     .into_iter()
+///     .map(|(r, _)| &input[r])
+///     .collect();
+/// assert_eq!(scoped, vec!["let x = old(1);"]);
+/// ```
+pub struct EnclosingStatement<L> {
+    scoper: Box<dyn Scoper>,
+    _language: PhantomData<L>,
+}
+
+impl<L: LanguageScoper> EnclosingStatement<L> {
+    /// Creates a new instance expanding the hits of `scoper` to their enclosing
+    /// statements.
+    #[must_use]
+    pub fn new(scoper: Box<dyn Scoper>) -> Self {
+        Self {
+            scoper,
+            _language: PhantomData,
         }
+    }
+}
+
+impl<L> fmt::Debug for EnclosingStatement<L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Scoper is an opaque trait object.
+        f.debug_struct("EnclosingStatement")
+            .field("language", &std::any::type_name::<L>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Whether a syntax node of this `kind` is a statement, see [`EnclosingStatement`].
+fn is_statement(kind: &str) -> bool {
+    ["_statement", "_declaration", "_definition", "_item"]
+        .iter()
+        .any(|suffix| kind.ends_with(suffix))
+}
+
+impl<L: LanguageScoper> Scoper for EnclosingStatement<L> {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        let tree = parse_as(&L::lang(), input);
+        let root = tree.root_node();
 
-        // This is slightly leaky in that it drops down to a more 'primitive' layer and
-        // uses `Ranges`.
         let mut ranges: Ranges<usize> = self
-            .iter()
-            .flat_map(|s| s.scope_raw(input))
-            .map(|(range, ctx)| {
-                assert!(
-                    ctx.is_none(),
-                    "When language scoping runs, no contexts exist yet."
-                );
-                range
+            .scoper
+            .scope_raw(input)
+            .into_iter()
+            .filter_map(|(range, _)| {
+                let mut node = root.descendant_for_byte_range(range.start, range.end)?;
+
+                while !is_statement(node.kind()) {
+                    node = node.parent()?;
+                }
+
+                Some(node.byte_range())
             })
             .collect();
         ranges.merge();
-        info!("New ranges after scoping many: {ranges:?}");
+        trace!("Enclosing statements yielded ranges: {:?}", ranges);
 
-        let ranges: RangesWithContext<'_> = ranges.into_iter().map(|r| (r, None)).collect();
+        ranges.into()
+    }
+}
 
-        ranges
+/// Scopes the syntax node enclosing a byte offset, or one of its ancestors.
+///
+/// See [`LanguageScoper::enclosing_nodes`] for which nodes are considered. A `level` of
+/// zero scopes the innermost node, one its parent, and so on; repeatedly increasing
+/// it is akin to "expand selection" in editors. Nothing is scoped if there aren't
+/// that many levels.
+///
+/// ## Example
+///
+/// ```rust
+/// use srgn::scoping::langs::rust::{PreparedRustQuery, Rust, RustQuery};
+/// use srgn::scoping::langs::EnclosingNode;
+/// use srgn::scoping::Scoper;
+///
+/// let input = "fn f() { g(a + b); }";
+/// let offset = input.find('b').unwrap();
+///
+/// let scoped = |level| {
+///     let rust = Rust::new(RustQuery::Prepared(PreparedRustQuery::Comments));
+///     let scoper = EnclosingNode::new(rust, offset, level);
+///     let ranges = scoper.scope_raw(input);
+///
+///     ranges
+///         .into_iter()
+///         .map(|(r, _)| &input[r])
+///         .collect::<Vec<_>>()
+/// };
+///
+/// assert_eq!(scoped(0), ["b"]);
+/// assert_eq!(scoped(1), ["a + b"]);
+/// assert_eq!(scoped(2), ["(a + b)"]);
+/// ```
+#[derive(Debug)]
+pub struct EnclosingNode<L> {
+    language: L,
+    offset: usize,
+    level: usize,
+}
+
+impl<L: LanguageScoper> EnclosingNode<L> {
+    /// Creates a new instance scoping the node `level`s up from the innermost one
+    /// enclosing `offset`, parsing input using `language`.
+    ///
+    /// The queries of `language` are irrelevant, only its grammar is used.
+    #[must_use]
+    pub const fn new(language: L, offset: usize, level: usize) -> Self {
+        Self {
+            language,
+            offset,
+            level,
+        }
+    }
+}
+
+impl<L: LanguageScoper> Scoper for EnclosingNode<L> {
+    fn scope_raw<'viewee>(&self, input: &'viewee str) -> RangesWithContext<'viewee> {
+        self.language
+            .enclosing_nodes(input, self.offset)
+            .into_iter()
+            .nth(self.level)
+            .into_iter()
+            .collect::<Ranges<_>>()
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    use super::python::{CustomPythonQuery, Python, PythonQuery};
+    use super::*;
+
+    const CALLS: &str = "f(a, b, c)\ng(x)\nh(1, 2)\nk()\n";
+
+    fn nth_arg(n: usize) -> NthCapture<Python> {
+        let query =
+            CustomPythonQuery::from_str(r#"(call arguments: (argument_list ((_) @arg ","?)+))"#)
+                .expect("valid query");
+        let python = Python::new(PythonQuery::Custom(query));
+
+        NthCapture::new(python, "arg".into(), NonZero::new(n).unwrap()).unwrap()
+    }
+
+    #[rstest]
+    #[case::first(1, vec!["a", "x", "1"])]
+    #[case::second(2, vec!["b", "2"])]
+    #[case::third(3, vec!["c"])]
+    #[case::none_left(4, vec![])]
+    fn test_nth_capture(#[case] n: usize, #[case] expected: Vec<&str>) {
+        let ranges = nth_arg(n).scope_via_query(CALLS);
+
+        let scoped: Vec<&str> = ranges.iter().map(|r| &CALLS[r.clone()]).collect();
+        assert_eq!(scoped, expected);
+    }
+
+    fn unmerged(query: &str, input: &str) -> Vec<(String, Vec<String>)> {
+        let query = CustomPythonQuery::from_str(query).expect("valid query");
+        let python = Python::new(PythonQuery::Custom(query));
+
+        python
+            .scope_via_query_unmerged(input)
+            .into_iter()
+            .map(|(range, context)| {
+                let Some(ScopeContext::CaptureGroups(groups)) = context else {
+                    panic!("context to be present");
+                };
+                let names = groups
+                    .into_keys()
+                    .map(|group| group.to_string())
+                    .sorted()
+                    .collect();
+
+                (input[range].to_owned(), names)
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case::bordering(
+        "(interpolation) @i",
+        "f'{a}{b}'",
+        vec![("{a}", vec!["i (named)"]), ("{b}", vec!["i (named)"])],
+    )]
+    #[case::distinct_names_bordering(
+        "(string (string_start) @start (string_content) @content)",
+        "'abc'",
+        vec![("'", vec!["start (named)"]), ("abc", vec!["content (named)"])],
+    )]
+    #[case::same_node_several_names(
+        "((identifier) @a (#eq? @a \"x\")) @b",
+        "x",
+        vec![("x", vec!["a (named)", "b (named)"])],
+    )]
+    #[case::overlapping_outer_kept(
+        "(call) @call (identifier) @id",
+        "f(x)\ny",
+        vec![("f(x)", vec!["call (named)"]), ("y", vec!["id (named)"])],
+    )]
+    #[case::ordered_regardless_of_query_order(
+        "(identifier) @id (integer) @int",
+        "f(1, x, 2)",
+        vec![
+            ("f", vec!["id (named)"]),
+            ("1", vec!["int (named)"]),
+            ("x", vec!["id (named)"]),
+            ("2", vec!["int (named)"]),
+        ],
+    )]
+    #[case::ignored_parts_cut_out(
+        "(call function: (_) @_SRGN_IGNORE) @call",
+        "f(x)",
+        vec![("(x)", vec!["call (named)"])],
+    )]
+    fn test_scope_via_query_unmerged(
+        #[case] query: &str,
+        #[case] input: &str,
+        #[case] expected: Vec<(&str, Vec<&str>)>,
+    ) {
+        let expected: Vec<(String, Vec<String>)> = expected
+            .into_iter()
+            .map(|(s, names)| (s.to_owned(), names.into_iter().map(str::to_owned).collect()))
+            .collect();
+
+        assert_eq!(unmerged(query, input), expected);
+    }
+
+    #[rstest]
+    #[case::narrowed("((string) @s (#offset! @s 0 1 0 -1))", vec!["abc"])]
+    #[case::start_only("((string) @s (#offset! @s 0 2 0 0))", vec!["bc'"])]
+    #[case::other_pattern_untouched(
+        "((string) @s (#offset! @s 0 1 0 -1)) (identifier) @id",
+        vec!["x", "abc"]
+    )]
+    #[case::growing_ignored("((string) @s (#offset! @s 0 -1 0 0))", vec!["'abc'"])]
+    #[case::crossing_ignored("((string) @s (#offset! @s 0 4 0 -4))", vec!["'abc'"])]
+    #[case::rows_ignored("((string) @s (#offset! @s 1 0 0 0))", vec!["'abc'"])]
+    #[case::malformed_ignored("((string) @s (#offset! @s 0 a 0 0))", vec!["'abc'"])]
+    fn test_offset_directive(#[case] query: &str, #[case] expected: Vec<&str>) {
+        let input = "x = 'abc'";
+        let query = CustomPythonQuery::from_str(query).expect("valid query");
+        let python = Python::new(PythonQuery::Custom(query));
+
+        let ranges = python.scope_via_query(input);
+
+        let scoped: Vec<&str> = ranges.iter().map(|r| &input[r.clone()]).collect();
+        assert_eq!(scoped, expected);
+    }
+
+    #[rstest]
+    #[case::valid("x = 1\n", 0)]
+    #[case::empty("", 0)]
+    #[case::unclosed_call("f(x\n", 1)]
+    #[case::several("x = = 1\ny = = 2\n", 2)]
+    fn test_syntax_errors(#[case] input: &str, #[case] expected: usize) {
+        let query = CustomPythonQuery::from_str("(comment) @c").expect("valid query");
+        let python = Python::new(PythonQuery::Custom(query));
+
+        assert_eq!(python.syntax_errors(input), Some(expected));
+    }
+
+    const NESTED_MODULES: &str = r#"use top::level;
+
+mod a {
+    use in_a::x;
+
+    mod b {
+        use in_b::y;
+
+        fn f() -> &'static str {
+            "in b"
+        }
+    }
+}
+
+mod c {
+    use in_c::z;
+}
+
+fn g() -> &'static str {
+    "top"
+}
+"#;
+
+    #[rstest]
+    #[case::all_modules("(mod_item) @m", "(use_declaration) @u", vec!["use in_a::x;", "use in_b::y;", "use in_c::z;"])]
+    #[case::named_module(
+        r#"(mod_item name: (identifier) @_SRGN_IGNORE (#eq? @_SRGN_IGNORE "a")) @m"#,
+        "(use_declaration) @u",
+        vec!["use in_a::x;", "use in_b::y;"]
+    )]
+    #[case::innermost_module(
+        r#"(mod_item name: (identifier) @_SRGN_IGNORE (#eq? @_SRGN_IGNORE "b")) @m"#,
+        "(string_literal) @s",
+        vec!["\"in b\""]
+    )]
+    #[case::container_itself_matches("(mod_item) @m", "(mod_item name: (identifier) @n)", vec!["a", "b", "c"])]
+    #[case::partially_inside_dropped("(mod_item name: (identifier) @n)", "(mod_item) @m", vec![])]
+    #[case::no_containers("(while_expression) @w", "(use_declaration) @u", vec![])]
+    #[case::negative_query_applies(
+        "(mod_item) @m",
+        "(use_declaration argument: (scoped_identifier path: (_) @_SRGN_IGNORE)) @u",
+        vec!["use ", "::x;", "use ", "::y;", "use ", "::z;"]
+    )]
+    fn test_subtree_query(
+        #[case] containers: &str,
+        #[case] query: &str,
+        #[case] expected: Vec<&str>,
+    ) {
+        let scoper = SubtreeQuery::new(
+            rust::Rust::new(rust::RustQuery::Custom(
+                rust::CustomRustQuery::from_str(containers).unwrap(),
+            )),
+            rust::Rust::new(rust::RustQuery::Custom(
+                rust::CustomRustQuery::from_str(query).unwrap(),
+            )),
+        );
+
+        let scoped: Vec<&str> = scoper
+            .scope_raw(NESTED_MODULES)
+            .into_iter()
+            .map(|(r, _)| &NESTED_MODULES[r])
+            .collect();
+        assert_eq!(scoped, expected);
+    }
+
+    fn enclosing_statements<L: LanguageScoper>(pattern: &str, input: &str) -> Vec<String> {
+        let regex = crate::scoping::regex::Regex::new(crate::RegexPattern::new(pattern).unwrap());
+        let scoper = EnclosingStatement::<L>::new(Box::new(regex));
+
+        scoper
+            .scope_raw(input)
+            .into_iter()
+            .map(|(r, _)| input[r].to_owned())
+            .collect()
+    }
+
+    #[rstest]
+    #[case::expression("old_api", vec!["old_api(x);"])]
+    #[case::let_declaration("compute", vec!["let x = compute(1);"])]
+    #[case::macro_invocation(r", x\)", vec!["println!(\"{}\", x);"])]
+    #[case::nested_in_if("nested", vec!["nested();"])]
+    #[case::several_hits_in_one("x = compute", vec!["let x = compute(1);"])]
+    #[case::function_name("main", vec!["fn main() {\n    let x = compute(1);\n    println!(\"{}\", x);\n    old_api(x);\n    if x > 0 {\n        nested();\n    }\n}"])]
+    #[case::no_hit("unknown", vec![])]
+    fn test_enclosing_statement_rust(#[case] pattern: &str, #[case] expected: Vec<&str>) {
+        let input = "fn main() {\n    let x = compute(1);\n    println!(\"{}\", x);\n    old_api(x);\n    if x > 0 {\n        nested();\n    }\n}\n";
+
+        assert_eq!(enclosing_statements::<rust::Rust>(pattern, input), expected);
+    }
+
+    #[rstest]
+    #[case::expression("old_api", vec!["old_api(x)"])]
+    #[case::assignment("y", vec!["y = 2"])]
+    #[case::return_statement("None", vec!["return None"])]
+    #[case::import("os", vec!["import os"])]
+    #[case::parameter("param", vec!["def f(param):\n    return None"])]
+    #[case::several_statements("old_api|y", vec!["old_api(x)", "y = 2"])]
+    fn test_enclosing_statement_python(#[case] pattern: &str, #[case] expected: Vec<&str>) {
+        let input = "import os\nx = 1\nif x:\n    old_api(x)\n    y = 2\n\ndef f(param):\n    return None\n";
+
+        assert_eq!(enclosing_statements::<Python>(pattern, input), expected);
+    }
+
+    const NESTED_EXPRESSION: &str = "fn main() {\n    let x = f(a, (b + c) * d);\n}\n";
+
+    #[rstest]
+    #[case::identifier("c", vec![
+        "c",
+        "b + c",
+        "(b + c)",
+        "(b + c) * d",
+        "(a, (b + c) * d)",
+        "f(a, (b + c) * d)",
+        "let x = f(a, (b + c) * d);",
+        "{\n    let x = f(a, (b + c) * d);\n}",
+        "fn main() {\n    let x = f(a, (b + c) * d);\n}",
+        "fn main() {\n    let x = f(a, (b + c) * d);\n}\n",
+    ])]
+    #[case::operator("+", vec![
+        "b + c",
+        "(b + c)",
+        "(b + c) * d",
+        "(a, (b + c) * d)",
+        "f(a, (b + c) * d)",
+        "let x = f(a, (b + c) * d);",
+        "{\n    let x = f(a, (b + c) * d);\n}",
+        "fn main() {\n    let x = f(a, (b + c) * d);\n}",
+        "fn main() {\n    let x = f(a, (b + c) * d);\n}\n",
+    ])]
+    fn test_enclosing_nodes(#[case] needle: &str, #[case] expected: Vec<&str>) {
+        let rust = rust::Rust::new(rust::RustQuery::Custom(
+            rust::CustomRustQuery::from_str("(identifier) @i").unwrap(),
+        ));
+        let offset = NESTED_EXPRESSION.find(needle).unwrap();
+
+        let enclosing: Vec<&str> = rust
+            .enclosing_nodes(NESTED_EXPRESSION, offset)
+            .into_iter()
+            .map(|r| &NESTED_EXPRESSION[r])
+            .collect();
+        assert_eq!(enclosing, expected);
+    }
+
+    #[rstest]
+    #[case::innermost(0, vec!["c"])]
+    #[case::parent(1, vec!["b + c"])]
+    #[case::grandparent(2, vec!["(b + c)"])]
+    #[case::beyond_root(10, vec![])]
+    fn test_enclosing_node(#[case] level: usize, #[case] expected: Vec<&str>) {
+        let rust = rust::Rust::new(rust::RustQuery::Custom(
+            rust::CustomRustQuery::from_str("(identifier) @i").unwrap(),
+        ));
+        let offset = NESTED_EXPRESSION.find('c').unwrap();
+        let scoper = EnclosingNode::new(rust, offset, level);
+
+        let scoped: Vec<&str> = scoper
+            .scope_raw(NESTED_EXPRESSION)
+            .into_iter()
+            .map(|(r, _)| &NESTED_EXPRESSION[r])
+            .collect();
+        assert_eq!(scoped, expected);
+    }
+
+    #[test]
+    fn test_enclosing_nodes_offset_past_end() {
+        let rust = rust::Rust::new(rust::RustQuery::Custom(
+            rust::CustomRustQuery::from_str("(identifier) @i").unwrap(),
+        ));
+
+        assert_eq!(rust.enclosing_nodes("fn f() {}", 100), vec![0..9]);
+    }
+
+    #[test]
+    fn test_nth_capture_unknown_capture() {
+        let query = CustomPythonQuery::from_str("(call) @call").expect("valid query");
+        let python = Python::new(PythonQuery::Custom(query));
+
+        assert_eq!(
+            NthCapture::new(python, "arg".into(), NonZero::new(1).unwrap()).unwrap_err(),
+            NthCaptureError::UnknownCapture("arg".into())
+        );
+    }
+
+    #[test]
+    fn test_nth_capture_drops_other_captures() {
+        let query = CustomPythonQuery::from_str(
+            r#"(call function: (_) @func arguments: (argument_list ((_) @arg ","?)+))"#,
+        )
+        .expect("valid query");
+        let python = Python::new(PythonQuery::Custom(query));
+        let nth_arg = NthCapture::new(python, "arg".into(), NonZero::new(1).unwrap()).unwrap();
+
+        let ranges = nth_arg.scope_via_query(CALLS);
+
+        let scoped: Vec<&str> = ranges.iter().map(|r| &CALLS[r.clone()]).collect();
+        assert_eq!(scoped, vec!["a", "x", "1"]);
+    }
+
+    #[test]
+    fn test_effective_queries_without_ignored_captures() {
+        let query = CustomPythonQuery::from_str("(call) @call").expect("valid query");
+        let python = Python::new(PythonQuery::Custom(query));
+
+        assert_eq!(
+            python.effective_queries(),
+            ("(call) @call".to_owned(), None)
+        );
+    }
+
+    #[test]
+    fn test_effective_queries_with_ignored_captures() {
+        let source = "(call function: (_) @_SRGN_IGNORE arguments: (_) @args) @call";
+        let query = CustomPythonQuery::from_str(source).expect("valid query");
+        let python = Python::new(PythonQuery::Custom(query));
+
+        let (positive, negative) = python.effective_queries();
+        assert_eq!(positive, source);
+        assert_eq!(
+            negative,
+            Some(format!("; Disabled captures: @args, @call\n{source}"))
+        );
+    }
+
+    #[test]
+    fn test_prepared_queries_compiled_once() {
+        use super::python::PreparedPythonQuery;
+
+        let a = Python::new(PythonQuery::Prepared(PreparedPythonQuery::ClassMethods));
+        let b = Python::new(PythonQuery::Prepared(PreparedPythonQuery::ClassMethods));
+        assert!(Arc::ptr_eq(&a.positive_query, &b.positive_query));
+        assert!(Arc::ptr_eq(
+            a.negative_query.as_ref().unwrap(),
+            b.negative_query.as_ref().unwrap()
+        ));
+
+        let other = Python::new(PythonQuery::Prepared(PreparedPythonQuery::Comments));
+        assert!(!Arc::ptr_eq(&a.positive_query, &other.positive_query));
+    }
+
+    #[test]
+    fn test_custom_queries_not_cached() {
+        let new = || {
+            let query = CustomPythonQuery::from_str("(call) @call").expect("valid query");
+            Python::new(PythonQuery::Custom(query))
+        };
+
+        assert!(!Arc::ptr_eq(&new().positive_query, &new().positive_query));
     }
 }
@@ -0,0 +1,135 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use const_format::formatcp;
+use tree_sitter::QueryError;
+
+use super::{tree_sitter_perl, CodeQuery, Language, LanguageScoper, TSLanguage, TSQuery};
+use crate::find::Find;
+use crate::scoping::langs::IGNORE;
+
+/// The Perl language.
+pub type Perl = Language<PerlQuery>;
+/// A query for Perl.
+pub type PerlQuery = CodeQuery<CustomPerlQuery, PreparedPerlQuery>;
+
+/// Prepared tree-sitter queries for Perl.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreparedPerlQuery {
+    /// Comments (excl. POD documentation).
+    Comments,
+    /// Strings (incl. quotes, `q//` and `qq//` quote-like operators and heredoc bodies;
+    /// excl. interpolation).
+    ///
+    /// For heredocs, only the body and its closing identifier are included, not the
+    /// opening `<<"ID"`.
+    Strings,
+    /// Subroutine definitions, named and anonymous (in their entirety).
+    Subroutines,
+    /// Variables, incl. their sigil, like `$scalar`, `@array` and `%hash`.
+    Variables,
+}
+
+impl AsRef<str> for PreparedPerlQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "(comments) @comment",
+            Self::Strings => {
+                formatcp!(
+                    r"
+                        [
+                            (string_double_quoted (interpolation) @{0})
+                            (string_qq_quoted (interpolation) @{0})
+                            (heredoc_body_statement (interpolation) @{0})
+                            (string_single_quoted)
+                            (string_double_quoted)
+                            (string_q_quoted)
+                            (string_qq_quoted)
+                            (heredoc_body_statement)
+                        ]
+                        @string
+                ",
+                    IGNORE
+                )
+            }
+            Self::Subroutines => "[(function_definition) (anonymous_function)] @sub",
+            Self::Variables => {
+                r"
+                [
+                    (scalar_variable)
+                    (array_variable)
+                    (hash_variable)
+                ]
+                @variable
+                "
+            }
+        }
+    }
+}
+
+impl From<PreparedPerlQuery> for TSQuery {
+    fn from(value: PreparedPerlQuery) -> Self {
+        Self::new(&Perl::lang(), value.as_ref()).expect("Prepared queries to be valid")
+    }
+}
+
+/// A custom tree-sitter query for Perl.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomPerlQuery(String);
+
+impl FromStr for CustomPerlQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TSQuery::new(&Perl::lang(), s) {
+            Ok(_) => Ok(Self(s.to_string())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsRef<str> for CustomPerlQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CustomPerlQuery> for TSQuery {
+    fn from(value: CustomPerlQuery) -> Self {
+        Self::new(&Perl::lang(), &value.0)
+            .expect("Valid query, as object cannot be constructed otherwise")
+    }
+}
+
+impl LanguageScoper for Perl {
+    fn lang() -> TSLanguage {
+        tree_sitter_perl::language()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        &self.positive_query
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Find for Perl {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pl", "pm"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["perl"]
+    }
+
+    fn interpreters(&self) -> Option<&'static [&'static str]> {
+        Some(&["perl"])
+    }
+}
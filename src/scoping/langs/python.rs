@@ -22,7 +22,8 @@ pub enum PreparedPythonQuery {
     Strings,
     /// Module names in imports (incl. periods; excl. `import`/`from`/`as`/`*`).
     Imports,
-    /// Docstrings (not including multi-line strings).
+    /// Docstrings (first string statement of a module, class or function body; not
+    /// including other multi-line strings).
     DocStrings,
     /// Function names, at the definition site.
     FunctionNames,
@@ -54,123 +55,136 @@ pub enum PreparedPythonQuery {
     Types,
 }
 
-impl From<PreparedPythonQuery> for TSQuery {
+impl AsRef<str> for PreparedPythonQuery {
     #[allow(clippy::too_many_lines)]
-    fn from(value: PreparedPythonQuery) -> Self {
-        Self::new(
-            &Python::lang(),
-            match value {
-                PreparedPythonQuery::Comments => "(comment) @comment",
-                PreparedPythonQuery::Strings => "(string_content) @string",
-                PreparedPythonQuery::Imports => {
-                    r"[
-                        (import_statement
-                                name: (dotted_name) @dn)
-                        (import_from_statement
-                                module_name: (dotted_name) @dn)
-                        (import_from_statement
-                                module_name: (dotted_name) @dn
-                                    (wildcard_import))
-                        (import_statement(
-                            aliased_import
-                                name: (dotted_name) @dn))
-                        (import_from_statement
-                            module_name: (relative_import) @ri)
-                    ]"
-                }
-                PreparedPythonQuery::DocStrings => {
-                    // Triple-quotes are also used for multi-line strings. So look only
-                    // for stand-alone expressions, which are not part of some variable
-                    // assignment.
-                    formatcp!(
-                        "
-                        (
-                            (expression_statement
-                                (string
-                                    (string_start) @{0}
-                                    (string_content) @string
-                                    (#match? @{0} \"\\^\\\"\\\"\\\"\")
-                                )
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "(comment) @comment",
+            Self::Strings => "(string_content) @string",
+            Self::Imports => {
+                r"[
+                    (import_statement
+                            name: (dotted_name) @dn)
+                    (import_from_statement
+                            module_name: (dotted_name) @dn)
+                    (import_from_statement
+                            module_name: (dotted_name) @dn
+                                (wildcard_import))
+                    (import_statement(
+                        aliased_import
+                            name: (dotted_name) @dn))
+                    (import_from_statement
+                        module_name: (relative_import) @ri)
+                ]"
+            }
+            Self::DocStrings => {
+                // Triple-quotes are also used for multi-line strings, and any string
+                // can be a docstring. So look for structure instead: a stand-alone
+                // string as the first statement of a body, where only comments may
+                // precede it.
+                r"
+                (
+                    [
+                        (module
+                            .
+                            (comment)*
+                            .
+                            (expression_statement (string (string_content) @string))
+                        )
+                        (class_definition
+                            body: (block
+                                .
+                                (comment)*
+                                .
+                                (expression_statement (string (string_content) @string))
                             )
                         )
-                        ",
-                        IGNORE
-                    )
-                }
-                PreparedPythonQuery::FunctionNames => {
-                    r"
-                    (function_definition
-                        name: (identifier) @function-name
-                    )
-                    "
-                }
-                PreparedPythonQuery::FunctionCalls => {
-                    r"
-                    (call
-                        function: (identifier) @function-name
+                        (function_definition
+                            body: (block
+                                .
+                                (comment)*
+                                .
+                                (expression_statement (string (string_content) @string))
+                            )
+                        )
+                    ]
+                )
+                "
+            }
+            Self::FunctionNames => {
+                r"
+                (function_definition
+                    name: (identifier) @function-name
+                )
+                "
+            }
+            Self::FunctionCalls => {
+                r"
+                (call
+                    function: (identifier) @function-name
+                )
+                "
+            }
+            Self::Class => "(class_definition) @class",
+            Self::Def => "(function_definition) @def",
+            Self::AsyncDef => r#"((function_definition) @def (#match? @def "^async "))"#,
+            Self::Methods => {
+                r"
+                (class_definition
+                    body: (block
+                        [
+                            (function_definition) @method
+                            (decorated_definition definition: (function_definition)) @method
+                        ]
                     )
+                )
+                "
+            }
+            Self::ClassMethods => {
+                formatcp!(
                     "
-                }
-                PreparedPythonQuery::Class => "(class_definition) @class",
-                PreparedPythonQuery::Def => "(function_definition) @def",
-                PreparedPythonQuery::AsyncDef => {
-                    r#"((function_definition) @def (#match? @def "^async "))"#
-                }
-                PreparedPythonQuery::Methods => {
-                    r"
                     (class_definition
                         body: (block
-                            [
-                                (function_definition) @method
-                                (decorated_definition definition: (function_definition)) @method
-                            ]
+                            (decorated_definition
+                                (decorator (identifier) @{0})
+                                definition: (function_definition) @method
+                                (#eq? @{0} \"classmethod\")
+                            )
                         )
-                    )
+                    )",
+                    IGNORE
+                )
+            }
+            Self::StaticMethods => {
+                formatcp!(
                     "
-                }
-                PreparedPythonQuery::ClassMethods => {
-                    formatcp!(
-                        "
-                        (class_definition
-                            body: (block
-                                (decorated_definition
-                                    (decorator (identifier) @{0})
-                                    definition: (function_definition) @method
-                                    (#eq? @{0} \"classmethod\")
-                                )
-                            )
-                        )",
-                        IGNORE
-                    )
-                }
-                PreparedPythonQuery::StaticMethods => {
-                    formatcp!(
-                        "
-                        (class_definition
-                            body: (block
-                                (decorated_definition
-                                    (decorator (identifier) @{0})
-                                    definition: (function_definition) @method
-                                    (#eq? @{0} \"staticmethod\")
-                                )
+                    (class_definition
+                        body: (block
+                            (decorated_definition
+                                (decorator (identifier) @{0})
+                                definition: (function_definition) @method
+                                (#eq? @{0} \"staticmethod\")
                             )
-                        )",
-                        IGNORE
-                    )
-                }
-                PreparedPythonQuery::With => "(with_statement) @with",
-                PreparedPythonQuery::Try => "(try_statement) @try",
-                PreparedPythonQuery::Lambda => "(lambda) @lambda",
-                PreparedPythonQuery::Globals => {
-                    "(module (expression_statement (assignment left: (identifier) @global)))"
-                }
-                PreparedPythonQuery::VariableIdentifiers => {
-                    "(assignment left: (identifier) @identifier)"
-                }
-                PreparedPythonQuery::Types => "(type) @type",
-            },
-        )
-        .expect("Prepared queries to be valid")
+                        )
+                    )",
+                    IGNORE
+                )
+            }
+            Self::With => "(with_statement) @with",
+            Self::Try => "(try_statement) @try",
+            Self::Lambda => "(lambda) @lambda",
+            Self::Globals => {
+                "(module (expression_statement (assignment left: (identifier) @global)))"
+            }
+            Self::VariableIdentifiers => "(assignment left: (identifier) @identifier)",
+            Self::Types => "(type) @type",
+        }
+    }
+}
+
+impl From<PreparedPythonQuery> for TSQuery {
+    fn from(value: PreparedPythonQuery) -> Self {
+        Self::new(&Python::lang(), value.as_ref()).expect("Prepared queries to be valid")
     }
 }
 
@@ -189,6 +203,12 @@ impl FromStr for CustomPythonQuery {
     }
 }
 
+impl AsRef<str> for CustomPythonQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
 impl From<CustomPythonQuery> for TSQuery {
     fn from(value: CustomPythonQuery) -> Self {
         Self::new(&Python::lang(), &value.0)
@@ -206,7 +226,11 @@ impl LanguageScoper for Python {
     }
 
     fn neg_query(&self) -> Option<&TSQuery> {
-        self.negative_query.as_ref()
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
     }
 }
 
@@ -215,6 +239,10 @@ impl Find for Python {
         &["py"]
     }
 
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["python"]
+    }
+
     fn interpreters(&self) -> Option<&'static [&'static str]> {
         Some(&["python", "python3"])
     }
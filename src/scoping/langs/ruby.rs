@@ -0,0 +1,137 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use const_format::formatcp;
+use tree_sitter::QueryError;
+
+use super::{tree_sitter_ruby, CodeQuery, Language, LanguageScoper, TSLanguage, TSQuery};
+use crate::find::Find;
+use crate::scoping::langs::IGNORE;
+
+/// The Ruby language.
+pub type Ruby = Language<RubyQuery>;
+/// A query for Ruby.
+pub type RubyQuery = CodeQuery<CustomRubyQuery, PreparedRubyQuery>;
+
+/// Prepared tree-sitter queries for Ruby.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreparedRubyQuery {
+    /// Comments (line and `=begin`/`=end` block).
+    Comments,
+    /// Strings (incl. quotes, `%w()` arrays and heredoc bodies; excl. interpolation).
+    ///
+    /// For heredocs, only the body and its closing identifier are included, not the
+    /// opening `<<~ID`.
+    Strings,
+    /// Symbols, like `:foo`, `:"foo bar"`, `%i(foo)` elements and hash keys (`foo` in
+    /// `foo: 1`).
+    Symbols,
+    /// Method definitions, incl. singleton methods (`def self.x`) (in their
+    /// entirety).
+    MethodDefs,
+    /// `class` definitions (in their entirety).
+    Classes,
+}
+
+impl AsRef<str> for PreparedRubyQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "(comment) @comment",
+            Self::Strings => {
+                formatcp!(
+                    r"
+                        [
+                            (string (interpolation) @{0})
+                            (heredoc_body (interpolation) @{0})
+                            (string)
+                            (heredoc_body)
+                            (string_array)
+                        ]
+                        @string
+                ",
+                    IGNORE
+                )
+            }
+            Self::Symbols => {
+                formatcp!(
+                    r"
+                        [
+                            (delimited_symbol (interpolation) @{0})
+                            (simple_symbol)
+                            (delimited_symbol)
+                            (bare_symbol)
+                            (hash_key_symbol)
+                        ]
+                        @symbol
+                ",
+                    IGNORE
+                )
+            }
+            Self::MethodDefs => "[(method) (singleton_method)] @method",
+            Self::Classes => "(class) @class",
+        }
+    }
+}
+
+impl From<PreparedRubyQuery> for TSQuery {
+    fn from(value: PreparedRubyQuery) -> Self {
+        Self::new(&Ruby::lang(), value.as_ref()).expect("Prepared queries to be valid")
+    }
+}
+
+/// A custom tree-sitter query for Ruby.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomRubyQuery(String);
+
+impl FromStr for CustomRubyQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TSQuery::new(&Ruby::lang(), s) {
+            Ok(_) => Ok(Self(s.to_string())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsRef<str> for CustomRubyQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CustomRubyQuery> for TSQuery {
+    fn from(value: CustomRubyQuery) -> Self {
+        Self::new(&Ruby::lang(), &value.0)
+            .expect("Valid query, as object cannot be constructed otherwise")
+    }
+}
+
+impl LanguageScoper for Ruby {
+    fn lang() -> TSLanguage {
+        tree_sitter_ruby::language()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        &self.positive_query
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Find for Ruby {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rb"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["ruby"]
+    }
+}
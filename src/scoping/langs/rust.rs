@@ -19,6 +19,12 @@ pub enum PreparedRustQuery {
     Comments,
     /// Doc comments (comment chars included).
     DocComments,
+    /// Text of doc comments (line and block styles; comment chars excluded).
+    ///
+    /// Only the text after the `///`, `//!`, `/**` and `/*!` markers is in scope, which
+    /// allows working on the prose without touching syntax. Leading ` * ` on
+    /// continuation lines of block comments are part of the text.
+    DocCommentText,
     /// Use statements (paths only; excl. `use`/`as`/`*`).
     Uses,
     /// Strings (regular, raw, byte; includes interpolation parts in format strings!).
@@ -26,8 +32,14 @@ pub enum PreparedRustQuery {
     /// There is currently no support for an 'interpolation' type node in
     /// tree-sitter-rust (like there is in TypeScript and Python, for example).
     Strings,
-    /// Attributes like `#[attr]`.
+    /// Attributes like `#[attr]` and `#![attr]` (excl. the `#[`/`#![` and `]`).
     Attribute,
+    /// String arguments of attributes, like `#[doc = "..."]` or `#[cfg(feature =
+    /// "...")]` (contents only; nested up to three levels deep).
+    AttributeStrings,
+    /// Argument lists of `#[derive(...)]` attributes (incl. parentheses), like
+    /// `(Debug, Clone)`.
+    Derives,
     /// `struct` definitions.
     Struct,
     /// `struct` definitions not marked `pub`.
@@ -56,6 +68,9 @@ pub enum PreparedRustQuery {
     EnumVariant,
     /// Function definitions.
     Fn,
+    /// Function names, at the definition site (free functions and those in `impl`
+    /// blocks).
+    FnName,
     /// Function definitions inside `impl` blocks (associated functions/methods).
     ImplFn,
     /// Function definitions not marked `pub`.
@@ -102,243 +117,301 @@ pub enum PreparedRustQuery {
     /// `unsafe` keyword usages (`unsafe fn`, `unsafe` blocks, `unsafe Trait`, `unsafe
     /// impl Trait`).
     Unsafe,
+    /// `async` code (`async fn`, `async` blocks, closures returning `async` blocks).
+    Async,
 }
 
-impl From<PreparedRustQuery> for TSQuery {
+impl AsRef<str> for PreparedRustQuery {
     #[allow(clippy::too_many_lines)]
-    fn from(value: PreparedRustQuery) -> Self {
-        Self::new(
-            &Rust::lang(),
-            match value {
-                PreparedRustQuery::Comments => {
-                    r#"
-                    [
-                        (line_comment)+ @line
-                        (block_comment)
-                        (#not-match? @line "^///")
-                    ]
-                    @comment
-                    "#
-                }
-                PreparedRustQuery::DocComments => {
-                    r#"
-                    (
-                        (line_comment)+ @line
-                        (#match? @line "^//(/|!)")
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => {
+                r#"
+                [
+                    (line_comment)+ @line
+                    (block_comment)
+                    (#not-match? @line "^///")
+                ]
+                @comment
+                "#
+            }
+            Self::DocComments => {
+                r#"
+                (
+                    (line_comment)+ @line
+                    (#match? @line "^//(/|!)")
+                )
+                "#
+            }
+            Self::DocCommentText => {
+                r"
+                [
+                    (line_comment doc: (doc_comment) @doc)
+                    (block_comment doc: (doc_comment) @doc)
+                ]
+                "
+            }
+            Self::Uses => {
+                // Match any (wildcard `_`) `argument`, which includes:
+                //
+                // - `scoped_identifier`
+                // - `scoped_use_list`
+                // - `use_wildcard`
+                // - `use_as_clause`
+                //
+                // all at once.
+                r"
+                [
+                    (use_declaration
+                        argument: (_) @use
                     )
-                    "#
-                }
-                PreparedRustQuery::Uses => {
-                    // Match any (wildcard `_`) `argument`, which includes:
-                    //
-                    // - `scoped_identifier`
-                    // - `scoped_use_list`
-                    // - `use_wildcard`
-                    // - `use_as_clause`
-                    //
-                    // all at once.
-                    r"
-                    [
-                        (use_declaration
-                            argument: (_) @use
-                        )
-                    ]
+                ]
+                "
+            }
+            Self::Strings => "(string_content) @string",
+            Self::Attribute => "(attribute) @attribute",
+            Self::AttributeStrings => {
+                // Arguments are unstructured token trees, which may nest. Queries
+                // cannot match descendants at arbitrary depth, so spell out levels.
+                r"
+                [
+                    (attribute value: (string_literal (string_content) @string))
+                    (attribute arguments: (token_tree
+                        (string_literal (string_content) @string)
+                    ))
+                    (attribute arguments: (token_tree (token_tree
+                        (string_literal (string_content) @string)
+                    )))
+                    (attribute arguments: (token_tree (token_tree (token_tree
+                        (string_literal (string_content) @string)
+                    ))))
+                ]
+                "
+            }
+            Self::Derives => {
+                formatcp!(
+                    "(attribute
+                        (identifier) @{0} (#eq? @{0} \"derive\")
+                        arguments: (token_tree) @derive
+                    )",
+                    IGNORE
+                )
+            }
+            Self::Struct => "(struct_item) @struct_item",
+            Self::PrivStruct => {
+                r"(struct_item
+                    .
+                    name: (type_identifier)
+                ) @struct_item_without_visibility_modifier"
+            }
+            Self::PubStruct => {
+                r#"(struct_item
+                    (visibility_modifier) @vis
+                    (#eq? @vis "pub")
+                ) @struct_item"#
+            }
+            Self::PubCrateStruct => {
+                r"(struct_item
+                    (visibility_modifier (crate))
+                ) @struct_item"
+            }
+            Self::PubSelfStruct => {
+                r"(struct_item
+                    (visibility_modifier (self))
+                ) @struct_item"
+            }
+            Self::PubSuperStruct => {
+                r"(struct_item
+                    (visibility_modifier (super))
+                ) @struct_item"
+            }
+            Self::Enum => "(enum_item) @enum_item",
+            Self::PrivEnum => {
+                r"(enum_item
+                    .
+                    name: (type_identifier)
+                ) @enum_item_without_visibility_modifier"
+            }
+            Self::PubEnum => {
+                r#"(enum_item
+                    (visibility_modifier) @vis
+                    (#eq? @vis "pub")
+                ) @enum_item"#
+            }
+            Self::PubCrateEnum => {
+                r"(enum_item
+                    (visibility_modifier (crate))
+                ) @enum_item"
+            }
+            Self::PubSelfEnum => {
+                r"(enum_item
+                    (visibility_modifier (self))
+                ) @enum_item"
+            }
+            Self::PubSuperEnum => {
+                r"(enum_item
+                    (visibility_modifier (super))
+                ) @enum_item"
+            }
+            Self::EnumVariant => "(enum_variant) @enum_variant",
+            Self::Fn => "(function_item) @function_item",
+            Self::FnName => "(function_item name: (identifier) @name)",
+            Self::ImplFn => {
+                r"(impl_item
+                    body: (_ (function_item) @function)
+                )"
+            }
+            Self::PrivFn => {
+                r"(function_item
+                    .
+                    name: (identifier)
+                ) @function_item_without_visibility_modifier"
+            }
+            Self::PubFn => {
+                r#"(function_item
+                    (visibility_modifier) @vis
+                    (#eq? @vis "pub")
+                ) @function_item"#
+            }
+            Self::PubCrateFn => {
+                r"(function_item
+                    (visibility_modifier (crate))
+                ) @function_item"
+            }
+            Self::PubSelfFn => {
+                r"(function_item
+                    (visibility_modifier (self))
+                ) @function_item"
+            }
+            Self::PubSuperFn => {
+                r"(function_item
+                    (visibility_modifier (super))
+                ) @function_item"
+            }
+            Self::ConstFn => {
+                r#"(function_item
+                    (function_modifiers) @funcmods
+                    (#match? @funcmods "const")
+                ) @function_item"#
+            }
+            Self::AsyncFn => {
+                r#"(function_item
+                    (function_modifiers) @funcmods
+                    (#match? @funcmods "async")
+                ) @function_item"#
+            }
+            Self::UnsafeFn => {
+                r#"(function_item
+                    (function_modifiers) @funcmods
+                    (#match? @funcmods "unsafe")
+                ) @function_item"#
+            }
+            Self::ExternFn => {
+                r"(function_item
+                    (function_modifiers (extern_modifier))
+                ) @extern_function"
+            }
+            Self::TestFn => {
+                // Any attribute which matches aka contains `test`, preceded or
+                // followed by more attributes, eventually preceded by a function.
+                // The anchors of `.` ensure nothing but the items we're after occur
+                // in between.
+                formatcp!(
                     "
-                }
-                PreparedRustQuery::Strings => "(string_content) @string",
-                PreparedRustQuery::Attribute => "(attribute) @attribute",
-                PreparedRustQuery::Struct => "(struct_item) @struct_item",
-                PreparedRustQuery::PrivStruct => {
-                    r"(struct_item
+                    (
+                        (attribute_item)*
                         .
-                        name: (type_identifier)
-                    ) @struct_item_without_visibility_modifier"
-                }
-                PreparedRustQuery::PubStruct => {
-                    r#"(struct_item
-                        (visibility_modifier) @vis
-                        (#eq? @vis "pub")
-                    ) @struct_item"#
-                }
-                PreparedRustQuery::PubCrateStruct => {
-                    r"(struct_item
-                        (visibility_modifier (crate))
-                    ) @struct_item"
-                }
-                PreparedRustQuery::PubSelfStruct => {
-                    r"(struct_item
-                        (visibility_modifier (self))
-                    ) @struct_item"
-                }
-                PreparedRustQuery::PubSuperStruct => {
-                    r"(struct_item
-                        (visibility_modifier (super))
-                    ) @struct_item"
-                }
-                PreparedRustQuery::Enum => "(enum_item) @enum_item",
-                PreparedRustQuery::PrivEnum => {
-                    r"(enum_item
+                        (attribute_item (attribute) @{0}.attr (#match? @{0}.attr \"test\"))
                         .
-                        name: (type_identifier)
-                    ) @enum_item_without_visibility_modifier"
-                }
-                PreparedRustQuery::PubEnum => {
-                    r#"(enum_item
-                        (visibility_modifier) @vis
-                        (#eq? @vis "pub")
-                    ) @enum_item"#
-                }
-                PreparedRustQuery::PubCrateEnum => {
-                    r"(enum_item
-                        (visibility_modifier (crate))
-                    ) @enum_item"
-                }
-                PreparedRustQuery::PubSelfEnum => {
-                    r"(enum_item
-                        (visibility_modifier (self))
-                    ) @enum_item"
-                }
-                PreparedRustQuery::PubSuperEnum => {
-                    r"(enum_item
-                        (visibility_modifier (super))
-                    ) @enum_item"
-                }
-                PreparedRustQuery::EnumVariant => "(enum_variant) @enum_variant",
-                PreparedRustQuery::Fn => "(function_item) @function_item",
-                PreparedRustQuery::ImplFn => {
-                    r"(impl_item
-                        body: (_ (function_item) @function)
-                    )"
-                }
-                PreparedRustQuery::PrivFn => {
-                    r"(function_item
+                        (attribute_item)*
                         .
-                        name: (identifier)
-                    ) @function_item_without_visibility_modifier"
-                }
-                PreparedRustQuery::PubFn => {
-                    r#"(function_item
-                        (visibility_modifier) @vis
-                        (#eq? @vis "pub")
-                    ) @function_item"#
-                }
-                PreparedRustQuery::PubCrateFn => {
-                    r"(function_item
-                        (visibility_modifier (crate))
-                    ) @function_item"
-                }
-                PreparedRustQuery::PubSelfFn => {
-                    r"(function_item
-                        (visibility_modifier (self))
-                    ) @function_item"
-                }
-                PreparedRustQuery::PubSuperFn => {
-                    r"(function_item
-                        (visibility_modifier (super))
-                    ) @function_item"
-                }
-                PreparedRustQuery::ConstFn => {
-                    r#"(function_item
-                        (function_modifiers) @funcmods
-                        (#match? @funcmods "const")
-                    ) @function_item"#
-                }
-                PreparedRustQuery::AsyncFn => {
-                    r#"(function_item
-                        (function_modifiers) @funcmods
-                        (#match? @funcmods "async")
-                    ) @function_item"#
-                }
-                PreparedRustQuery::UnsafeFn => {
-                    r#"(function_item
-                        (function_modifiers) @funcmods
-                        (#match? @funcmods "unsafe")
-                    ) @function_item"#
-                }
-                PreparedRustQuery::ExternFn => {
-                    r"(function_item
-                        (function_modifiers (extern_modifier))
-                    ) @extern_function"
-                }
-                PreparedRustQuery::TestFn => {
-                    // Any attribute which matches aka contains `test`, preceded or
-                    // followed by more attributes, eventually preceded by a function.
-                    // The anchors of `.` ensure nothing but the items we're after occur
-                    // in between.
-                    formatcp!(
-                        "
+                        (function_item) @func
+                    )",
+                    IGNORE
+                )
+            }
+            Self::Trait => "(trait_item) @trait_item",
+            Self::Impl => "(impl_item) @impl_item",
+            Self::ImplType => {
+                r"(impl_item
+                    type: (_)
+                    !trait
+                ) @impl_item"
+            }
+            Self::ImplTrait => {
+                r"(impl_item
+                    trait: (_)
+                    .
+                    type: (_)
+                ) @impl_item"
+            }
+            Self::Mod => "(mod_item) @mod_item",
+            Self::ModTests => {
+                r#"(mod_item
+                    name: (identifier) @mod_name
+                    (#eq? @mod_name "tests")
+                ) @mod_tests
+                "#
+            }
+            Self::TypeDef => {
+                r"
+                [
+                    (struct_item)
+                    (enum_item)
+                    (union_item)
+                ]
+                @typedef
+                "
+            }
+            Self::Identifier => "(identifier) @identifier",
+            Self::TypeIdentifier => "(type_identifier) @identifier",
+            Self::Closure => "(closure_expression) @closure",
+            Self::Unsafe => {
+                r#"
+                    [
                         (
-                            (attribute_item)*
-                            .
-                            (attribute_item (attribute) @{0}.attr (#match? @{0}.attr \"test\"))
-                            .
-                            (attribute_item)*
-                            .
-                            (function_item) @func
-                        )",
-                        IGNORE
-                    )
-                }
-                PreparedRustQuery::Trait => "(trait_item) @trait_item",
-                PreparedRustQuery::Impl => "(impl_item) @impl_item",
-                PreparedRustQuery::ImplType => {
-                    r"(impl_item
-                        type: (_)
-                        !trait
-                    ) @impl_item"
-                }
-                PreparedRustQuery::ImplTrait => {
-                    r"(impl_item
-                        trait: (_)
-                        .
-                        type: (_)
-                    ) @impl_item"
-                }
-                PreparedRustQuery::Mod => "(mod_item) @mod_item",
-                PreparedRustQuery::ModTests => {
-                    r#"(mod_item
-                        name: (identifier) @mod_name
-                        (#eq? @mod_name "tests")
-                    ) @mod_tests
-                    "#
-                }
-                PreparedRustQuery::TypeDef => {
-                    r"
+                            (trait_item) @ti (#match? @ti "^unsafe")
+                        )
+                        (
+                            (impl_item) @ii (#match? @ii "^unsafe")
+                        )
+                        (function_item
+                            (function_modifiers) @funcmods
+                            (#match? @funcmods "unsafe")
+                        ) @function_item
+                        (function_signature_item
+                            (function_modifiers) @funcmods
+                            (#match? @funcmods "unsafe")
+                        ) @function_signature_item
+                        (unsafe_block) @block
+                    ] @unsafe
+                "#
+            }
+            Self::Async => {
+                r#"
                     [
-                        (struct_item)
-                        (enum_item)
-                        (union_item)
-                    ]
-                    @typedef
-                    "
-                }
-                PreparedRustQuery::Identifier => "(identifier) @identifier",
-                PreparedRustQuery::TypeIdentifier => "(type_identifier) @identifier",
-                PreparedRustQuery::Closure => "(closure_expression) @closure",
-                PreparedRustQuery::Unsafe => {
-                    r#"
-                        [
-                            (
-                                (trait_item) @ti (#match? @ti "^unsafe")
-                            )
-                            (
-                                (impl_item) @ii (#match? @ii "^unsafe")
-                            )
-                            (function_item
-                                (function_modifiers) @funcmods
-                                (#match? @funcmods "unsafe")
-                            ) @function_item
-                            (function_signature_item
-                                (function_modifiers) @funcmods
-                                (#match? @funcmods "unsafe")
-                            ) @function_signature_item
-                            (unsafe_block) @block
-                        ] @unsafe
-                    "#
-                }
-            },
-        )
-        .expect("Prepared queries to be valid")
+                        (function_item
+                            (function_modifiers) @funcmods
+                            (#match? @funcmods "async")
+                        ) @function_item
+                        (function_signature_item
+                            (function_modifiers) @funcmods
+                            (#match? @funcmods "async")
+                        ) @function_signature_item
+                        (closure_expression
+                            body: (async_block)
+                        ) @closure
+                        (async_block) @block
+                    ] @async
+                "#
+            }
+        }
+    }
+}
+
+impl From<PreparedRustQuery> for TSQuery {
+    fn from(value: PreparedRustQuery) -> Self {
+        Self::new(&Rust::lang(), value.as_ref()).expect("Prepared queries to be valid")
     }
 }
 
@@ -357,6 +430,12 @@ impl FromStr for CustomRustQuery {
     }
 }
 
+impl AsRef<str> for CustomRustQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
 impl From<CustomRustQuery> for TSQuery {
     fn from(value: CustomRustQuery) -> Self {
         Self::new(&Rust::lang(), &value.0)
@@ -374,7 +453,11 @@ impl LanguageScoper for Rust {
     }
 
     fn neg_query(&self) -> Option<&TSQuery> {
-        self.negative_query.as_ref()
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
     }
 }
 
@@ -382,4 +465,8 @@ impl Find for Rust {
     fn extensions(&self) -> &'static [&'static str] {
         &["rs"]
     }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["rust"]
+    }
 }
@@ -0,0 +1,132 @@
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use clap::ValueEnum;
+use tree_sitter::QueryError;
+
+use super::{tree_sitter_sql, CodeQuery, Language, LanguageScoper, TSLanguage, TSQuery};
+use crate::find::Find;
+
+/// The SQL language.
+///
+/// There is no single SQL grammar. The one used is [permissive, covering common
+/// syntax](https://github.com/DerekStride/tree-sitter-sql) of the widespread dialects
+/// (`PostgreSQL`, `MySQL`, `SQLite`, and others like `BigQuery`) rather than any
+/// dialect fully. Dialect-specific statements may not parse, and are then not scoped
+/// reliably. Procedural code, like `PL/pgSQL` function bodies, is a string.
+pub type Sql = Language<SqlQuery>;
+/// A query for SQL.
+pub type SqlQuery = CodeQuery<CustomSqlQuery, PreparedSqlQuery>;
+
+/// Prepared tree-sitter queries for SQL.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreparedSqlQuery {
+    /// Comments (line and block).
+    Comments,
+    /// String literals (incl. quotes), like `'a'`, `E'a'` and `$$a$$`.
+    ///
+    /// Double-quoted text is an identifier in standard SQL, so is never included, even
+    /// where `MySQL` would treat it as a string.
+    Strings,
+    /// Identifiers, like table, column and function names (incl. quotes or backticks).
+    ///
+    /// Qualified names like `schema.table` are separate identifiers.
+    Identifiers,
+    /// Keywords, like `SELECT` and `NULL`, but not function names like `count`.
+    Keywords,
+}
+
+/// A query capturing all keywords of the grammar, as `@keyword`.
+///
+/// The grammar has hundreds of keyword nodes, named `keyword_*`, with no common
+/// supertype. Listing them by hand would be error-prone, so they are taken from the
+/// grammar itself.
+static KEYWORDS_QUERY: LazyLock<String> = LazyLock::new(|| {
+    let lang = Sql::lang();
+    let keywords = (0..u16::try_from(lang.node_kind_count()).unwrap_or(u16::MAX))
+        .filter(|&id| lang.node_kind_is_named(id) && lang.node_kind_is_visible(id))
+        .filter_map(|id| lang.node_kind_for_id(id))
+        .filter(|kind| kind.starts_with("keyword_"))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|kind| format!("({kind})"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!("[{keywords}] @keyword")
+});
+
+impl AsRef<str> for PreparedSqlQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "[(comment) (marginalia)] @comment",
+            Self::Strings => r#"((literal) @string (#match? @string "^(([uU]&|[eE])?'|\\$)"))"#,
+            Self::Identifiers => "(identifier) @identifier",
+            Self::Keywords => &KEYWORDS_QUERY,
+        }
+    }
+}
+
+impl From<PreparedSqlQuery> for TSQuery {
+    fn from(value: PreparedSqlQuery) -> Self {
+        Self::new(&Sql::lang(), value.as_ref()).expect("Prepared queries to be valid")
+    }
+}
+
+/// A custom tree-sitter query for SQL.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomSqlQuery(String);
+
+impl FromStr for CustomSqlQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TSQuery::new(&Sql::lang(), s) {
+            Ok(_) => Ok(Self(s.to_string())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsRef<str> for CustomSqlQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CustomSqlQuery> for TSQuery {
+    fn from(value: CustomSqlQuery) -> Self {
+        Self::new(&Sql::lang(), &value.0)
+            .expect("Valid query, as object cannot be constructed otherwise")
+    }
+}
+
+impl LanguageScoper for Sql {
+    fn lang() -> TSLanguage {
+        tree_sitter_sql::language()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        &self.positive_query
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Find for Sql {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["sql"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["sql"]
+    }
+}
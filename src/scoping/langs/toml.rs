@@ -0,0 +1,107 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use tree_sitter::QueryError;
+
+use super::{tree_sitter_toml, CodeQuery, Language, LanguageScoper, TSLanguage, TSQuery};
+use crate::find::Find;
+
+/// The TOML language.
+pub type Toml = Language<TomlQuery>;
+/// A query for TOML.
+pub type TomlQuery = CodeQuery<CustomTomlQuery, PreparedTomlQuery>;
+
+/// Prepared tree-sitter queries for TOML.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreparedTomlQuery {
+    /// Comments.
+    Comments,
+    /// Keys of key/value pairs, incl. quotes; dotted keys (`a.b`) as a whole.
+    Keys,
+    /// String values (incl. quotes), not quoted keys.
+    Strings,
+    /// Keys of table (`[a.b]`) and array of tables (`[[a.b]]`) headers, excl.
+    /// brackets; dotted keys as a whole.
+    TableHeaders,
+}
+
+impl AsRef<str> for PreparedTomlQuery {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Comments => "(comment) @comment",
+            Self::Keys => "(pair [(bare_key) (quoted_key) (dotted_key)] @key)",
+            Self::Strings => "(string) @string",
+            Self::TableHeaders => {
+                r"
+                [
+                    (table [(bare_key) (quoted_key) (dotted_key)] @header)
+                    (table_array_element [(bare_key) (quoted_key) (dotted_key)] @header)
+                ]
+                "
+            }
+        }
+    }
+}
+
+impl From<PreparedTomlQuery> for TSQuery {
+    fn from(value: PreparedTomlQuery) -> Self {
+        Self::new(&Toml::lang(), value.as_ref()).expect("Prepared queries to be valid")
+    }
+}
+
+/// A custom tree-sitter query for TOML.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomTomlQuery(String);
+
+impl FromStr for CustomTomlQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TSQuery::new(&Toml::lang(), s) {
+            Ok(_) => Ok(Self(s.to_string())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsRef<str> for CustomTomlQuery {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CustomTomlQuery> for TSQuery {
+    fn from(value: CustomTomlQuery) -> Self {
+        Self::new(&Toml::lang(), &value.0)
+            .expect("Valid query, as object cannot be constructed otherwise")
+    }
+}
+
+impl LanguageScoper for Toml {
+    fn lang() -> TSLanguage {
+        tree_sitter_toml::language()
+    }
+
+    fn pos_query(&self) -> &TSQuery {
+        &self.positive_query
+    }
+
+    fn neg_query(&self) -> Option<&TSQuery> {
+        self.negative_query.as_deref()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Find for Toml {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["toml"]
+    }
+
+    fn modeline_names(&self) -> &'static [&'static str] {
+        &["toml"]
+    }
+}
@@ -0,0 +1,16 @@
+//! Slimmed down version of `bindings/rust/lib.rs` of the `tree-sitter-css` crate
+//! (version 0.23.2), whose generated parser is vendored in this directory.
+//!
+//! **Remove this module once a release of `tree-sitter-css` compatible with our
+//! `tree-sitter` version is available on <https://crates.io>**.
+
+extern "C" {
+    fn tree_sitter_css() -> tree_sitter::Language;
+}
+
+pub fn language() -> tree_sitter::Language {
+    #[allow(unsafe_code)]
+    unsafe {
+        tree_sitter_css()
+    }
+}
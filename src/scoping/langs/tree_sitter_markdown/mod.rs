@@ -0,0 +1,27 @@
+//! Slimmed down version of `bindings/rust/lib.rs` of the `tree-sitter-md` crate
+//! (version 0.3.2), whose generated parsers are vendored in this directory.
+//!
+//! **Remove this module once a release of `tree-sitter-md` compatible with our
+//! `tree-sitter` version is available on <https://crates.io>**.
+
+extern "C" {
+    fn tree_sitter_markdown() -> tree_sitter::Language;
+    fn tree_sitter_markdown_inline() -> tree_sitter::Language;
+}
+
+/// The grammar of the block structure of Markdown documents.
+pub fn language() -> tree_sitter::Language {
+    #[allow(unsafe_code)]
+    unsafe {
+        tree_sitter_markdown()
+    }
+}
+
+/// The grammar of the inline structure of Markdown documents, such as within
+/// paragraphs.
+pub fn inline_language() -> tree_sitter::Language {
+    #[allow(unsafe_code)]
+    unsafe {
+        tree_sitter_markdown_inline()
+    }
+}
@@ -0,0 +1,16 @@
+//! Slimmed down version of `bindings/rust/lib.rs` of the `tree-sitter-ruby` crate
+//! (version 0.23.1), whose generated parser is vendored in this directory.
+//!
+//! **Remove this module once a release of `tree-sitter-ruby` compatible with our
+//! `tree-sitter` version is available on <https://crates.io>**.
+
+extern "C" {
+    fn tree_sitter_ruby() -> tree_sitter::Language;
+}
+
+pub fn language() -> tree_sitter::Language {
+    #[allow(unsafe_code)]
+    unsafe {
+        tree_sitter_ruby()
+    }
+}